@@ -0,0 +1,66 @@
+mod direction;
+mod hex;
+mod spiral;
+
+use glam::IVec2;
+
+pub use direction::{Direction, ParseDirectionError};
+pub use hex::{FlatHexDirection, Hex, PointyHexDirection};
+pub use spiral::{spiral_index, spiral_position};
+
+/// Sum of absolute differences of the coordinates
+pub fn manhattan(a: IVec2, b: IVec2) -> u32 {
+    a.x.abs_diff(b.x) + a.y.abs_diff(b.y)
+}
+
+/// Maximum of absolute differences of the coordinates.
+/// Number of king moves between `a` and `b`.
+pub fn chebyshev(a: IVec2, b: IVec2) -> u32 {
+    a.x.abs_diff(b.x).max(a.y.abs_diff(b.y))
+}
+
+/// Rotates the vector by 90 degrees clockwise.
+///
+/// Y axis is pointing down (as in `Grid`), so `IVec2::X` becomes `IVec2::Y`.
+pub const fn rotate_cw(v: IVec2) -> IVec2 {
+    IVec2::new(-v.y, v.x)
+}
+
+/// Rotates the vector by 90 degrees counter-clockwise.
+///
+/// Y axis is pointing down (as in `Grid`), so `IVec2::X` becomes `IVec2::NEG_Y`.
+pub const fn rotate_ccw(v: IVec2) -> IVec2 {
+    IVec2::new(v.y, -v.x)
+}
+
+pub const fn rotate_180(v: IVec2) -> IVec2 {
+    IVec2::new(-v.x, -v.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use super::{chebyshev, manhattan, rotate_180, rotate_ccw, rotate_cw};
+
+    #[test]
+    fn test_distances() {
+        let a = IVec2::new(-2, 3);
+        let b = IVec2::new(4, -1);
+        assert_eq!(manhattan(a, b), 10);
+        assert_eq!(chebyshev(a, b), 6);
+        assert_eq!(manhattan(a, a), 0);
+        assert_eq!(chebyshev(b, a), 6);
+    }
+
+    #[test]
+    fn test_rotations() {
+        assert_eq!(rotate_cw(IVec2::X), IVec2::Y);
+        assert_eq!(rotate_cw(IVec2::Y), IVec2::NEG_X);
+        assert_eq!(rotate_ccw(IVec2::X), IVec2::NEG_Y);
+        assert_eq!(rotate_180(IVec2::new(2, -3)), IVec2::new(-2, 3));
+        let v = IVec2::new(3, 7);
+        assert_eq!(rotate_ccw(rotate_cw(v)), v);
+        assert_eq!(rotate_cw(rotate_cw(v)), rotate_180(v));
+    }
+}
@@ -0,0 +1,188 @@
+use std::str::FromStr;
+
+use glam::IVec2;
+use thiserror::Error;
+
+/// One of four non-diagonal directions.
+///
+/// Y axis is pointing down (as in `Grid`), so `Up` is `IVec2::NEG_Y`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("unknown direction: {0:?}")]
+pub struct ParseDirectionError(pub String);
+
+impl Direction {
+    /// In the same order as `NonDiagonal::directions()`
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub const fn to_ivec2(self) -> IVec2 {
+        match self {
+            Direction::Up => IVec2::NEG_Y,
+            Direction::Right => IVec2::X,
+            Direction::Down => IVec2::Y,
+            Direction::Left => IVec2::NEG_X,
+        }
+    }
+
+    pub fn from_ivec2(dir: IVec2) -> Option<Self> {
+        match (dir.x, dir.y) {
+            (0, -1) => Some(Direction::Up),
+            (1, 0) => Some(Direction::Right),
+            (0, 1) => Some(Direction::Down),
+            (-1, 0) => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub const fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub const fn turn_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    pub const fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+        }
+    }
+
+    pub const fn is_horizontal(self) -> bool {
+        matches!(self, Direction::Right | Direction::Left)
+    }
+
+    pub const fn is_vertical(self) -> bool {
+        !self.is_horizontal()
+    }
+
+    /// Index in [`Direction::ALL`]
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// One of `^>v<`
+    pub const fn to_ascii_char(self) -> u8 {
+        match self {
+            Direction::Up => b'^',
+            Direction::Right => b'>',
+            Direction::Down => b'v',
+            Direction::Left => b'<',
+        }
+    }
+
+    /// Accepts arrows (`^>v<`), `URDL` and compass `NESW` letters in any case.
+    pub const fn from_ascii_char(c: u8) -> Option<Self> {
+        match c {
+            b'^' | b'U' | b'u' | b'N' | b'n' => Some(Direction::Up),
+            b'>' | b'R' | b'r' | b'E' | b'e' => Some(Direction::Right),
+            b'v' | b'D' | b'd' | b'S' | b's' => Some(Direction::Down),
+            b'<' | b'L' | b'l' | b'W' | b'w' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+}
+
+impl From<Direction> for IVec2 {
+    fn from(dir: Direction) -> Self {
+        dir.to_ivec2()
+    }
+}
+
+impl TryFrom<u8> for Direction {
+    type Error = ParseDirectionError;
+
+    fn try_from(c: u8) -> Result<Self, Self::Error> {
+        Self::from_ascii_char(c).ok_or_else(|| ParseDirectionError(char::from(c).to_string()))
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = ParseDirectionError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        u8::try_from(c)
+            .ok()
+            .and_then(Self::from_ascii_char)
+            .ok_or_else(|| ParseDirectionError(c.to_string()))
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().as_bytes() {
+            [c] => Self::from_ascii_char(*c),
+            _ => None,
+        }
+        .ok_or_else(|| ParseDirectionError(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+    use itertools::Itertools;
+
+    use super::Direction;
+    use crate::grid::NonDiagonal;
+
+    #[test]
+    fn test_parse() {
+        let arrows = "^>v<".bytes().map(Direction::try_from).collect_vec();
+        let letters = "URDL".bytes().map(Direction::try_from).collect_vec();
+        let compass = "NESW".bytes().map(Direction::try_from).collect_vec();
+        let expected = Direction::ALL.map(Ok).to_vec();
+        assert_eq!(arrows, expected);
+        assert_eq!(letters, expected);
+        assert_eq!(compass, expected);
+        assert_eq!("d".parse::<Direction>(), Ok(Direction::Down));
+        assert!("x".parse::<Direction>().is_err());
+        assert!("UR".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_vectors() {
+        assert_eq!(
+            Direction::ALL.map(Direction::to_ivec2),
+            NonDiagonal::directions()
+        );
+        for d in Direction::ALL {
+            assert_eq!(Direction::from_ivec2(d.to_ivec2()), Some(d));
+            assert_eq!(
+                d.turn_right().to_ivec2(),
+                crate::coords::rotate_cw(d.to_ivec2())
+            );
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.opposite().to_ivec2(), -d.to_ivec2());
+            assert_eq!(Direction::try_from(d.to_ascii_char()), Ok(d));
+        }
+        assert_eq!(Direction::from_ivec2(IVec2::ONE), None);
+    }
+}
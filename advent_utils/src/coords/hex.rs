@@ -0,0 +1,349 @@
+use std::{
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use glam::{IVec2, IVec3};
+
+use super::ParseDirectionError;
+
+/// Hexagon in axial coordinates.
+///
+/// The third cube coordinate is `s = -q - r`.
+/// See <https://www.redblobgames.com/grids/hexagons/>
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+/// Axial offsets of the neighbours, going around counter-clockwise
+/// starting from `q + 1`.
+const AXIAL_DIRECTIONS: [Hex; 6] = [
+    Hex::new(1, 0),
+    Hex::new(1, -1),
+    Hex::new(0, -1),
+    Hex::new(-1, 0),
+    Hex::new(-1, 1),
+    Hex::new(0, 1),
+];
+
+impl Hex {
+    pub const ZERO: Self = Self::new(0, 0);
+
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    /// Returns `None` if `x + y + z != 0`
+    pub fn from_cube(cube: IVec3) -> Option<Self> {
+        (cube.element_sum() == 0).then_some(Self::new(cube.x, cube.y))
+    }
+
+    pub const fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    pub const fn cube(self) -> IVec3 {
+        IVec3::new(self.q, self.r, self.s())
+    }
+
+    pub const fn axial(self) -> IVec2 {
+        IVec2::new(self.q, self.r)
+    }
+
+    /// All six offsets to the neighbours
+    pub const fn directions() -> [Hex; 6] {
+        AXIAL_DIRECTIONS
+    }
+
+    pub fn neighbours(self) -> [Hex; 6] {
+        AXIAL_DIRECTIONS.map(|d| self + d)
+    }
+
+    /// Number of steps between two hexagons
+    pub fn distance(self, other: Hex) -> u32 {
+        let d = (self - other).cube().abs();
+        d.max_element() as u32
+    }
+
+    /// Distance from the origin
+    pub fn length(self) -> u32 {
+        self.distance(Hex::ZERO)
+    }
+
+    /// Hexagons that are exactly `radius` steps away from `self`
+    pub fn ring(self, radius: u32) -> impl Iterator<Item = Hex> {
+        let start = self + AXIAL_DIRECTIONS[4] * radius as i32;
+        (0..6usize)
+            .flat_map(move |side| std::iter::repeat_n(AXIAL_DIRECTIONS[side], radius as usize))
+            .scan(start, |pos, d| {
+                let res = *pos;
+                *pos += d;
+                Some(res)
+            })
+            .chain((radius == 0).then_some(start))
+    }
+
+    /// Hexagons that are at most `radius` steps away from `self`,
+    /// ring by ring starting from `self`
+    pub fn within(self, radius: u32) -> impl Iterator<Item = Hex> {
+        (0..=radius).flat_map(move |r| self.ring(r))
+    }
+
+    /// Hexagons on the straight line between `self` and `other` (both inclusive)
+    pub fn line_to(self, other: Hex) -> Vec<Hex> {
+        let n = self.distance(other);
+        if n == 0 {
+            return vec![self];
+        }
+        // Nudging avoids landing exactly on the edge between two hexagons
+        let a = self.cube().as_dvec3() + glam::DVec3::new(1e-6, 2e-6, -3e-6);
+        let b = other.cube().as_dvec3() + glam::DVec3::new(1e-6, 2e-6, -3e-6);
+        (0..=n)
+            .map(|i| cube_round(a.lerp(b, i as f64 / n as f64)))
+            .collect()
+    }
+
+    pub fn rotate_cw(self) -> Self {
+        Self::new(-self.r, -self.s())
+    }
+
+    pub fn rotate_ccw(self) -> Self {
+        Self::new(-self.s(), -self.q)
+    }
+}
+
+fn cube_round(c: glam::DVec3) -> Hex {
+    let mut rounded = c.round();
+    let diff = (rounded - c).abs();
+    if diff.x > diff.y && diff.x > diff.z {
+        rounded.x = -rounded.y - rounded.z;
+    } else if diff.y > diff.z {
+        rounded.y = -rounded.x - rounded.z;
+    }
+    Hex::new(rounded.x as i32, rounded.y as i32)
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl SubAssign for Hex {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Hex::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Self::Output {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+macro_rules! hex_directions {
+    ($name:ident, $doc:literal, $(($variant:ident, $s:literal, $q:expr, $r:expr)),+) => {
+        #[doc = $doc]
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum $name {
+            $($variant),+
+        }
+
+        impl $name {
+            pub const ALL: [$name; 6] = [$($name::$variant),+];
+
+            pub const fn to_hex(self) -> Hex {
+                match self {
+                    $($name::$variant => Hex::new($q, $r)),+
+                }
+            }
+
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    $($name::$variant => $s),+
+                }
+            }
+
+            /// Parses the direction at the start of `s`
+            /// and returns it with the rest of the string.
+            ///
+            /// Useful for inputs like `esenee`.
+            pub fn split_prefix(s: &str) -> Option<(Self, &str)> {
+                // Two letter directions are tried first, so `se` wins over `s`
+                let mut all = Self::ALL;
+                all.sort_by_key(|d| std::cmp::Reverse(d.as_str().len()));
+                all.into_iter().find_map(|d| {
+                    let prefix = s.get(..d.as_str().len())?;
+                    prefix
+                        .eq_ignore_ascii_case(d.as_str())
+                        .then(|| (d, &s[d.as_str().len()..]))
+                })
+            }
+        }
+
+        impl From<$name> for Hex {
+            fn from(dir: $name) -> Hex {
+                dir.to_hex()
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseDirectionError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let trimmed = s.trim();
+                Self::ALL
+                    .into_iter()
+                    .find(|d| d.as_str().eq_ignore_ascii_case(trimmed))
+                    .ok_or_else(|| ParseDirectionError(s.to_string()))
+            }
+        }
+    };
+}
+
+hex_directions!(
+    FlatHexDirection,
+    "Directions of a grid of flat-topped hexagons (`n`, `ne`, `se`, `s`, `sw`, `nw`)",
+    (N, "n", 0, -1),
+    (NE, "ne", 1, -1),
+    (SE, "se", 1, 0),
+    (S, "s", 0, 1),
+    (SW, "sw", -1, 1),
+    (NW, "nw", -1, 0)
+);
+
+hex_directions!(
+    PointyHexDirection,
+    "Directions of a grid of pointy-topped hexagons (`e`, `se`, `sw`, `w`, `nw`, `ne`)",
+    (E, "e", 1, 0),
+    (SE, "se", 0, 1),
+    (SW, "sw", -1, 1),
+    (W, "w", -1, 0),
+    (NW, "nw", 0, -1),
+    (NE, "ne", 1, -1)
+);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use itertools::Itertools;
+
+    use super::{FlatHexDirection, Hex, PointyHexDirection};
+
+    #[test]
+    fn test_distance() {
+        // AoC 2017 day 11 examples
+        let walk = |s: &str| {
+            s.split(',')
+                .map(|d| d.parse::<FlatHexDirection>().unwrap().to_hex())
+                .fold(Hex::ZERO, |a, b| a + b)
+                .length()
+        };
+        assert_eq!(walk("ne,ne,ne"), 3);
+        assert_eq!(walk("ne,ne,sw,sw"), 0);
+        assert_eq!(walk("ne,ne,s,s"), 2);
+        assert_eq!(walk("se,sw,se,sw,sw"), 3);
+    }
+
+    #[test]
+    fn test_split_prefix() {
+        let mut rest = "esenee";
+        let mut dirs = vec![];
+        while let Some((d, r)) = PointyHexDirection::split_prefix(rest) {
+            dirs.push(d);
+            rest = r;
+        }
+        assert_eq!(rest, "");
+        assert_eq!(
+            dirs,
+            vec![
+                PointyHexDirection::E,
+                PointyHexDirection::SE,
+                PointyHexDirection::NE,
+                PointyHexDirection::E
+            ]
+        );
+        assert_eq!(PointyHexDirection::split_prefix("x"), None);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let center = Hex::new(2, -5);
+        for n in center.neighbours() {
+            assert_eq!(center.distance(n), 1);
+        }
+        assert_eq!(center.neighbours().into_iter().unique().count(), 6);
+        assert_eq!(Hex::from_cube(center.cube()), Some(center));
+        assert_eq!(Hex::from_cube(glam::IVec3::ONE), None);
+    }
+
+    #[test]
+    fn test_rings() {
+        let center = Hex::new(-1, 3);
+        assert_eq!(center.ring(0).collect_vec(), vec![center]);
+        for radius in 1..6 {
+            let ring = center.ring(radius).collect_vec();
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert!(ring.iter().all(|h| h.distance(center) == radius));
+            assert_eq!(ring.iter().unique().count(), ring.len());
+        }
+        let area: HashSet<Hex> = center.within(3).collect();
+        assert_eq!(area.len(), 1 + 6 + 12 + 18);
+    }
+
+    #[test]
+    fn test_line_to() {
+        let a = Hex::new(0, 0);
+        let b = Hex::new(4, -7);
+        let line = a.line_to(b);
+        assert_eq!(line.len() as u32, a.distance(b) + 1);
+        assert_eq!(line.first(), Some(&a));
+        assert_eq!(line.last(), Some(&b));
+        assert!(line
+            .iter()
+            .tuple_windows()
+            .all(|(x, y)| x.distance(*y) == 1));
+        assert_eq!(a.line_to(a), vec![a]);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let h = Hex::new(3, -1);
+        assert_eq!(h.rotate_cw().length(), h.length());
+        assert_eq!(h.rotate_cw().rotate_ccw(), h);
+        let mut x = h;
+        for _ in 0..6 {
+            x = x.rotate_cw();
+        }
+        assert_eq!(x, h);
+    }
+}
@@ -0,0 +1,101 @@
+use glam::IVec2;
+
+/// Returns the position of the cell with the given index of the square spiral:
+///
+/// ```ignore
+/// 16 15 14 13 12
+/// 17  4  3  2 11
+/// 18  5  0  1 10
+/// 19  6  7  8  9
+/// 20 21 22 23 24 ...
+/// ```
+///
+/// Index `0` is at `IVec2::ZERO`, index `1` is at `IVec2::X`,
+/// the spiral goes counter-clockwise (Y axis is pointing down).
+pub fn spiral_position(index: u64) -> IVec2 {
+    if index == 0 {
+        return IVec2::ZERO;
+    }
+    let ring = index.isqrt().div_ceil(2);
+    let side = 2 * ring;
+    let offset = index - (side - 1) * (side - 1);
+    let ring = ring as i32;
+    let along = (offset % side) as i32;
+    match offset / side {
+        0 => IVec2::new(ring, ring - 1 - along),
+        1 => IVec2::new(ring - 1 - along, -ring),
+        2 => IVec2::new(-ring, -ring + 1 + along),
+        _ => IVec2::new(-ring + 1 + along, ring),
+    }
+}
+
+/// Inverse of [`spiral_position`]
+pub fn spiral_index(pos: IVec2) -> u64 {
+    let ring = pos.x.unsigned_abs().max(pos.y.unsigned_abs()) as u64;
+    if ring == 0 {
+        return 0;
+    }
+    let side = 2 * ring;
+    let start = (side - 1) * (side - 1);
+    let r = ring as i64;
+    let (x, y) = (pos.x as i64, pos.y as i64);
+    let offset = if x == r && y < r {
+        r - 1 - y
+    } else if y == -r && x < r {
+        2 * r + (r - 1 - x)
+    } else if x == -r {
+        4 * r + (y + r - 1)
+    } else {
+        6 * r + (x + r - 1)
+    };
+    start + offset as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use super::{spiral_index, spiral_position};
+
+    #[test]
+    fn test_spiral_position() {
+        let expected = [
+            (0, 0),
+            (1, 0),
+            (1, -1),
+            (0, -1),
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+            (2, 1),
+            (2, 0),
+            (2, -1),
+            (2, -2),
+            (1, -2),
+        ];
+        for (i, (x, y)) in expected.into_iter().enumerate() {
+            assert_eq!(spiral_position(i as u64), IVec2::new(x, y), "index {i}");
+        }
+    }
+
+    #[test]
+    fn test_spiral_roundtrip() {
+        let mut prev = spiral_position(0);
+        for i in 0..10_000 {
+            let p = spiral_position(i);
+            assert_eq!(spiral_index(p), i);
+            assert_eq!((p - prev).abs().element_sum(), if i == 0 { 0 } else { 1 });
+            prev = p;
+        }
+    }
+
+    #[test]
+    fn test_spiral_far() {
+        // 1024th square of AoC 2017 day 3 is 31 steps away
+        assert_eq!(spiral_position(1023).abs().element_sum(), 31);
+        let p = spiral_position(10_000_000_000);
+        assert_eq!(spiral_index(p), 10_000_000_000);
+    }
+}
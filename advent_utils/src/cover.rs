@@ -0,0 +1,381 @@
+//! Exact and partial cover with Dancing Links.
+//!
+//! Every column has a range of how many chosen rows may contain it: primary
+//! columns must be covered exactly once, secondary columns at most once, and
+//! any other range gives a column with multiplicity. Rows are only chosen to
+//! satisfy columns with a positive minimum, so a row made of columns that may
+//! stay uncovered is never part of a solution.
+use std::ops::{ControlFlow, RangeInclusive};
+
+#[derive(Debug, Clone, Default)]
+pub struct ExactCover {
+    columns: Vec<(usize, usize)>,
+    rows: Vec<Vec<usize>>,
+}
+
+impl ExactCover {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Column that must be covered exactly once
+    pub fn add_primary(&mut self) -> usize {
+        self.add_column(1..=1)
+    }
+
+    /// Column that may be covered at most once
+    pub fn add_secondary(&mut self) -> usize {
+        self.add_column(0..=1)
+    }
+
+    /// Column contained in a number of chosen rows within the range
+    ///
+    /// # Panics
+    /// If the range is empty or allows only zero rows
+    pub fn add_column(&mut self, times: RangeInclusive<usize>) -> usize {
+        let (min, max) = times.into_inner();
+        assert!(min <= max && max > 0, "column must allow at least one row");
+        self.columns.push((min, max));
+        self.columns.len() - 1
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// # Panics
+    /// If a column is unknown or repeated
+    pub fn add_row(&mut self, columns: impl IntoIterator<Item = usize>) -> usize {
+        let row = columns.into_iter().collect::<Vec<_>>();
+        for (i, column) in row.iter().enumerate() {
+            assert!(*column < self.columns.len(), "unknown column {column}");
+            assert!(!row[..i].contains(column), "repeated column {column}");
+        }
+        self.rows.push(row);
+        self.rows.len() - 1
+    }
+
+    /// Sorted indices of the rows of any solution
+    pub fn solve(&self) -> Option<Vec<usize>> {
+        let mut found = None;
+        self.for_each_solution(|rows| {
+            let mut rows = rows.to_vec();
+            rows.sort_unstable();
+            found = Some(rows);
+            ControlFlow::Break(())
+        });
+        found
+    }
+
+    pub fn count_solutions(&self) -> usize {
+        let mut count = 0;
+        self.for_each_solution(|_| {
+            count += 1;
+            ControlFlow::Continue(())
+        });
+        count
+    }
+
+    /// Calls `f` with the rows of every solution once, in the order they were chosen
+    pub fn for_each_solution(&self, mut f: impl FnMut(&[usize]) -> ControlFlow<()>) {
+        let mut links = Links::new(self);
+        let _ = links.search(&mut Vec::new(), &mut f);
+    }
+}
+
+/// Toroidal lists of the rows, nodes `0..columns` are the column headers
+struct Links {
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    row_of: Vec<usize>,
+    row_start: Vec<usize>,
+    len: Vec<usize>,
+    count: Vec<usize>,
+    bounds: Vec<(usize, usize)>,
+    /// Columns still to be decided, linked through `root`
+    prev: Vec<usize>,
+    next: Vec<usize>,
+    root: usize,
+}
+
+impl Links {
+    fn new(problem: &ExactCover) -> Self {
+        let columns = problem.columns.len();
+        let mut links = Links {
+            up: (0..columns).collect(),
+            down: (0..columns).collect(),
+            column: (0..columns).collect(),
+            row_of: vec![usize::MAX; columns],
+            row_start: Vec::with_capacity(problem.rows.len() + 1),
+            len: vec![0; columns],
+            count: vec![0; columns],
+            bounds: problem.columns.clone(),
+            prev: vec![columns; columns + 1],
+            next: vec![columns; columns + 1],
+            root: columns,
+        };
+        for (r, row) in problem.rows.iter().enumerate() {
+            links.row_start.push(links.up.len());
+            for c in row.iter().copied() {
+                let node = links.up.len();
+                let last = links.up[c];
+                links.up.push(last);
+                links.down.push(c);
+                links.down[last] = node;
+                links.up[c] = node;
+                links.column.push(c);
+                links.row_of.push(r);
+                links.len[c] += 1;
+            }
+        }
+        links.row_start.push(links.up.len());
+        for c in (0..columns).filter(|c| links.bounds[*c].0 > 0) {
+            let last = links.prev[links.root];
+            links.prev[c] = last;
+            links.next[c] = links.root;
+            links.next[last] = c;
+            links.prev[links.root] = c;
+        }
+        links
+    }
+
+    fn nodes(&self, row: usize) -> std::ops::Range<usize> {
+        self.row_start[row]..self.row_start[row + 1]
+    }
+
+    fn unlink(&mut self, node: usize) {
+        let (up, down) = (self.up[node], self.down[node]);
+        self.down[up] = down;
+        self.up[down] = up;
+        self.len[self.column[node]] -= 1;
+    }
+
+    fn relink(&mut self, node: usize) {
+        let (up, down) = (self.up[node], self.down[node]);
+        self.down[up] = node;
+        self.up[down] = node;
+        self.len[self.column[node]] += 1;
+    }
+
+    fn deactivate(&mut self, c: usize) {
+        let (prev, next) = (self.prev[c], self.next[c]);
+        self.next[prev] = next;
+        self.prev[next] = prev;
+    }
+
+    fn activate(&mut self, c: usize) {
+        let (prev, next) = (self.prev[c], self.next[c]);
+        self.next[prev] = c;
+        self.prev[next] = c;
+    }
+
+    fn hide_row(&mut self, row: usize) {
+        for node in self.nodes(row) {
+            self.unlink(node);
+        }
+    }
+
+    fn unhide_row(&mut self, row: usize) {
+        for node in self.nodes(row).rev() {
+            self.relink(node);
+        }
+    }
+
+    /// Removes the column with every row still containing it
+    fn cover(&mut self, c: usize) {
+        if self.bounds[c].0 > 0 {
+            self.deactivate(c);
+        }
+        let mut i = self.down[c];
+        while i != c {
+            for node in self.nodes(self.row_of[i]).filter(|node| *node != i) {
+                self.unlink(node);
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            for node in self.nodes(self.row_of[i]).rev().filter(|node| *node != i) {
+                self.relink(node);
+            }
+            i = self.up[i];
+        }
+        if self.bounds[c].0 > 0 {
+            self.activate(c);
+        }
+    }
+
+    fn select(&mut self, row: usize) {
+        self.hide_row(row);
+        for node in self.nodes(row) {
+            let c = self.column[node];
+            self.count[c] += 1;
+            if self.count[c] == self.bounds[c].1 {
+                self.cover(c);
+            }
+        }
+    }
+
+    fn deselect(&mut self, row: usize) {
+        for node in self.nodes(row).rev() {
+            let c = self.column[node];
+            if self.count[c] == self.bounds[c].1 {
+                self.uncover(c);
+            }
+            self.count[c] -= 1;
+        }
+        self.unhide_row(row);
+    }
+
+    /// Column with the fewest ways to continue, `None` if some column can no longer be satisfied
+    fn choose(&self) -> Option<usize> {
+        let mut best = (usize::MAX, self.root);
+        let mut c = self.next[self.root];
+        while c != self.root {
+            let need = self.bounds[c].0.saturating_sub(self.count[c]);
+            if self.len[c] < need {
+                return None;
+            }
+            // Leaving the column as it is counts as one more way
+            let ways = self.len[c] + usize::from(need == 0);
+            if ways < best.0 {
+                best = (ways, c);
+            }
+            c = self.next[c];
+        }
+        Some(best.1)
+    }
+
+    /// Branches on the first remaining row of a column, rows tried before are
+    /// excluded so every set of rows is found once
+    fn search(
+        &mut self,
+        chosen: &mut Vec<usize>,
+        f: &mut impl FnMut(&[usize]) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
+        if self.next[self.root] == self.root {
+            return f(chosen);
+        }
+        let Some(c) = self.choose() else {
+            return ControlFlow::Continue(());
+        };
+        let need = self.bounds[c].0.saturating_sub(self.count[c]);
+        let mut excluded = Vec::new();
+        let mut flow = ControlFlow::Continue(());
+        while self.down[c] != c && self.len[c] >= need {
+            let row = self.row_of[self.down[c]];
+            self.select(row);
+            chosen.push(row);
+            flow = self.search(chosen, f);
+            chosen.pop();
+            self.deselect(row);
+            if flow.is_break() {
+                break;
+            }
+            self.hide_row(row);
+            excluded.push(row);
+        }
+        if flow.is_continue() && need == 0 {
+            self.deactivate(c);
+            flow = self.search(chosen, f);
+            self.activate(c);
+        }
+        for row in excluded.into_iter().rev() {
+            self.unhide_row(row);
+        }
+        flow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExactCover;
+
+    #[test]
+    fn test_exact() {
+        // Knuth's example, the only cover is rows 0, 3 and 4
+        let mut problem = ExactCover::new();
+        for _ in 0..7 {
+            problem.add_primary();
+        }
+        problem.add_row([2, 4, 5]);
+        problem.add_row([0, 3, 6]);
+        problem.add_row([1, 2, 5]);
+        problem.add_row([0, 3]);
+        problem.add_row([1, 6]);
+        problem.add_row([3, 4, 6]);
+        assert_eq!(problem.solve(), Some(vec![0, 3, 4]));
+        assert_eq!(problem.count_solutions(), 1);
+
+        problem.add_row([0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(problem.count_solutions(), 2);
+    }
+
+    #[test]
+    fn test_queens() {
+        // Ranks and files are primary, diagonals are secondary
+        for (n, expected) in [(1, 1), (4, 2), (5, 10), (6, 4), (8, 92)] {
+            let mut problem = ExactCover::new();
+            let ranks = (0..n).map(|_| problem.add_primary()).collect::<Vec<_>>();
+            let files = (0..n).map(|_| problem.add_primary()).collect::<Vec<_>>();
+            let diagonals = (0..2 * n - 1)
+                .map(|_| problem.add_secondary())
+                .collect::<Vec<_>>();
+            let anti = (0..2 * n - 1)
+                .map(|_| problem.add_secondary())
+                .collect::<Vec<_>>();
+            for r in 0..n {
+                for c in 0..n {
+                    problem.add_row([ranks[r], files[c], diagonals[r + c], anti[n - 1 + r - c]]);
+                }
+            }
+            assert_eq!(problem.count_solutions(), expected, "{n} queens");
+        }
+    }
+
+    #[test]
+    fn test_multiplicity() {
+        // Two dominoes and a monomino in a row of five cells, the pieces may repeat
+        let mut problem = ExactCover::new();
+        let dominoes = problem.add_column(2..=2);
+        let monominoes = problem.add_column(1..=1);
+        let cells = (0..5).map(|_| problem.add_primary()).collect::<Vec<_>>();
+        for start in 0..4 {
+            problem.add_row([dominoes, cells[start], cells[start + 1]]);
+        }
+        for cell in &cells {
+            problem.add_row([monominoes, *cell]);
+        }
+        // The monomino goes to cell 0, 2 or 4
+        assert_eq!(problem.count_solutions(), 3);
+
+        // Covering cells at most once leaves gaps anywhere
+        let mut problem = ExactCover::new();
+        let dominoes = problem.add_column(1..=2);
+        let cells = (0..4).map(|_| problem.add_secondary()).collect::<Vec<_>>();
+        for start in 0..3 {
+            problem.add_row([dominoes, cells[start], cells[start + 1]]);
+        }
+        // One of three places, or both ends
+        assert_eq!(problem.count_solutions(), 4);
+        assert_eq!(problem.solve(), Some(vec![0, 2]));
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        let mut problem = ExactCover::new();
+        let a = problem.add_primary();
+        let b = problem.add_column(2..=3);
+        problem.add_row([a, b]);
+        problem.add_row([a]);
+        assert_eq!(problem.solve(), None);
+    }
+}
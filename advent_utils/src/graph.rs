@@ -0,0 +1,300 @@
+//! Static graphs with nodes identified by dense `u32` ids.
+//!
+//! Graphs are collected with [`GraphBuilder`], usually from name pairs of the input,
+//! and stored in compressed sparse rows: the edges of a node are a contiguous slice.
+//! Undirected edges are stored in both directions.
+mod all_pairs;
+mod cliques;
+mod components;
+mod longest_path;
+mod maze;
+mod min_cut;
+mod paths;
+mod search;
+mod tsp;
+
+pub use all_pairs::DistanceMatrix;
+pub use cliques::AdjacencyBits;
+pub use components::{Components, CycleError};
+pub use longest_path::{LongestPathOptions, SimplePath};
+pub use maze::{contract_maze, Corridor, MazeGraph};
+pub use min_cut::MinCut;
+pub use paths::{BigCount, ModCount, PathCount, WaypointOrder};
+pub use tsp::{brute_force_tsp, held_karp, Objective, Route, Tour, HELD_KARP_MAX_NODES};
+
+use crate::interner::Interner;
+
+#[derive(Debug, Clone)]
+pub struct Graph<'t, W = ()> {
+    names: Interner<'t>,
+    directed: bool,
+    /// Edges of node `i` are `offsets[i]..offsets[i + 1]`
+    offsets: Vec<u32>,
+    targets: Vec<u32>,
+    weights: Vec<W>,
+}
+
+impl<'t, W> Graph<'t, W> {
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Number of added edges, undirected ones are counted once
+    pub fn edge_count(&self) -> usize {
+        if self.directed {
+            self.targets.len()
+        } else {
+            self.targets.len() / 2
+        }
+    }
+
+    pub fn nodes(&self) -> impl ExactSizeIterator<Item = u32> {
+        0..self.node_count() as u32
+    }
+
+    /// Id of a named node
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.names.get(name)
+    }
+
+    /// Name of the node, `None` for nodes added by id
+    pub fn name(&self, id: u32) -> Option<&'t str> {
+        self.names.names().get(id as usize).copied()
+    }
+
+    pub fn names(&self) -> &Interner<'t> {
+        &self.names
+    }
+
+    pub fn degree(&self, node: u32) -> usize {
+        let node = node as usize;
+        (self.offsets[node + 1] - self.offsets[node]) as usize
+    }
+
+    pub fn neighbours(&self, node: u32) -> &[u32] {
+        &self.targets[self.range(node)]
+    }
+
+    /// Outgoing edges as `(target, weight)`
+    pub fn edges(&self, node: u32) -> impl ExactSizeIterator<Item = (u32, &W)> + '_ {
+        let range = self.range(node);
+        self.targets[range.clone()]
+            .iter()
+            .copied()
+            .zip(&self.weights[range])
+    }
+
+    pub fn has_edge(&self, from: u32, to: u32) -> bool {
+        self.neighbours(from).contains(&to)
+    }
+
+    /// Graph with every edge turned around
+    pub fn reversed(&self) -> Self
+    where
+        W: Clone,
+    {
+        if !self.directed {
+            return self.clone();
+        }
+        let mut builder = GraphBuilder::with_names(self.names.clone());
+        builder.nodes = self.node_count();
+        for from in self.nodes() {
+            for (to, w) in self.edges(from) {
+                builder.edges.push((to, from, w.clone()));
+            }
+        }
+        builder.directed()
+    }
+
+    /// The same graph with every weight mapped
+    pub fn map_weights<U>(&self, f: impl FnMut(&W) -> U) -> Graph<'t, U> {
+        Graph {
+            names: self.names.clone(),
+            directed: self.directed,
+            offsets: self.offsets.clone(),
+            targets: self.targets.clone(),
+            weights: self.weights.iter().map(f).collect(),
+        }
+    }
+
+    fn range(&self, node: u32) -> std::ops::Range<usize> {
+        let node = node as usize;
+        self.offsets[node] as usize..self.offsets[node + 1] as usize
+    }
+}
+
+/// Collects nodes and edges of a [`Graph`]
+#[derive(Debug, Clone)]
+pub struct GraphBuilder<'t, W = ()> {
+    names: Interner<'t>,
+    nodes: usize,
+    edges: Vec<(u32, u32, W)>,
+}
+
+impl<W> Default for GraphBuilder<'_, W> {
+    fn default() -> Self {
+        Self::with_names(Interner::new())
+    }
+}
+
+impl<'t, W> GraphBuilder<'t, W> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder which keeps ids of already interned names
+    pub fn with_names(names: Interner<'t>) -> Self {
+        Self {
+            nodes: names.len(),
+            names,
+            edges: Vec::new(),
+        }
+    }
+
+    /// Id of the named node, adding it if it is new
+    pub fn node(&mut self, name: &'t str) -> u32 {
+        let id = self.names.intern(name);
+        self.nodes = self.nodes.max(id as usize + 1);
+        id
+    }
+
+    pub fn weighted_edge(&mut self, from: &'t str, to: &'t str, weight: W) -> &mut Self {
+        let from = self.node(from);
+        let to = self.node(to);
+        self.weighted_id_edge(from, to, weight)
+    }
+
+    /// Adds an edge between nodes known by ids only.
+    ///
+    /// Ids should not be mixed with names, unless they come from [`GraphBuilder::node`].
+    pub fn weighted_id_edge(&mut self, from: u32, to: u32, weight: W) -> &mut Self {
+        self.nodes = self.nodes.max(from.max(to) as usize + 1);
+        self.edges.push((from, to, weight));
+        self
+    }
+
+    pub fn directed(self) -> Graph<'t, W> {
+        self.build(true)
+    }
+
+    pub fn undirected(mut self) -> Graph<'t, W>
+    where
+        W: Clone,
+    {
+        let len = self.edges.len();
+        for i in 0..len {
+            let (from, to, w) = self.edges[i].clone();
+            self.edges.push((to, from, w));
+        }
+        self.build(false)
+    }
+
+    fn build(self, directed: bool) -> Graph<'t, W> {
+        let mut offsets = vec![0u32; self.nodes + 1];
+        for (from, _, _) in &self.edges {
+            offsets[*from as usize + 1] += 1;
+        }
+        for i in 1..offsets.len() {
+            offsets[i] += offsets[i - 1];
+        }
+        let mut slots: Vec<Option<(u32, W)>> = (0..self.edges.len()).map(|_| None).collect();
+        let mut next = offsets.clone();
+        for (from, to, w) in self.edges {
+            let slot = &mut next[from as usize];
+            slots[*slot as usize] = Some((to, w));
+            *slot += 1;
+        }
+        let (targets, weights) = slots.into_iter().map(Option::unwrap).unzip();
+        Graph {
+            names: self.names,
+            directed,
+            offsets,
+            targets,
+            weights,
+        }
+    }
+}
+
+impl<'t> GraphBuilder<'t, ()> {
+    pub fn edge(&mut self, from: &'t str, to: &'t str) -> &mut Self {
+        self.weighted_edge(from, to, ())
+    }
+
+    pub fn id_edge(&mut self, from: u32, to: u32) -> &mut Self {
+        self.weighted_id_edge(from, to, ())
+    }
+}
+
+impl<'t> Graph<'t, ()> {
+    /// Directed graph of `(from, to)` name pairs
+    pub fn directed_from_pairs(pairs: impl IntoIterator<Item = (&'t str, &'t str)>) -> Self {
+        let mut builder = GraphBuilder::new();
+        for (from, to) in pairs {
+            builder.edge(from, to);
+        }
+        builder.directed()
+    }
+
+    /// Undirected graph of name pairs
+    pub fn undirected_from_pairs(pairs: impl IntoIterator<Item = (&'t str, &'t str)>) -> Self {
+        let mut builder = GraphBuilder::new();
+        for (from, to) in pairs {
+            builder.edge(from, to);
+        }
+        builder.undirected()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Graph, GraphBuilder};
+
+    #[test]
+    fn test_directed() {
+        let graph = Graph::directed_from_pairs([("a", "b"), ("a", "c"), ("c", "b"), ("d", "a")]);
+        assert!(graph.is_directed());
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 4);
+        let a = graph.id("a").unwrap();
+        let b = graph.id("b").unwrap();
+        assert_eq!(graph.neighbours(a), [b, graph.id("c").unwrap()]);
+        assert_eq!(graph.neighbours(b), []);
+        assert_eq!(graph.name(graph.id("d").unwrap()), Some("d"));
+        let reversed = graph.reversed();
+        assert_eq!(reversed.neighbours(a), [graph.id("d").unwrap()]);
+        assert_eq!(reversed.degree(b), 2);
+    }
+
+    #[test]
+    fn test_undirected_weighted() {
+        let mut builder = GraphBuilder::new();
+        builder
+            .weighted_edge("x", "y", 3)
+            .weighted_edge("y", "z", 4);
+        let graph = builder.undirected();
+        assert!(!graph.is_directed());
+        assert_eq!(graph.edge_count(), 2);
+        let y = graph.id("y").unwrap();
+        let mut edges = graph
+            .edges(y)
+            .map(|(to, w)| (graph.name(to).unwrap(), *w))
+            .collect::<Vec<_>>();
+        edges.sort();
+        assert_eq!(edges, [("x", 3), ("z", 4)]);
+        assert!(graph.has_edge(graph.id("z").unwrap(), y));
+    }
+
+    #[test]
+    fn test_id_edges() {
+        let mut builder = GraphBuilder::new();
+        builder.id_edge(0, 5);
+        let graph = builder.directed();
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.name(5), None);
+        assert_eq!(graph.neighbours(0), [5]);
+    }
+}
@@ -0,0 +1,253 @@
+use std::{collections::VecDeque, ops::Add};
+
+use super::Graph;
+
+/// Shortest distances between all pairs of nodes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceMatrix<D> {
+    nodes: usize,
+    /// `distances[from * nodes + to]`
+    distances: Vec<Option<D>>,
+    /// First node after `from` on the shortest path to `to`, if paths were requested
+    next: Option<Vec<u32>>,
+}
+
+impl<D: Copy> DistanceMatrix<D> {
+    pub fn node_count(&self) -> usize {
+        self.nodes
+    }
+
+    /// `None` if `to` cannot be reached
+    pub fn get(&self, from: u32, to: u32) -> Option<D> {
+        self.distances[from as usize * self.nodes + to as usize]
+    }
+
+    /// Distances from the node to every node
+    pub fn row(&self, from: u32) -> &[Option<D>] {
+        let start = from as usize * self.nodes;
+        &self.distances[start..start + self.nodes]
+    }
+
+    /// First node after `from` on a shortest path to `to`, `to` itself for adjacent
+    /// nodes and `from` for the node itself, `None` if `to` cannot be reached.
+    ///
+    /// # Panics
+    /// If the matrix was computed without paths
+    pub fn next_hop(&self, from: u32, to: u32) -> Option<u32> {
+        let next = self
+            .next
+            .as_ref()
+            .expect("the matrix was computed without paths");
+        self.get(from, to)?;
+        Some(next[from as usize * self.nodes + to as usize])
+    }
+
+    /// Nodes of a shortest path, both ends included, `None` if `to` cannot be reached.
+    ///
+    /// # Panics
+    /// If the matrix was computed without paths
+    pub fn path(&self, from: u32, to: u32) -> Option<Vec<u32>> {
+        self.get(from, to)?;
+        let mut path = vec![from];
+        let mut current = from;
+        while current != to {
+            current = self.next_hop(current, to)?;
+            path.push(current);
+        }
+        Some(path)
+    }
+
+    /// Distances between the given nodes only, indexed by their position in `nodes`.
+    ///
+    /// Usual for keeping the interesting nodes of a puzzle before searching over them.
+    /// Next hops are kept when every shortest path between the nodes stays among them,
+    /// as for all nodes reachable from a start.
+    pub fn restrict(&self, nodes: &[u32]) -> Self {
+        let mut position = vec![u32::MAX; self.nodes];
+        for (i, node) in nodes.iter().enumerate() {
+            position[*node as usize] = i as u32;
+        }
+        let next = self.next.as_ref().and_then(|_| {
+            nodes
+                .iter()
+                .flat_map(|from| nodes.iter().map(move |to| (*from, *to)))
+                .map(|(from, to)| match self.next_hop(from, to) {
+                    Some(hop) => Some(position[hop as usize]).filter(|i| *i != u32::MAX),
+                    None => Some(u32::MAX),
+                })
+                .collect::<Option<Vec<_>>>()
+        });
+        Self {
+            nodes: nodes.len(),
+            distances: nodes
+                .iter()
+                .flat_map(|from| nodes.iter().map(|to| self.get(*from, *to)))
+                .collect(),
+            next,
+        }
+    }
+
+    /// Rows of distances with unreachable pairs replaced
+    pub fn to_rows(&self, unreachable: D) -> Vec<Vec<D>> {
+        self.distances
+            .chunks(self.nodes.max(1))
+            .take(self.nodes)
+            .map(|row| row.iter().map(|d| d.unwrap_or(unreachable)).collect())
+            .collect()
+    }
+}
+
+impl<W> Graph<'_, W> {
+    /// Shortest distances between all nodes by Floyd-Warshall algorithm, `O(n^3)`.
+    ///
+    /// Weights must not be negative, `paths` keeps next hops for [`DistanceMatrix::path`].
+    pub fn floyd_warshall(&self, paths: bool) -> DistanceMatrix<W>
+    where
+        W: Copy + Ord + Default + Add<Output = W>,
+    {
+        let n = self.node_count();
+        let mut distances = vec![None; n * n];
+        let mut next = vec![u32::MAX; n * n];
+        for from in self.nodes() {
+            let row = from as usize * n;
+            distances[row + from as usize] = Some(W::default());
+            next[row + from as usize] = from;
+            for (to, w) in self.edges(from) {
+                let slot = &mut distances[row + to as usize];
+                if slot.is_none_or(|d| *w < d) {
+                    *slot = Some(*w);
+                    next[row + to as usize] = to;
+                }
+            }
+        }
+        for k in 0..n {
+            for i in 0..n {
+                let Some(to_k) = distances[i * n + k] else {
+                    continue;
+                };
+                for j in 0..n {
+                    let Some(from_k) = distances[k * n + j] else {
+                        continue;
+                    };
+                    let through = to_k + from_k;
+                    if distances[i * n + j].is_none_or(|d| through < d) {
+                        distances[i * n + j] = Some(through);
+                        next[i * n + j] = next[i * n + k];
+                    }
+                }
+            }
+        }
+        DistanceMatrix {
+            nodes: n,
+            distances,
+            next: paths.then_some(next),
+        }
+    }
+
+    /// Number of edges between all nodes by a breadth-first search from each of them,
+    /// `O(n * (n + e))`.
+    ///
+    /// `paths` keeps next hops for [`DistanceMatrix::path`].
+    pub fn all_pairs_bfs(&self, paths: bool) -> DistanceMatrix<usize> {
+        let n = self.node_count();
+        let mut distances = vec![None; n * n];
+        let mut next = vec![u32::MAX; n * n];
+        let mut queue = VecDeque::new();
+        for from in self.nodes() {
+            let row = from as usize * n;
+            distances[row + from as usize] = Some(0);
+            next[row + from as usize] = from;
+            queue.push_back(from);
+            while let Some(node) = queue.pop_front() {
+                let distance = distances[row + node as usize].unwrap() + 1;
+                for &to in self.neighbours(node) {
+                    if distances[row + to as usize].is_none() {
+                        distances[row + to as usize] = Some(distance);
+                        next[row + to as usize] = if node == from {
+                            to
+                        } else {
+                            next[row + node as usize]
+                        };
+                        queue.push_back(to);
+                    }
+                }
+            }
+        }
+        DistanceMatrix {
+            nodes: n,
+            distances,
+            next: paths.then_some(next),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, GraphBuilder};
+
+    #[test]
+    fn test_floyd_warshall() {
+        let mut builder = GraphBuilder::new();
+        builder
+            .weighted_id_edge(0, 1, 4u32)
+            .weighted_id_edge(0, 2, 1)
+            .weighted_id_edge(2, 1, 2)
+            .weighted_id_edge(1, 3, 1)
+            .weighted_id_edge(4, 0, 1);
+        let graph = builder.directed();
+        let matrix = graph.floyd_warshall(true);
+        assert_eq!(matrix.get(0, 3), Some(4));
+        assert_eq!(matrix.get(4, 3), Some(5));
+        assert_eq!(matrix.get(3, 0), None);
+        assert_eq!(matrix.path(4, 3), Some(vec![4, 0, 2, 1, 3]));
+        assert_eq!(matrix.path(2, 2), Some(vec![2]));
+        assert_eq!(matrix.path(3, 0), None);
+        assert_eq!(matrix.row(0), [Some(0), Some(3), Some(1), Some(4), None]);
+
+        let restricted = matrix.restrict(&[3, 0, 4]);
+        assert_eq!(restricted.node_count(), 3);
+        assert_eq!(restricted.get(2, 0), Some(5));
+        // Paths from 4 to 3 leave the nodes
+        assert_eq!(restricted.next, None);
+        assert_eq!(
+            restricted.to_rows(u32::MAX),
+            [
+                vec![0, u32::MAX, u32::MAX],
+                vec![4, 0, u32::MAX],
+                vec![5, 1, 0]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bfs_matches_floyd_warshall() {
+        let graph = Graph::undirected_from_pairs([
+            ("AA", "DD"),
+            ("AA", "II"),
+            ("AA", "BB"),
+            ("BB", "CC"),
+            ("CC", "DD"),
+            ("DD", "EE"),
+            ("EE", "FF"),
+            ("FF", "GG"),
+            ("GG", "HH"),
+            ("II", "JJ"),
+        ]);
+        let bfs = graph.all_pairs_bfs(true);
+        let floyd = graph.map_weights(|_| 1usize).floyd_warshall(false);
+        for from in graph.nodes() {
+            assert_eq!(bfs.row(from), floyd.row(from));
+            for to in graph.nodes() {
+                let path = bfs.path(from, to).unwrap();
+                assert_eq!(path.len() - 1, bfs.get(from, to).unwrap());
+                assert!(path.windows(2).all(|w| graph.has_edge(w[0], w[1])));
+            }
+        }
+        let id = |name| graph.id(name).unwrap();
+        assert_eq!(bfs.get(id("JJ"), id("HH")), Some(7));
+
+        let chain = bfs.restrict(&[id("HH"), id("FF"), id("GG")]);
+        assert_eq!(chain.next_hop(0, 1), Some(2));
+        assert_eq!(chain.path(1, 0), Some(vec![1, 2, 0]));
+    }
+}
@@ -0,0 +1,314 @@
+use super::Graph;
+
+/// Undirected adjacency matrix stored as rows of bits,
+/// the representation used for clique search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdjacencyBits {
+    rows: Vec<Bits>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Bits(Vec<u64>);
+
+impl Bits {
+    fn empty(n: usize) -> Self {
+        Self(vec![0; n.div_ceil(64)])
+    }
+    fn full(n: usize) -> Self {
+        let mut res = Self::empty(n);
+        for i in 0..n {
+            res.insert(i as u32);
+        }
+        res
+    }
+    fn insert(&mut self, i: u32) {
+        self.0[i as usize / 64] |= 1 << (i % 64);
+    }
+    fn remove(&mut self, i: u32) {
+        self.0[i as usize / 64] &= !(1 << (i % 64));
+    }
+    fn contains(&self, i: u32) -> bool {
+        self.0[i as usize / 64] & (1 << (i % 64)) != 0
+    }
+    fn is_empty(&self) -> bool {
+        self.0.iter().all(|w| *w == 0)
+    }
+    fn first(&self) -> Option<u32> {
+        self.iter().next()
+    }
+    fn len(&self) -> usize {
+        self.0.iter().map(|w| w.count_ones() as usize).sum()
+    }
+    fn and(&self, other: &Self) -> Self {
+        Self(self.0.iter().zip(&other.0).map(|(a, b)| a & b).collect())
+    }
+    fn and_not(&self, other: &Self) -> Self {
+        Self(self.0.iter().zip(&other.0).map(|(a, b)| a & !b).collect())
+    }
+    fn and_len(&self, other: &Self) -> usize {
+        self.0
+            .iter()
+            .zip(&other.0)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+    /// Members above `i`
+    fn above(&self, i: u32) -> Self {
+        let mut res = self.clone();
+        let word = i as usize / 64;
+        res.0[..word].fill(0);
+        res.0[word] &= u64::MAX.checked_shl(i % 64 + 1).unwrap_or(0);
+        res
+    }
+    fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros();
+                    word &= word - 1;
+                    (i * 64) as u32 + bit
+                })
+            })
+        })
+    }
+}
+
+impl AdjacencyBits {
+    pub fn new(nodes: usize) -> Self {
+        Self {
+            rows: vec![Bits::empty(nodes); nodes],
+        }
+    }
+
+    pub fn from_graph<W>(graph: &Graph<'_, W>) -> Self {
+        let mut res = Self::new(graph.node_count());
+        for from in graph.nodes() {
+            for &to in graph.neighbours(from) {
+                res.add_edge(from, to);
+            }
+        }
+        res
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Adds an undirected edge, loops are ignored
+    pub fn add_edge(&mut self, a: u32, b: u32) {
+        if a != b {
+            self.rows[a as usize].insert(b);
+            self.rows[b as usize].insert(a);
+        }
+    }
+
+    pub fn has_edge(&self, a: u32, b: u32) -> bool {
+        self.rows[a as usize].contains(b)
+    }
+
+    /// All cliques which cannot be extended (Bron-Kerbosch with pivoting).
+    ///
+    /// Isolated nodes are cliques of one node.
+    pub fn maximal_cliques(&self) -> Vec<Vec<u32>> {
+        let n = self.node_count();
+        let mut res = Vec::new();
+        self.bron_kerbosch(
+            &mut Vec::new(),
+            Bits::full(n),
+            Bits::empty(n),
+            &mut |clique| res.push(clique.to_vec()),
+        );
+        res
+    }
+
+    /// One of the largest cliques, in ascending order of nodes
+    pub fn maximum_clique(&self) -> Vec<u32> {
+        let mut best = Vec::new();
+        self.largest_clique(&mut Vec::new(), Bits::full(self.node_count()), &mut best);
+        best.sort_unstable();
+        best
+    }
+
+    /// All cliques of `k` nodes with at least one node matching the filter.
+    ///
+    /// Nodes of every clique are in ascending order.
+    pub fn k_cliques(&self, k: usize, filter: impl Fn(u32) -> bool) -> Vec<Vec<u32>> {
+        let mut res = Vec::new();
+        let mut clique = Vec::with_capacity(k);
+        self.cliques_of_size(
+            k,
+            &mut clique,
+            Bits::full(self.node_count()),
+            &filter,
+            &mut res,
+        );
+        res
+    }
+
+    fn bron_kerbosch(
+        &self,
+        clique: &mut Vec<u32>,
+        mut candidates: Bits,
+        mut excluded: Bits,
+        report: &mut impl FnMut(&[u32]),
+    ) {
+        if candidates.is_empty() {
+            if excluded.is_empty() {
+                report(clique);
+            }
+            return;
+        }
+        // Neighbours of the pivot are reached through the pivot or its neighbours
+        let pivot = candidates
+            .iter()
+            .chain(excluded.iter())
+            .max_by_key(|u| candidates.and_len(&self.rows[*u as usize]))
+            .unwrap();
+        for v in candidates
+            .and_not(&self.rows[pivot as usize])
+            .iter()
+            .collect::<Vec<_>>()
+        {
+            let neighbours = &self.rows[v as usize];
+            clique.push(v);
+            self.bron_kerbosch(
+                clique,
+                candidates.and(neighbours),
+                excluded.and(neighbours),
+                report,
+            );
+            clique.pop();
+            candidates.remove(v);
+            excluded.insert(v);
+        }
+    }
+
+    fn largest_clique(&self, clique: &mut Vec<u32>, mut candidates: Bits, best: &mut Vec<u32>) {
+        if clique.len() > best.len() {
+            best.clone_from(clique);
+        }
+        while let Some(v) = candidates.first() {
+            if clique.len() + candidates.len() <= best.len() {
+                return;
+            }
+            clique.push(v);
+            self.largest_clique(clique, candidates.and(&self.rows[v as usize]), best);
+            clique.pop();
+            candidates.remove(v);
+        }
+    }
+
+    fn cliques_of_size(
+        &self,
+        k: usize,
+        clique: &mut Vec<u32>,
+        candidates: Bits,
+        filter: &impl Fn(u32) -> bool,
+        res: &mut Vec<Vec<u32>>,
+    ) {
+        if clique.len() == k {
+            if clique.iter().any(|v| filter(*v)) {
+                res.push(clique.clone());
+            }
+            return;
+        }
+        if clique.len() + candidates.len() < k {
+            return;
+        }
+        for v in candidates.iter() {
+            clique.push(v);
+            let next = candidates.and(&self.rows[v as usize]).above(v);
+            self.cliques_of_size(k, clique, next, filter, res);
+            clique.pop();
+        }
+    }
+}
+
+impl<W> Graph<'_, W> {
+    /// See [`AdjacencyBits::maximal_cliques`]
+    pub fn maximal_cliques(&self) -> Vec<Vec<u32>> {
+        AdjacencyBits::from_graph(self).maximal_cliques()
+    }
+
+    /// See [`AdjacencyBits::maximum_clique`]
+    pub fn maximum_clique(&self) -> Vec<u32> {
+        AdjacencyBits::from_graph(self).maximum_clique()
+    }
+
+    /// See [`AdjacencyBits::k_cliques`]
+    pub fn k_cliques(&self, k: usize, filter: impl Fn(u32) -> bool) -> Vec<Vec<u32>> {
+        AdjacencyBits::from_graph(self).k_cliques(k, filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::Graph;
+
+    use super::AdjacencyBits;
+
+    const NETWORK: &str = "kh-tc qp-kh de-cg ka-co yn-aq qp-ub cg-tb vc-aq tb-ka wh-tc yn-cg kh-ub ta-co de-co tc-td tb-wq wh-td ta-ka td-qp aq-cg wq-ub ub-vc de-ta wq-aq wq-vc wh-yn ka-de kh-ta co-tc wh-qp tb-vc td-yn";
+
+    fn network() -> Graph<'static> {
+        Graph::undirected_from_pairs(NETWORK.split(' ').map(|pair| pair.split_once('-').unwrap()))
+    }
+
+    fn names(graph: &Graph<'static>, clique: &[u32]) -> String {
+        let mut names = clique
+            .iter()
+            .map(|n| graph.name(*n).unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        names.join(",")
+    }
+
+    #[test]
+    fn test_k_cliques() {
+        let graph = network();
+        let triangles = graph.k_cliques(3, |_| true);
+        assert_eq!(triangles.len(), 12);
+        let with_t = graph.k_cliques(3, |n| graph.name(n).unwrap().starts_with('t'));
+        assert_eq!(with_t.len(), 7);
+        assert!(with_t.iter().all(|c| c.is_sorted()));
+    }
+
+    #[test]
+    fn test_maximum_clique() {
+        let graph = network();
+        assert_eq!(names(&graph, &graph.maximum_clique()), "co,de,ka,ta");
+    }
+
+    #[test]
+    fn test_maximal_cliques() {
+        let mut adjacency = AdjacencyBits::new(7);
+        for (a, b) in [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+            (3, 6),
+        ] {
+            adjacency.add_edge(a, b);
+        }
+        let mut cliques = adjacency
+            .maximal_cliques()
+            .into_iter()
+            .map(|mut c| {
+                c.sort();
+                c
+            })
+            .collect::<Vec<_>>();
+        cliques.sort();
+        assert_eq!(
+            cliques,
+            [vec![0, 1, 2], vec![2, 3], vec![3, 4, 5], vec![3, 6]]
+        );
+        assert_eq!(adjacency.maximum_clique(), [0, 1, 2]);
+        assert!(adjacency.has_edge(6, 3));
+        assert_eq!(AdjacencyBits::new(2).maximal_cliques(), [vec![0], vec![1]]);
+    }
+}
@@ -0,0 +1,217 @@
+use thiserror::Error;
+
+use super::Graph;
+use crate::disjoint_set::DisjointSets;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("graph has a cycle through node {node}")]
+pub struct CycleError {
+    pub node: u32,
+}
+
+/// Partition of nodes into connected components
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Components {
+    /// Component of every node, components are numbered in order of their first node
+    pub labels: Vec<u32>,
+    /// Number of nodes in every component
+    pub sizes: Vec<usize>,
+}
+
+impl Components {
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Nodes of every component
+    pub fn groups(&self) -> Vec<Vec<u32>> {
+        let mut groups = vec![Vec::new(); self.count()];
+        for (node, label) in self.labels.iter().enumerate() {
+            groups[*label as usize].push(node as u32);
+        }
+        groups
+    }
+}
+
+impl<W> Graph<'_, W> {
+    /// Nodes ordered so that every edge goes forward (Kahn's algorithm)
+    pub fn topological_sort(&self) -> Result<Vec<u32>, CycleError> {
+        let mut in_degree = vec![0u32; self.node_count()];
+        for &to in &self.targets {
+            in_degree[to as usize] += 1;
+        }
+        let mut order = self
+            .nodes()
+            .filter(|n| in_degree[*n as usize] == 0)
+            .collect::<Vec<_>>();
+        let mut i = 0;
+        while let Some(&node) = order.get(i) {
+            i += 1;
+            for &next in self.neighbours(node) {
+                in_degree[next as usize] -= 1;
+                if in_degree[next as usize] == 0 {
+                    order.push(next);
+                }
+            }
+        }
+        match in_degree.iter().position(|d| *d > 0) {
+            Some(node) => Err(CycleError { node: node as u32 }),
+            None => Ok(order),
+        }
+    }
+
+    /// Strongly connected components (Tarjan's algorithm).
+    ///
+    /// Components come in reverse topological order:
+    /// edges between components go from later ones to earlier ones.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<u32>> {
+        const UNVISITED: u32 = u32::MAX;
+        let n = self.node_count();
+        let mut index = vec![UNVISITED; n];
+        let mut low = vec![0u32; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+        // (node, position in its neighbours)
+        let mut calls: Vec<(u32, usize)> = Vec::new();
+
+        for root in self.nodes() {
+            if index[root as usize] != UNVISITED {
+                continue;
+            }
+            calls.push((root, 0));
+            while let Some((node, edge)) = calls.last_mut() {
+                let node = *node;
+                let v = node as usize;
+                if *edge == 0 && index[v] == UNVISITED {
+                    index[v] = next_index;
+                    low[v] = next_index;
+                    next_index += 1;
+                    stack.push(node);
+                    on_stack[v] = true;
+                }
+                if let Some(&next) = self.neighbours(node).get(*edge) {
+                    *edge += 1;
+                    let w = next as usize;
+                    if index[w] == UNVISITED {
+                        calls.push((next, 0));
+                    } else if on_stack[w] {
+                        low[v] = low[v].min(index[w]);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if let Some((parent, _)) = calls.last() {
+                    let p = *parent as usize;
+                    low[p] = low[p].min(low[v]);
+                }
+                if low[v] == index[v] {
+                    let mut component = Vec::new();
+                    while let Some(w) = stack.pop() {
+                        on_stack[w as usize] = false;
+                        component.push(w);
+                        if w == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    /// Connected components, directions of edges are ignored
+    pub fn connected_components(&self) -> Components {
+        let mut sets = DisjointSets::new(self.node_count());
+        for from in self.nodes() {
+            for &to in self.neighbours(from) {
+                sets.join(from as usize, to as usize);
+            }
+        }
+        let mut label_of_root = vec![u32::MAX; self.node_count()];
+        let mut labels = Vec::with_capacity(self.node_count());
+        let mut sizes = Vec::new();
+        for node in self.nodes() {
+            let set = sets.get_set_of(node as usize);
+            let label = &mut label_of_root[set.member];
+            if *label == u32::MAX {
+                *label = sizes.len() as u32;
+                sizes.push(set.size);
+            }
+            labels.push(*label);
+        }
+        Components { labels, sizes }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{CycleError, Graph};
+
+    #[test]
+    fn test_topological_sort() {
+        let graph = Graph::directed_from_pairs([
+            ("shirt", "tie"),
+            ("tie", "jacket"),
+            ("pants", "shoes"),
+            ("pants", "belt"),
+            ("belt", "jacket"),
+            ("shirt", "belt"),
+        ]);
+        let order = graph.topological_sort().unwrap();
+        assert_eq!(order.len(), graph.node_count());
+        let position = |name| {
+            order
+                .iter()
+                .position(|n| Some(*n) == graph.id(name))
+                .unwrap()
+        };
+        for from in graph.nodes() {
+            for &to in graph.neighbours(from) {
+                assert!(position(graph.name(from).unwrap()) < position(graph.name(to).unwrap()));
+            }
+        }
+
+        let cyclic = Graph::directed_from_pairs([("a", "b"), ("b", "c"), ("c", "b")]);
+        assert_eq!(cyclic.topological_sort(), Err(CycleError { node: 1 }));
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let graph = Graph::directed_from_pairs([
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("c", "d"),
+            ("d", "e"),
+            ("e", "d"),
+            ("f", "f"),
+        ]);
+        let mut components = graph
+            .strongly_connected_components()
+            .into_iter()
+            .map(|c| {
+                let mut names = c
+                    .into_iter()
+                    .map(|n| graph.name(n).unwrap())
+                    .collect::<Vec<_>>();
+                names.sort();
+                names
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(components[0], ["d", "e"]);
+        components.sort();
+        assert_eq!(components, [vec!["a", "b", "c"], vec!["d", "e"], vec!["f"]]);
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let graph = Graph::directed_from_pairs([("a", "b"), ("c", "b"), ("d", "e"), ("f", "f")]);
+        let components = graph.connected_components();
+        assert_eq!(components.labels, [0, 0, 0, 1, 1, 2]);
+        assert_eq!(components.sizes, [3, 2, 1]);
+        assert_eq!(components.groups(), [vec![0, 1, 2], vec![3, 4], vec![5]]);
+    }
+}
@@ -0,0 +1,428 @@
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+};
+
+use super::Graph;
+
+/// Path without repeated nodes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimplePath {
+    pub length: u64,
+    /// From the start to the end, both included
+    pub nodes: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LongestPathOptions {
+    parallel: bool,
+    prune: bool,
+    perimeter: bool,
+}
+
+impl LongestPathOptions {
+    /// Explores the first branches in separate threads
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Fixes the chain of nodes with a single predecessor leading into the
+    /// target up front, and abandons a branch as soon as a breadth-first search
+    /// over the unvisited nodes cannot reach the target from it.
+    pub fn prune(mut self, prune: bool) -> Self {
+        self.prune = prune;
+        self
+    }
+
+    /// Forced-edge simplification for perimeter nodes, the ones with at most three
+    /// neighbours. Edges between them may only be walked towards the target along
+    /// the perimeter.
+    ///
+    /// Only valid for planar grid-like graphs whose perimeter nodes form the outer
+    /// boundary, such as contracted mazes: a path that walks the boundary away
+    /// from the target cuts itself off from it.
+    pub fn perimeter(mut self, perimeter: bool) -> Self {
+        self.perimeter = perimeter;
+        self
+    }
+}
+
+struct Search {
+    /// Outgoing edges, parallel ones merged into the longest
+    edges: Vec<Vec<(u32, u64)>>,
+    /// Outgoing neighbours as bitsets
+    neighbours: Vec<u64>,
+    /// Longest edge coming into every node
+    best_in: Vec<u64>,
+    target: u32,
+    prune: bool,
+}
+
+#[derive(Clone)]
+struct State {
+    node: u32,
+    visited: u64,
+    length: u64,
+    /// Upper bound of the length still to be walked
+    bound: u64,
+    path: Vec<u32>,
+}
+
+impl Search {
+    fn target_reachable(&self, state: &State) -> bool {
+        let mut reached = 1u64 << state.node;
+        let mut frontier = reached;
+        while frontier != 0 {
+            let node = frontier.trailing_zeros();
+            frontier &= frontier - 1;
+            let next = self.neighbours[node as usize] & !state.visited & !reached;
+            reached |= next;
+            frontier |= next;
+        }
+        reached & (1 << self.target) != 0
+    }
+
+    /// States one step further
+    fn expand<'s>(&'s self, state: &'s State) -> impl Iterator<Item = State> + 's {
+        self.edges[state.node as usize]
+            .iter()
+            .filter(|(next, _)| state.visited & (1 << next) == 0)
+            .map(|&(next, w)| {
+                let mut path = state.path.clone();
+                path.push(next);
+                State {
+                    node: next,
+                    visited: state.visited | (1 << next),
+                    length: state.length + w,
+                    bound: state.bound - self.best_in[next as usize],
+                    path,
+                }
+            })
+    }
+
+    fn dfs(&self, state: &mut State, best: &AtomicU64, best_path: &mut Option<SimplePath>) {
+        if state.node == self.target {
+            if best.fetch_max(state.length, Ordering::Relaxed) < state.length
+                || best_path.is_none() && best.load(Ordering::Relaxed) == state.length
+            {
+                *best_path = Some(SimplePath {
+                    length: state.length,
+                    nodes: state.path.clone(),
+                });
+            }
+            return;
+        }
+        let best_length = best.load(Ordering::Relaxed);
+        if best_path.is_some() && state.length + state.bound <= best_length {
+            return;
+        }
+        if state.length + state.bound < best_length {
+            return;
+        }
+        if self.prune && !self.target_reachable(state) {
+            return;
+        }
+        let (node, visited, length, bound) = (state.node, state.visited, state.length, state.bound);
+        for &(next, w) in &self.edges[node as usize] {
+            if visited & (1 << next) != 0 {
+                continue;
+            }
+            state.node = next;
+            state.visited = visited | (1 << next);
+            state.length = length + w;
+            state.bound = bound - self.best_in[next as usize];
+            state.path.push(next);
+            self.dfs(state, best, best_path);
+            state.path.pop();
+        }
+        (state.node, state.visited, state.length, state.bound) = (node, visited, length, bound);
+    }
+}
+
+impl<W> Graph<'_, W> {
+    /// Longest path without repeated nodes, see [`Graph::longest_path_with`]
+    pub fn longest_path(
+        &self,
+        from: u32,
+        to: u32,
+        weight: impl Fn(&W) -> u64,
+    ) -> Option<SimplePath> {
+        self.longest_path_with(from, to, weight, LongestPathOptions::default())
+    }
+
+    /// Longest path without repeated nodes by exhaustive search
+    /// with bitset visited sets and upper-bound pruning.
+    ///
+    /// # Panics
+    /// If the graph has more than 64 nodes
+    pub fn longest_path_with(
+        &self,
+        from: u32,
+        to: u32,
+        weight: impl Fn(&W) -> u64,
+        options: LongestPathOptions,
+    ) -> Option<SimplePath> {
+        let n = self.node_count();
+        assert!(
+            n <= 64,
+            "longest path search supports up to 64 nodes, got {n}"
+        );
+        let mut edges = vec![Vec::<(u32, u64)>::new(); n];
+        let mut neighbours = vec![0u64; n];
+        for node in self.nodes() {
+            for (next, w) in self.edges(node) {
+                if next == node {
+                    continue;
+                }
+                let w = weight(w);
+                let out = &mut edges[node as usize];
+                match out.iter_mut().find(|(to, _)| *to == next) {
+                    Some((_, existing)) => *existing = (*existing).max(w),
+                    None => out.push((next, w)),
+                }
+                neighbours[node as usize] |= 1 << next;
+            }
+        }
+        if options.perimeter {
+            orient_perimeter(&mut edges, &mut neighbours, to);
+        }
+        let mut best_in = vec![0u64; n];
+        for &(next, w) in edges.iter().flatten() {
+            best_in[next as usize] = best_in[next as usize].max(w);
+        }
+
+        // The part of the path which is forced by the only way into the target
+        let mut tail = vec![to];
+        let mut tail_length = 0;
+        let mut target = to;
+        if options.prune {
+            while target != from {
+                let mut predecessors = self
+                    .nodes()
+                    .filter(|p| neighbours[*p as usize] & (1 << target) != 0);
+                let (Some(p), None) = (predecessors.next(), predecessors.next()) else {
+                    break;
+                };
+                if tail.contains(&p) {
+                    break;
+                }
+                tail_length += edges[p as usize]
+                    .iter()
+                    .find(|(to, _)| *to == target)
+                    .unwrap()
+                    .1;
+                tail.push(p);
+                target = p;
+            }
+        }
+        let mut visited = 1u64 << from;
+        for node in &tail {
+            visited |= 1 << node;
+        }
+        visited &= !(1 << target);
+        let bound = (0..n as u32)
+            .filter(|v| visited & (1 << v) == 0 && *v != from)
+            .map(|v| best_in[v as usize])
+            .sum();
+
+        let search = Search {
+            edges,
+            neighbours,
+            best_in,
+            target,
+            prune: options.prune,
+        };
+        let start = State {
+            node: from,
+            visited: visited | (1 << from),
+            length: 0,
+            bound,
+            path: vec![from],
+        };
+        let best = AtomicU64::new(0);
+        let found = if options.parallel {
+            search.parallel_dfs(start, &best)
+        } else {
+            let mut found = None;
+            search.dfs(&mut start.clone(), &best, &mut found);
+            found
+        };
+
+        found.map(|mut path| {
+            path.length += tail_length;
+            path.nodes.extend(tail.iter().rev().skip(1));
+            path
+        })
+    }
+}
+
+/// Drops edges between perimeter nodes which lead away from the target,
+/// distances are counted in steps along the perimeter
+fn orient_perimeter(edges: &mut [Vec<(u32, u64)>], neighbours: &mut [u64], target: u32) {
+    let n = edges.len();
+    let mut adjacent = neighbours.to_vec();
+    for (node, out) in neighbours.iter().enumerate() {
+        let mut out = *out;
+        while out != 0 {
+            adjacent[out.trailing_zeros() as usize] |= 1 << node;
+            out &= out - 1;
+        }
+    }
+    let perimeter = adjacent
+        .iter()
+        .map(|a| a.count_ones() <= 3)
+        .collect::<Vec<_>>();
+    if !perimeter[target as usize] {
+        return;
+    }
+
+    let mut distance = vec![u32::MAX; n];
+    distance[target as usize] = 0;
+    let mut queue = VecDeque::from([target]);
+    while let Some(node) = queue.pop_front() {
+        for p in 0..n {
+            if perimeter[p] && distance[p] == u32::MAX && neighbours[p] & (1 << node) != 0 {
+                distance[p] = distance[node as usize] + 1;
+                queue.push_back(p as u32);
+            }
+        }
+    }
+
+    for node in (0..n).filter(|node| distance[*node] != u32::MAX) {
+        edges[node].retain(|(next, _)| {
+            let next = distance[*next as usize];
+            next == u32::MAX || next <= distance[node]
+        });
+        neighbours[node] = edges[node].iter().fold(0, |acc, (next, _)| acc | 1 << next);
+    }
+}
+
+impl Search {
+    fn parallel_dfs(&self, start: State, best: &AtomicU64) -> Option<SimplePath> {
+        let threads = thread::available_parallelism().map_or(4, |n| n.get());
+        // Breadth-first until there is enough work for every thread
+        let mut frontier = vec![start];
+        while frontier.len() < threads * 4 {
+            let next = frontier
+                .iter()
+                .flat_map(|state| {
+                    if state.node == self.target {
+                        vec![state.clone()]
+                    } else {
+                        self.expand(state).collect()
+                    }
+                })
+                .collect::<Vec<_>>();
+            if next.len() <= frontier.len() {
+                frontier = next;
+                break;
+            }
+            frontier = next;
+        }
+        let chunk = frontier.len().div_ceil(threads).max(1);
+        thread::scope(|scope| {
+            let handles = frontier
+                .chunks(chunk)
+                .map(|states| {
+                    scope.spawn(move || {
+                        let mut found: Option<SimplePath> = None;
+                        for state in states {
+                            let mut local = None;
+                            self.dfs(&mut state.clone(), best, &mut local);
+                            if let Some(path) = local {
+                                if found.as_ref().is_none_or(|f| f.length < path.length) {
+                                    found = Some(path);
+                                }
+                            }
+                        }
+                        found
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .filter_map(|h| h.join().unwrap())
+                .max_by_key(|path| path.length)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, GraphBuilder};
+
+    use super::LongestPathOptions;
+
+    /// Grid of `size * size` junctions like a contracted maze, with the start
+    /// attached to the top left corner and the target to the bottom right one.
+    /// Nodes are the start, the target, then the junctions row by row.
+    fn grid(size: u32, seed: u64) -> Graph<'static, u64> {
+        let junction = |x: u32, y: u32| 2 + y * size + x;
+        let weight = |a: u32, b: u32| (u64::from(a * 31 + b * 17) * seed) % 23 + 1;
+        let mut builder = GraphBuilder::new();
+        builder
+            .weighted_id_edge(0, junction(0, 0), 5)
+            .weighted_id_edge(junction(size - 1, size - 1), 1, 7);
+        for y in 0..size {
+            for x in 0..size {
+                let node = junction(x, y);
+                if x + 1 < size {
+                    builder.weighted_id_edge(node, node + 1, weight(node, node + 1));
+                }
+                if y + 1 < size {
+                    builder.weighted_id_edge(node, node + size, weight(node, node + size));
+                }
+            }
+        }
+        builder.undirected()
+    }
+
+    #[test]
+    fn test_longest_path() {
+        let mut builder = GraphBuilder::new();
+        builder
+            .weighted_edge("a", "b", 1u64)
+            .weighted_edge("b", "c", 1)
+            .weighted_edge("a", "c", 5)
+            .weighted_edge("c", "d", 1)
+            .weighted_edge("b", "d", 10);
+        let graph = builder.undirected();
+        let id = |name| graph.id(name).unwrap();
+        let path = graph.longest_path(id("a"), id("d"), |w| *w).unwrap();
+        assert_eq!(path.length, 16);
+        assert_eq!(path.nodes, [id("a"), id("c"), id("b"), id("d")]);
+        assert_eq!(
+            graph.longest_path(id("a"), id("a"), |w| *w).unwrap().length,
+            0
+        );
+
+        let disconnected = Graph::directed_from_pairs([("a", "b"), ("c", "d")]);
+        assert_eq!(disconnected.longest_path(0, 3, |_| 1), None);
+    }
+
+    #[test]
+    fn test_options_agree() {
+        for (size, seed) in [(3, 1), (4, 3), (5, 7), (5, 11)] {
+            let graph = grid(size, seed);
+            let expected = graph.longest_path(0, 1, |w| *w).unwrap();
+            for options in 1..8 {
+                let options = LongestPathOptions::default()
+                    .parallel(options & 1 != 0)
+                    .prune(options & 2 != 0)
+                    .perimeter(options & 4 != 0);
+                let path = graph.longest_path_with(0, 1, |w| *w, options).unwrap();
+                assert_eq!(path.length, expected.length, "{size} {seed} {options:?}");
+                assert_eq!(path.nodes.first(), Some(&0));
+                assert_eq!(path.nodes.last(), Some(&1));
+                let walked = path
+                    .nodes
+                    .windows(2)
+                    .map(|w| graph.edges(w[0]).find(|(to, _)| *to == w[1]).unwrap().1)
+                    .sum::<u64>();
+                assert_eq!(walked, path.length);
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use glam::IVec2;
+
+use super::{Graph, GraphBuilder};
+use crate::grid::{Grid, NonDiagonal};
+
+/// Cells walked between two nodes of a [`MazeGraph`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Corridor {
+    /// From the start node to the end node, both included
+    pub cells: Vec<IVec2>,
+}
+
+impl Corridor {
+    /// Number of steps
+    pub fn len(&self) -> usize {
+        self.cells.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Maze contracted to junctions and designated points connected by corridors
+#[derive(Debug, Clone)]
+pub struct MazeGraph {
+    /// Position of every node
+    pub points: Vec<IVec2>,
+    /// Directed, two-way corridors are present in both directions
+    pub graph: Graph<'static, Corridor>,
+    /// Node of every cell of the maze
+    nodes: Grid<Option<u32>>,
+}
+
+impl MazeGraph {
+    /// Node at the position, if there is one
+    pub fn node(&self, pos: IVec2) -> Option<u32> {
+        self.nodes.get(pos).copied().flatten()
+    }
+
+    /// The same graph with corridor lengths as weights
+    pub fn lengths(&self) -> Graph<'static, usize> {
+        self.graph.map_weights(Corridor::len)
+    }
+}
+
+/// Contracts corridors of a maze into edges.
+///
+/// `can_step(from, to, direction)` tells whether one step between adjacent cells is allowed,
+/// it is where walls and one-way cells are described. Steps from walls must not be allowed.
+/// Nodes are the `points` followed by the junctions: cells with at least three neighbours
+/// one can step to or from. Corridors ending in a dead end are dropped.
+///
+/// # Panics
+/// If a point is outside the grid
+pub fn contract_maze<T>(
+    grid: &Grid<T>,
+    points: &[IVec2],
+    can_step: impl Fn(&T, &T, IVec2) -> bool,
+) -> MazeGraph {
+    let step = |pos: IVec2, dir: IVec2| {
+        let next = pos + dir;
+        let from = grid.get(pos)?;
+        let to = grid.get(next)?;
+        can_step(from, to, dir).then_some(next)
+    };
+    let is_junction = |pos: IVec2| {
+        NonDiagonal::directions()
+            .into_iter()
+            .filter(|dir| step(pos, *dir).is_some() || step(pos + *dir, -*dir).is_some())
+            .count()
+            >= 3
+    };
+
+    for pos in points {
+        assert!(
+            grid.get(*pos).is_some(),
+            "maze point {pos} is outside the grid of size {}",
+            grid.size()
+        );
+    }
+    let mut nodes = Grid::new(grid.size(), None::<u32>);
+    let mut all_points = Vec::new();
+    for pos in points
+        .iter()
+        .copied()
+        .chain(grid.coords().filter(|pos| is_junction(*pos)))
+    {
+        if nodes.get(pos).copied().flatten().is_none() {
+            nodes.set(pos, Some(all_points.len() as u32));
+            all_points.push(pos);
+        }
+    }
+
+    let mut builder = GraphBuilder::new();
+    for (from, start) in all_points.iter().copied().enumerate() {
+        for dir in NonDiagonal::directions() {
+            let Some(mut pos) = step(start, dir) else {
+                continue;
+            };
+            let mut cells = vec![start, pos];
+            let end = loop {
+                if let Some(node) = nodes.get(pos).copied().flatten() {
+                    break Some(node);
+                }
+                let prev = cells[cells.len() - 2];
+                let mut options = NonDiagonal::directions()
+                    .into_iter()
+                    .filter_map(|dir| step(pos, dir))
+                    .filter(|next| *next != prev);
+                match (options.next(), options.next()) {
+                    (Some(next), None) => {
+                        pos = next;
+                        cells.push(pos);
+                    }
+                    _ => break None,
+                }
+            };
+            if let Some(to) = end {
+                builder.weighted_id_edge(from as u32, to, Corridor { cells });
+            }
+        }
+    }
+    builder.nodes = builder.nodes.max(all_points.len());
+
+    MazeGraph {
+        points: all_points,
+        graph: builder.directed(),
+        nodes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use super::contract_maze;
+    use crate::{grid::Grid, parse::ascii_grid};
+
+    const MAZE: &str = "\
+#.#####
+#.....#
+#.###.#
+#.>.#.#
+###.#.#
+###...#
+#####.#";
+
+    fn can_step(from: &u8, to: &u8, dir: IVec2) -> bool {
+        *from != b'#'
+            && *to != b'#'
+            && match from {
+                b'>' => dir == IVec2::X,
+                _ => true,
+            }
+    }
+
+    #[test]
+    fn test_contract_maze() {
+        let grid: Grid<u8> = ascii_grid(MAZE);
+        let start = IVec2::new(1, 0);
+        let end = IVec2::new(5, 6);
+        let maze = contract_maze(&grid, &[start, end], can_step);
+        // Points first, then junctions
+        assert_eq!(
+            maze.points,
+            [start, end, IVec2::new(1, 1), IVec2::new(5, 5)]
+        );
+        let lengths = maze.lengths();
+        let edges = |node: u32| {
+            let mut edges = lengths
+                .edges(node)
+                .map(|(to, len)| (to, *len))
+                .collect::<Vec<_>>();
+            edges.sort();
+            edges
+        };
+        assert_eq!(edges(0), [(2, 1)]);
+        // The slope forbids going back from (5, 5) to (1, 1) through the lower corridor
+        assert_eq!(edges(2), [(0, 1), (3, 8), (3, 8)]);
+        assert_eq!(edges(3), [(1, 1), (2, 8)]);
+        let corridor = maze.graph.edges(3).find(|(to, _)| *to == 1).unwrap().1;
+        assert_eq!(corridor.cells, [IVec2::new(5, 5), IVec2::new(5, 6)]);
+        assert_eq!(maze.node(IVec2::new(5, 5)), Some(3));
+        assert_eq!(maze.node(IVec2::new(3, 1)), None);
+        assert_eq!(maze.node(IVec2::new(-1, 0)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "maze point [5, 7] is outside the grid of size [7, 7]")]
+    fn test_point_outside() {
+        let grid: Grid<u8> = ascii_grid(MAZE);
+        contract_maze(&grid, &[IVec2::new(1, 0), IVec2::new(5, 7)], can_step);
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::{BinaryHeap, HashMap};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use super::Graph;
+use crate::disjoint_set::DisjointSets;
+
+/// Global minimum cut, directions of edges are ignored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut {
+    /// Total weight of the cut edges
+    pub weight: u64,
+    /// Edges between the sides, as `(first side node, second side node)`
+    pub edges: Vec<(u32, u32)>,
+    /// The first side contains node `0`
+    pub sides: [Vec<u32>; 2],
+}
+
+impl<W> Graph<'_, W> {
+    /// Minimum cut by Stoer-Wagner algorithm, `None` for graphs with less than two nodes.
+    ///
+    /// Weights must not be negative.
+    pub fn min_cut(&self, weight: impl Fn(&W) -> u64) -> Option<MinCut> {
+        let n = self.node_count();
+        if n < 2 {
+            return None;
+        }
+        let mut adjacent: Vec<HashMap<u32, u64>> = vec![HashMap::new(); n];
+        for (from, to, w) in self.undirected_edges(&weight) {
+            *adjacent[from as usize].entry(to).or_default() += w;
+            *adjacent[to as usize].entry(from).or_default() += w;
+        }
+        let mut members: Vec<Vec<u32>> = self.nodes().map(|node| vec![node]).collect();
+        let mut active: Vec<u32> = self.nodes().collect();
+        let mut best: Option<(u64, Vec<u32>)> = None;
+
+        let mut connection = vec![0u64; n];
+        let mut added = vec![false; n];
+        while active.len() > 1 {
+            // Maximum adjacency order, the last two nodes are the most tightly connected
+            for &node in &active {
+                connection[node as usize] = 0;
+                added[node as usize] = false;
+            }
+            let mut queue: BinaryHeap<(u64, u32)> = active.iter().map(|&node| (0, node)).collect();
+            let mut order = Vec::with_capacity(active.len());
+            while let Some((w, node)) = queue.pop() {
+                if added[node as usize] || connection[node as usize] != w {
+                    continue;
+                }
+                added[node as usize] = true;
+                order.push(node);
+                for (&next, &w) in &adjacent[node as usize] {
+                    if !added[next as usize] {
+                        connection[next as usize] += w;
+                        queue.push((connection[next as usize], next));
+                    }
+                }
+            }
+            let [.., s, t] = order[..] else {
+                unreachable!("there are at least two active nodes")
+            };
+            let cut_of_phase = connection[t as usize];
+            if best.as_ref().is_none_or(|(w, _)| cut_of_phase < *w) {
+                best = Some((cut_of_phase, members[t as usize].clone()));
+            }
+
+            // Merge t into s
+            for (next, w) in std::mem::take(&mut adjacent[t as usize]) {
+                adjacent[next as usize].remove(&t);
+                if next != s {
+                    *adjacent[s as usize].entry(next).or_default() += w;
+                    *adjacent[next as usize].entry(s).or_default() += w;
+                }
+            }
+            let merged = std::mem::take(&mut members[t as usize]);
+            members[s as usize].extend(merged);
+            active.retain(|node| *node != t);
+        }
+
+        let (_, side) = best?;
+        let mut in_side = vec![false; n];
+        for node in side {
+            in_side[node as usize] = true;
+        }
+        Some(self.cut(&in_side, &weight))
+    }
+
+    /// Minimum cut by repeated random contractions (Karger's algorithm).
+    ///
+    /// Every trial finds the minimum cut with probability of at least `2 / n^2`,
+    /// usually much higher. The same seed gives the same result.
+    pub fn karger_min_cut(
+        &self,
+        trials: usize,
+        seed: u64,
+        weight: impl Fn(&W) -> u64,
+    ) -> Option<MinCut> {
+        let n = self.node_count();
+        if n < 2 {
+            return None;
+        }
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let edges = self
+            .undirected_edges(&weight)
+            .filter(|(_, _, w)| *w > 0)
+            .collect::<Vec<_>>();
+        let mut best: Option<MinCut> = None;
+        let mut keyed = Vec::with_capacity(edges.len());
+        for _ in 0..trials {
+            // Contracting edges in order of these keys picks every next edge
+            // with probability proportional to its weight
+            keyed.clear();
+            keyed.extend(edges.iter().map(|&(from, to, w)| {
+                let key = rng.gen::<f64>().powf(1.0 / w as f64);
+                (key, from, to)
+            }));
+            keyed.sort_unstable_by(|a, b| b.0.total_cmp(&a.0));
+
+            let mut sets = DisjointSets::new(n);
+            let mut components = n;
+            for &(_, from, to) in &keyed {
+                if components == 2 {
+                    break;
+                }
+                if sets.get_set_of(from as usize).member != sets.get_set_of(to as usize).member {
+                    sets.join(from as usize, to as usize);
+                    components -= 1;
+                }
+            }
+            let first = sets.get_set_of(0).member;
+            let in_first = (0..n)
+                .map(|node| sets.get_set_of(node).member == first)
+                .collect::<Vec<_>>();
+            let cut = self.cut(&in_first, &weight);
+            if best.as_ref().is_none_or(|b| cut.weight < b.weight) {
+                best = Some(cut);
+            }
+        }
+        best
+    }
+
+    /// Every edge once, with positive direction for undirected graphs, without loops
+    fn undirected_edges<'a>(
+        &'a self,
+        weight: &'a impl Fn(&W) -> u64,
+    ) -> impl Iterator<Item = (u32, u32, u64)> + 'a {
+        self.nodes().flat_map(move |from| {
+            self.edges(from)
+                .filter(move |(to, _)| *to != from && (self.directed || from < *to))
+                .map(move |(to, w)| (from, to, weight(w)))
+        })
+    }
+
+    fn cut(&self, in_side: &[bool], weight: &impl Fn(&W) -> u64) -> MinCut {
+        // The first side contains node 0
+        let in_first = |node: u32| in_side[node as usize] == in_side[0];
+        let mut res = MinCut {
+            weight: 0,
+            edges: Vec::new(),
+            sides: [Vec::new(), Vec::new()],
+        };
+        for node in self.nodes() {
+            res.sides[usize::from(!in_first(node))].push(node);
+        }
+        for (from, to, w) in self.undirected_edges(weight) {
+            if in_first(from) != in_first(to) {
+                res.weight += w;
+                res.edges.push(if in_first(from) {
+                    (from, to)
+                } else {
+                    (to, from)
+                });
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, GraphBuilder};
+
+    /// The example from the Stoer-Wagner paper
+    fn paper_graph() -> Graph<'static, u64> {
+        let mut builder = GraphBuilder::new();
+        for (from, to, w) in [
+            ("1", "2", 2),
+            ("1", "5", 3),
+            ("2", "3", 3),
+            ("2", "5", 2),
+            ("2", "6", 2),
+            ("3", "4", 4),
+            ("3", "7", 2),
+            ("4", "7", 2),
+            ("4", "8", 2),
+            ("5", "6", 3),
+            ("6", "7", 1),
+            ("7", "8", 3),
+        ] {
+            builder.weighted_edge(from, to, w);
+        }
+        builder.undirected()
+    }
+
+    fn names(graph: &Graph<'static, u64>, nodes: &[u32]) -> Vec<&'static str> {
+        let mut res = nodes
+            .iter()
+            .map(|n| graph.name(*n).unwrap())
+            .collect::<Vec<_>>();
+        res.sort();
+        res
+    }
+
+    #[test]
+    fn test_stoer_wagner() {
+        let graph = paper_graph();
+        let cut = graph.min_cut(|w| *w).unwrap();
+        assert_eq!(cut.weight, 4);
+        assert_eq!(names(&graph, &cut.sides[0]), ["1", "2", "5", "6"]);
+        assert_eq!(names(&graph, &cut.sides[1]), ["3", "4", "7", "8"]);
+        let mut edges = cut
+            .edges
+            .iter()
+            .map(|(a, b)| (graph.name(*a).unwrap(), graph.name(*b).unwrap()))
+            .collect::<Vec<_>>();
+        edges.sort();
+        assert_eq!(edges, [("2", "3"), ("6", "7")]);
+    }
+
+    #[test]
+    fn test_karger() {
+        let graph = paper_graph();
+        let cut = graph.karger_min_cut(50, 42, |w| *w).unwrap();
+        assert_eq!(cut.weight, 4);
+        assert_eq!(names(&graph, &cut.sides[1]), ["3", "4", "7", "8"]);
+        assert_eq!(graph.karger_min_cut(50, 42, |w| *w), Some(cut));
+    }
+
+    #[test]
+    fn test_disconnected() {
+        let graph = Graph::undirected_from_pairs([("a", "b"), ("c", "d")]);
+        let cut = graph.min_cut(|_| 1).unwrap();
+        assert_eq!(cut.weight, 0);
+        assert_eq!(cut.sides, [vec![0, 1], vec![2, 3]]);
+        assert!(cut.edges.is_empty());
+        assert_eq!(
+            Graph::undirected_from_pairs([("a", "a")]).min_cut(|_| 1),
+            None
+        );
+    }
+}
@@ -0,0 +1,360 @@
+use std::fmt;
+
+use super::{CycleError, Graph};
+
+/// Number type of path counts, only addition is needed
+pub trait PathCount: Clone {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn add_assign(&mut self, other: &Self);
+}
+
+macro_rules! impl_path_count {
+    ($($t:ty),*) => {
+        $(impl PathCount for $t {
+            fn zero() -> Self {
+                0
+            }
+            fn one() -> Self {
+                1
+            }
+            fn add_assign(&mut self, other: &Self) {
+                *self += other;
+            }
+        })*
+    };
+}
+
+impl_path_count!(u32, u64, u128, usize);
+
+/// Count modulo `M`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ModCount<const M: u64>(pub u64);
+
+impl<const M: u64> PathCount for ModCount<M> {
+    fn zero() -> Self {
+        Self(0)
+    }
+    fn one() -> Self {
+        Self(1 % M)
+    }
+    fn add_assign(&mut self, other: &Self) {
+        self.0 = ((self.0 as u128 + other.0 as u128) % M as u128) as u64;
+    }
+}
+
+impl<const M: u64> fmt::Display for ModCount<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Unbounded count
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigCount {
+    /// Little-endian base 2^32 digits without trailing zeros
+    limbs: Vec<u32>,
+}
+
+impl BigCount {
+    /// `None` if the count does not fit
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0, |acc, limb| (acc << 32) | *limb as u128),
+        )
+    }
+}
+
+impl From<u64> for BigCount {
+    fn from(value: u64) -> Self {
+        let mut res = Self {
+            limbs: vec![value as u32, (value >> 32) as u32],
+        };
+        while res.limbs.last() == Some(&0) {
+            res.limbs.pop();
+        }
+        res
+    }
+}
+
+impl PathCount for BigCount {
+    fn zero() -> Self {
+        Self::default()
+    }
+    fn one() -> Self {
+        Self::from(1)
+    }
+    fn add_assign(&mut self, other: &Self) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+        let mut carry = 0u64;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb as u64 + other.limbs.get(i).copied().unwrap_or(0) as u64 + carry;
+            *limb = sum as u32;
+            carry = sum >> 32;
+            if carry == 0 && i >= other.limbs.len() {
+                break;
+            }
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl fmt::Display for BigCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const BASE: u64 = 1_000_000_000;
+        let mut limbs = self.limbs.clone();
+        let mut chunks = Vec::new();
+        while !limbs.is_empty() {
+            let mut rem = 0u64;
+            for limb in limbs.iter_mut().rev() {
+                let cur = (rem << 32) | *limb as u64;
+                *limb = (cur / BASE) as u32;
+                rem = cur % BASE;
+            }
+            chunks.push(rem);
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+        }
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((first, rest)) => {
+                write!(f, "{first}")?;
+                for chunk in rest.iter().rev() {
+                    write!(f, "{chunk:09}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// How paths must pass the waypoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaypointOrder {
+    /// Every waypoint, in any order
+    Any,
+    /// Every waypoint, in the given order
+    Given,
+}
+
+impl<W> Graph<'_, W> {
+    /// Number of paths from `from` to `to`.
+    ///
+    /// Fails if a cycle lies on such a path, as there would be infinitely many of them.
+    pub fn count_paths<C: PathCount>(&self, from: u32, to: u32) -> Result<C, CycleError> {
+        self.count_paths_visiting(from, to, &[], WaypointOrder::Any)
+    }
+
+    /// Number of paths from `from` to `to` passing all `waypoints`.
+    ///
+    /// Any order supports up to 16 waypoints.
+    pub fn count_paths_visiting<C: PathCount>(
+        &self,
+        from: u32,
+        to: u32,
+        waypoints: &[u32],
+        order: WaypointOrder,
+    ) -> Result<C, CycleError> {
+        let n = self.node_count();
+        let states = match order {
+            WaypointOrder::Any => {
+                assert!(waypoints.len() <= 16, "too many waypoints");
+                1 << waypoints.len()
+            }
+            WaypointOrder::Given => waypoints.len() + 1,
+        };
+        let last_state = states - 1;
+        let mut waypoint_index = vec![None; n];
+        for (i, w) in waypoints.iter().enumerate() {
+            waypoint_index[*w as usize] = Some(i);
+        }
+        // State after entering the node, `None` if the order is broken
+        let enter = |node: u32, state: usize| match (waypoint_index[node as usize], order) {
+            (None, _) => Some(state),
+            (Some(i), WaypointOrder::Any) => Some(state | (1 << i)),
+            (Some(i), WaypointOrder::Given) => (i == state).then_some(state + 1),
+        };
+
+        let reachable = self.reachable(from);
+        let leads_to_target = self.reaching(to);
+        let relevant = |node: u32| reachable[node as usize] && leads_to_target[node as usize];
+        if !relevant(from) {
+            return Ok(C::zero());
+        }
+
+        // Topological order of nodes on the paths
+        let mut in_degree = vec![0u32; n];
+        for node in self.nodes().filter(|n| relevant(*n)) {
+            for &next in self.neighbours(node) {
+                if relevant(next) {
+                    in_degree[next as usize] += 1;
+                }
+            }
+        }
+        if in_degree[from as usize] > 0 {
+            // Every node on the paths is reachable from `from`
+            return Err(CycleError { node: from });
+        }
+        let mut counts: Vec<Vec<Option<C>>> = vec![Vec::new(); n];
+        counts[from as usize] = vec![None; states];
+        if let Some(state) = enter(from, 0) {
+            counts[from as usize][state] = Some(C::one());
+        }
+        let mut queue = vec![from];
+        let mut visited = 0;
+        while let Some(node) = queue.pop() {
+            visited += 1;
+            let current = std::mem::take(&mut counts[node as usize]);
+            for &next in self.neighbours(node) {
+                if !relevant(next) {
+                    continue;
+                }
+                let next_counts = &mut counts[next as usize];
+                if next_counts.is_empty() {
+                    next_counts.resize(states, None);
+                }
+                for (state, count) in current.iter().enumerate() {
+                    let (Some(count), Some(next_state)) = (count, enter(next, state)) else {
+                        continue;
+                    };
+                    match &mut next_counts[next_state] {
+                        Some(total) => total.add_assign(count),
+                        slot => *slot = Some(count.clone()),
+                    }
+                }
+                in_degree[next as usize] -= 1;
+                if in_degree[next as usize] == 0 {
+                    queue.push(next);
+                }
+            }
+            if node == to {
+                counts[node as usize] = current;
+            }
+        }
+
+        let relevant_count = self.nodes().filter(|n| relevant(*n)).count();
+        if visited < relevant_count {
+            let node = self
+                .nodes()
+                .find(|n| relevant(*n) && in_degree[*n as usize] > 0)
+                .unwrap();
+            return Err(CycleError { node });
+        }
+        Ok(counts[to as usize]
+            .get_mut(last_state)
+            .and_then(Option::take)
+            .unwrap_or_else(C::zero))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{CycleError, Graph, GraphBuilder};
+
+    use super::{BigCount, ModCount, PathCount, WaypointOrder};
+
+    const SERVER_RACK: &str = "svr: aaa bbb
+aaa: fft
+fft: ccc
+bbb: tty
+tty: ccc
+ccc: ddd eee
+ddd: hub
+hub: fff
+eee: dac
+dac: fff
+fff: ggg hhh
+ggg: out
+hhh: out";
+
+    fn rack() -> Graph<'static> {
+        Graph::directed_from_pairs(SERVER_RACK.lines().flat_map(|line| {
+            let (from, to) = line.split_once(": ").unwrap();
+            to.split(' ').map(move |to| (from, to))
+        }))
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let graph = rack();
+        let id = |name| graph.id(name).unwrap();
+        assert_eq!(graph.count_paths::<u64>(id("svr"), id("out")), Ok(8));
+        assert_eq!(graph.count_paths::<u64>(id("ccc"), id("fff")), Ok(2));
+        assert_eq!(graph.count_paths::<u64>(id("out"), id("svr")), Ok(0));
+        assert_eq!(graph.count_paths::<u64>(id("hub"), id("hub")), Ok(1));
+    }
+
+    #[test]
+    fn test_waypoints() {
+        let graph = rack();
+        let id = |name| graph.id(name).unwrap();
+        let count = |waypoints: &[&'static str], order| {
+            let waypoints = waypoints.iter().map(|w| id(w)).collect::<Vec<_>>();
+            graph.count_paths_visiting::<u64>(id("svr"), id("out"), &waypoints, order)
+        };
+        assert_eq!(count(&["dac", "fft"], WaypointOrder::Any), Ok(2));
+        assert_eq!(count(&["fft", "dac"], WaypointOrder::Given), Ok(2));
+        assert_eq!(count(&["dac", "fft"], WaypointOrder::Given), Ok(0));
+        assert_eq!(count(&["fft", "hub"], WaypointOrder::Any), Ok(2));
+        assert_eq!(count(&["bbb", "fft"], WaypointOrder::Any), Ok(0));
+        assert_eq!(count(&["ccc"], WaypointOrder::Given), Ok(8));
+    }
+
+    #[test]
+    fn test_cycles() {
+        let graph = Graph::directed_from_pairs([
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "b"),
+            ("a", "d"),
+            ("x", "x"),
+        ]);
+        let id = |name| graph.id(name).unwrap();
+        assert_eq!(graph.count_paths::<u64>(id("a"), id("d")), Ok(1));
+        assert_eq!(
+            graph.count_paths::<u64>(id("a"), id("c")),
+            Err(CycleError { node: id("b") })
+        );
+    }
+
+    #[test]
+    fn test_big_counts() {
+        // n diamonds in a row make 2^n paths
+        let mut builder = GraphBuilder::new();
+        for i in 0..150 {
+            builder
+                .id_edge(3 * i, 3 * i + 1)
+                .id_edge(3 * i, 3 * i + 2)
+                .id_edge(3 * i + 1, 3 * i + 3)
+                .id_edge(3 * i + 2, 3 * i + 3);
+        }
+        let graph = builder.directed();
+        let count = graph.count_paths::<BigCount>(0, 300).unwrap();
+        assert_eq!(count.to_string(), "1267650600228229401496703205376");
+        assert_eq!(count.to_u128(), Some(1 << 100));
+        assert_eq!(
+            graph.count_paths::<BigCount>(0, 3 * 150).unwrap().to_u128(),
+            None
+        );
+        assert_eq!(
+            graph.count_paths::<BigCount>(0, 3 * 64).unwrap().to_u128(),
+            Some(1 << 64)
+        );
+        assert_eq!(
+            graph.count_paths::<ModCount<1_000_000_007>>(0, 300),
+            Ok(ModCount(976371285))
+        );
+        assert_eq!(BigCount::zero().to_string(), "0");
+    }
+}
@@ -0,0 +1,126 @@
+use std::{cmp::Reverse, collections::BinaryHeap, collections::VecDeque, ops::Add};
+
+use super::Graph;
+
+impl<W> Graph<'_, W> {
+    /// Number of edges on the shortest path from `start` to every node
+    pub fn bfs(&self, start: u32) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.node_count()];
+        distances[start as usize] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            let next_distance = distances[node as usize].map(|d| d + 1);
+            for &next in self.neighbours(node) {
+                if distances[next as usize].is_none() {
+                    distances[next as usize] = next_distance;
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Nodes which can be reached from `start`, including itself
+    pub fn reachable(&self, start: u32) -> Vec<bool> {
+        let mut visited = vec![false; self.node_count()];
+        visited[start as usize] = true;
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for &next in self.neighbours(node) {
+                if !visited[next as usize] {
+                    visited[next as usize] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        visited
+    }
+
+    /// Nodes from which `target` can be reached, including itself
+    pub fn reaching(&self, target: u32) -> Vec<bool> {
+        let mut incoming = vec![Vec::new(); self.node_count()];
+        for from in self.nodes() {
+            for &to in self.neighbours(from) {
+                incoming[to as usize].push(from);
+            }
+        }
+        let mut visited = vec![false; self.node_count()];
+        visited[target as usize] = true;
+        let mut stack = vec![target];
+        while let Some(node) = stack.pop() {
+            for &prev in &incoming[node as usize] {
+                if !visited[prev as usize] {
+                    visited[prev as usize] = true;
+                    stack.push(prev);
+                }
+            }
+        }
+        visited
+    }
+
+    /// Length of the shortest path from `start` to every node.
+    ///
+    /// Weights must not be negative.
+    pub fn dijkstra(&self, start: u32) -> Vec<Option<W>>
+    where
+        W: Copy + Ord + Default + Add<Output = W>,
+    {
+        let mut distances = vec![None; self.node_count()];
+        let mut queue = BinaryHeap::from([(Reverse(W::default()), start)]);
+        while let Some((Reverse(distance), node)) = queue.pop() {
+            if distances[node as usize].is_some() {
+                continue;
+            }
+            distances[node as usize] = Some(distance);
+            for (next, w) in self.edges(node) {
+                if distances[next as usize].is_none() {
+                    queue.push((Reverse(distance + *w), next));
+                }
+            }
+        }
+        distances
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, GraphBuilder};
+
+    #[test]
+    fn test_bfs() {
+        let graph = Graph::undirected_from_pairs([
+            ("a", "b"),
+            ("b", "c"),
+            ("a", "d"),
+            ("d", "c"),
+            ("x", "y"),
+        ]);
+        let id = |name| graph.id(name).unwrap();
+        let distances = graph.bfs(id("a"));
+        assert_eq!(distances[id("c") as usize], Some(2));
+        assert_eq!(distances[id("y") as usize], None);
+        let reachable = graph.reachable(id("y"));
+        assert_eq!(reachable.iter().filter(|x| **x).count(), 2);
+
+        let directed = Graph::directed_from_pairs([("a", "b"), ("b", "c"), ("d", "c")]);
+        assert_eq!(directed.reaching(2), [true, true, true, true]);
+        assert_eq!(directed.reaching(1), [true, true, false, false]);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let mut builder = GraphBuilder::new();
+        builder
+            .weighted_edge("a", "b", 7u32)
+            .weighted_edge("a", "c", 2)
+            .weighted_edge("c", "b", 3)
+            .weighted_edge("b", "d", 1)
+            .weighted_edge("e", "a", 1);
+        let graph = builder.directed();
+        let id = |name| graph.id(name).unwrap();
+        let distances = graph.dijkstra(id("a"));
+        assert_eq!(distances[id("b") as usize], Some(5));
+        assert_eq!(distances[id("d") as usize], Some(6));
+        assert_eq!(distances[id("e") as usize], None);
+    }
+}
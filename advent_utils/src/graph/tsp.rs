@@ -0,0 +1,237 @@
+use std::ops::Add;
+
+use itertools::Itertools;
+
+/// Largest problem [`held_karp`] accepts, its tables take `n * 2^n` entries
+pub const HELD_KARP_MAX_NODES: usize = 20;
+
+/// Which tours are searched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    /// Visit every node once, starting and ending anywhere
+    Path,
+    /// Visit every node once, starting at the given node
+    PathFrom(usize),
+    /// Visit every node once and return to the first one
+    Cycle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    Min,
+    Max,
+}
+
+impl Objective {
+    fn is_better<D: Ord>(self, a: D, b: D) -> bool {
+        match self {
+            Self::Min => a < b,
+            Self::Max => a > b,
+        }
+    }
+}
+
+/// Solution of a travelling salesman problem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tour<D> {
+    pub cost: D,
+    /// Every node once, cycles start at node `0` and do not repeat it at the end
+    pub order: Vec<usize>,
+}
+
+fn cost_of<D>(distances: &[Vec<D>], order: &[usize], route: Route) -> D
+where
+    D: Copy + Default + Add<Output = D>,
+{
+    let closing = match (route, order) {
+        (Route::Cycle, [first, .., last]) => Some(distances[*last][*first]),
+        _ => None,
+    };
+    order
+        .iter()
+        .tuple_windows()
+        .map(|(a, b)| distances[*a][*b])
+        .chain(closing)
+        .fold(D::default(), |acc, d| acc + d)
+}
+
+/// Travelling salesman by Held-Karp dynamic programming over subsets of visited nodes.
+///
+/// `distances[a][b]` is the cost of going from `a` to `b`, it does not have to be symmetric.
+/// Takes `O(2^n * n^2)` time, so it is meant for up to [`HELD_KARP_MAX_NODES`] nodes.
+/// `None` if there are no nodes.
+///
+/// # Panics
+/// If there are more than [`HELD_KARP_MAX_NODES`] nodes or the start of
+/// [`Route::PathFrom`] is not a node
+pub fn held_karp<D>(distances: &[Vec<D>], route: Route, objective: Objective) -> Option<Tour<D>>
+where
+    D: Copy + Ord + Default + Add<Output = D>,
+{
+    let n = distances.len();
+    if n == 0 {
+        return None;
+    }
+    assert!(
+        n <= HELD_KARP_MAX_NODES,
+        "too many nodes for Held-Karp: {n}, at most {HELD_KARP_MAX_NODES}"
+    );
+    check_start(route, n);
+    let full = (1usize << n) - 1;
+    // best[mask * n + last] is the best cost of visiting `mask` and ending at `last`
+    let mut best: Vec<Option<D>> = vec![None; n << n];
+    let mut parent = vec![u8::MAX; n << n];
+    let starts = match route {
+        Route::Path => (0..n).collect_vec(),
+        Route::PathFrom(start) => vec![start],
+        Route::Cycle => vec![0],
+    };
+    for start in starts {
+        best[(1 << start) * n + start] = Some(D::default());
+    }
+    for mask in 1..=full {
+        for last in 0..n {
+            let Some(cost) = best[mask * n + last] else {
+                continue;
+            };
+            for next in (0..n).filter(|next| mask & (1 << next) == 0) {
+                let index = (mask | (1 << next)) * n + next;
+                let candidate = cost + distances[last][next];
+                if best[index].is_none_or(|current| objective.is_better(candidate, current)) {
+                    best[index] = Some(candidate);
+                    parent[index] = last as u8;
+                }
+            }
+        }
+    }
+
+    let mut end: Option<(D, usize)> = None;
+    for last in 0..n {
+        let Some(mut cost) = best[full * n + last] else {
+            continue;
+        };
+        if route == Route::Cycle && n > 1 {
+            cost = cost + distances[last][0];
+        }
+        if end.is_none_or(|(current, _)| objective.is_better(cost, current)) {
+            end = Some((cost, last));
+        }
+    }
+    let (cost, mut last) = end?;
+    let mut order = Vec::with_capacity(n);
+    let mut mask = full;
+    loop {
+        order.push(last);
+        let prev = parent[mask * n + last];
+        if prev == u8::MAX {
+            break;
+        }
+        mask &= !(1 << last);
+        last = prev as usize;
+    }
+    order.reverse();
+    Some(Tour { cost, order })
+}
+
+fn check_start(route: Route, n: usize) {
+    if let Route::PathFrom(start) = route {
+        assert!(start < n, "start node {start} out of range for {n} nodes");
+    }
+}
+
+/// Travelling salesman by trying every permutation, a reference for [`held_karp`]
+pub fn brute_force_tsp<D>(
+    distances: &[Vec<D>],
+    route: Route,
+    objective: Objective,
+) -> Option<Tour<D>>
+where
+    D: Copy + Ord + Default + Add<Output = D>,
+{
+    let n = distances.len();
+    check_start(route, n);
+    let first = match route {
+        Route::Path => None,
+        Route::PathFrom(start) => Some(start),
+        Route::Cycle => Some(0),
+    };
+    let rest = (0..n).filter(|i| Some(*i) != first).collect_vec();
+    let mut best: Option<Tour<D>> = None;
+    for permutation in rest.iter().copied().permutations(rest.len()) {
+        let order = first.into_iter().chain(permutation).collect_vec();
+        if order.is_empty() {
+            continue;
+        }
+        let cost = cost_of(distances, &order, route);
+        if best
+            .as_ref()
+            .is_none_or(|b| objective.is_better(cost, b.cost))
+        {
+            best = Some(Tour { cost, order });
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{brute_force_tsp, cost_of, held_karp, Objective, Route};
+
+    /// Deterministic asymmetric distances
+    fn distances(n: usize) -> Vec<Vec<i64>> {
+        (0..n)
+            .map(|a| {
+                (0..n)
+                    .map(|b| ((a * 7 + b * 13 + a * b * 5) % 23) as i64 - 4)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_santa_routes() {
+        // London, Dublin, Belfast
+        let distances = vec![vec![0, 464, 518], vec![464, 0, 141], vec![518, 141, 0]];
+        let shortest = held_karp(&distances, Route::Path, Objective::Min).unwrap();
+        assert_eq!(shortest.cost, 605);
+        assert!(shortest.order == [0, 1, 2] || shortest.order == [2, 1, 0]);
+        let longest = held_karp(&distances, Route::Path, Objective::Max).unwrap();
+        assert_eq!(longest.cost, 982);
+        assert_eq!(held_karp::<u64>(&[], Route::Cycle, Objective::Min), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "start node 3 out of range for 3 nodes")]
+    fn test_start_out_of_range() {
+        held_karp(&distances(3), Route::PathFrom(3), Objective::Min);
+    }
+
+    #[test]
+    #[should_panic(expected = "too many nodes for Held-Karp: 21")]
+    fn test_too_many_nodes() {
+        held_karp(&distances(21), Route::Path, Objective::Min);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for n in 1..=7 {
+            let distances = distances(n);
+            for route in [Route::Path, Route::PathFrom(n / 2), Route::Cycle] {
+                for objective in [Objective::Min, Objective::Max] {
+                    let expected = brute_force_tsp(&distances, route, objective).unwrap();
+                    let tour = held_karp(&distances, route, objective).unwrap();
+                    assert_eq!(tour.cost, expected.cost, "{n} {route:?} {objective:?}");
+                    assert_eq!(cost_of(&distances, &tour.order, route), tour.cost);
+                    let mut nodes = tour.order.clone();
+                    nodes.sort();
+                    assert_eq!(nodes, (0..n).collect::<Vec<_>>());
+                    match route {
+                        Route::PathFrom(start) => assert_eq!(tour.order[0], start),
+                        Route::Cycle => assert_eq!(tour.order[0], 0),
+                        Route::Path => {}
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Dense ids for puzzle identifiers.
+//!
+//! Ids are assigned in order of the first appearance, so per-node data
+//! can be kept in a `Vec` indexed by id instead of a hash map keyed by name.
+use std::{collections::HashMap, fmt, str::FromStr};
+
+/// Assigns dense `u32` ids to names borrowed from the input
+#[derive(Debug, Clone, Default)]
+pub struct Interner<'t> {
+    ids: HashMap<&'t str, u32>,
+    names: Vec<&'t str>,
+}
+
+impl<'t> Interner<'t> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            ids: HashMap::with_capacity(capacity),
+            names: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the id of the name, assigning the next one if the name is new
+    pub fn intern(&mut self, name: &'t str) -> u32 {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = self.names.len() as u32;
+        self.names.push(name);
+        self.ids.insert(name, id);
+        id
+    }
+
+    /// Id of an already interned name
+    pub fn get(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    /// # Panics
+    /// If the id was not produced by this interner
+    pub fn name(&self, id: u32) -> &'t str {
+        self.names[id as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// All names in order of their ids
+    pub fn names(&self) -> &[&'t str] {
+        &self.names
+    }
+
+    /// Iterates `(id, name)` pairs in order of ids
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (u32, &'t str)> + '_ {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| (id as u32, *name))
+    }
+
+    /// Table with a slot for every interned name
+    pub fn table<T: Clone>(&self, value: T) -> Vec<T> {
+        vec![value; self.len()]
+    }
+}
+
+/// Perfect hash of names made of exactly `LEN` lowercase ascii letters.
+///
+/// Ids are dense in `0..Self::COUNT`, so no interner is needed:
+/// `"aa"` is `0`, `"ab"` is `1` and `"zz"` is `26 * 26 - 1`.
+#[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lowercase<const LEN: usize>(u32);
+
+impl<const LEN: usize> Lowercase<LEN> {
+    /// Number of possible names
+    pub const COUNT: usize = {
+        assert!(LEN <= 6, "26^LEN must fit into u32");
+        26usize.pow(LEN as u32)
+    };
+
+    pub fn new(name: &str) -> Option<Self> {
+        if name.len() != LEN {
+            return None;
+        }
+        name.bytes()
+            .try_fold(0, |id, b| {
+                b.is_ascii_lowercase().then(|| id * 26 + (b - b'a') as u32)
+            })
+            .map(Self)
+    }
+
+    /// # Panics
+    /// If `index >= Self::COUNT`
+    pub fn from_index(index: usize) -> Self {
+        assert!(index < Self::COUNT, "{index} is out of range");
+        Self(index as u32)
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Letters of the name
+    pub fn bytes(self) -> [u8; LEN] {
+        let mut res = [b'a'; LEN];
+        let mut id = self.0;
+        for b in res.iter_mut().rev() {
+            *b += (id % 26) as u8;
+            id /= 26;
+        }
+        res
+    }
+
+    /// Table with a slot for every possible name
+    pub fn table<T: Clone>(value: T) -> Vec<T> {
+        vec![value; Self::COUNT]
+    }
+}
+
+impl<const LEN: usize> FromStr for Lowercase<LEN> {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s).ok_or(())
+    }
+}
+
+impl<const LEN: usize> fmt::Display for Lowercase<LEN> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.bytes() {
+            write!(f, "{}", b as char)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const LEN: usize> fmt::Debug for Lowercase<LEN> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Interner, Lowercase};
+
+    #[test]
+    fn test_interner() {
+        let mut interner = Interner::new();
+        let ids = "root humn dbpl root pppw humn"
+            .split(' ')
+            .map(|name| interner.intern(name))
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [0, 1, 2, 0, 3, 1]);
+        assert_eq!(interner.len(), 4);
+        assert_eq!(interner.get("pppw"), Some(3));
+        assert_eq!(interner.get("zczc"), None);
+        assert_eq!(interner.name(2), "dbpl");
+        assert_eq!(interner.names(), ["root", "humn", "dbpl", "pppw"]);
+        assert_eq!(interner.table(0u8).len(), 4);
+    }
+
+    #[test]
+    fn test_lowercase() {
+        assert_eq!("aa".parse::<Lowercase<2>>(), Ok(Lowercase(0)));
+        assert_eq!("ab".parse::<Lowercase<2>>(), Ok(Lowercase(1)));
+        assert_eq!("zz".parse::<Lowercase<2>>(), Ok(Lowercase(26 * 26 - 1)));
+        assert_eq!("zz".parse::<Lowercase<3>>(), Err(()));
+        assert_eq!("aB".parse::<Lowercase<2>>(), Err(()));
+        assert_eq!(Lowercase::<3>::new("svr").unwrap().to_string(), "svr");
+        assert_eq!(Lowercase::<2>::new("ta").unwrap().bytes(), *b"ta");
+        assert_eq!(Lowercase::<3>::COUNT, 26 * 26 * 26);
+        assert_eq!(Lowercase::<2>::from_index(27).to_string(), "bb");
+    }
+}
@@ -2,16 +2,25 @@ pub mod algo;
 pub mod array_2d;
 mod binary_search;
 pub mod bitfield;
+pub mod coords;
+pub mod cover;
 pub mod disjoint_set;
 pub mod doubly_linked_list;
 pub mod fixed_slice_vec;
 pub mod genetic;
+pub mod graph;
 pub mod grid;
 pub mod immutable_lists;
+pub mod interner;
 pub mod math;
+pub mod memo;
+pub mod ocr;
 pub mod parse;
 pub mod reduces;
+pub mod sat;
+pub mod search;
 pub mod svg;
+pub mod template;
 
 pub use array_2d::Array2d;
 pub use binary_search::binary_search;
@@ -19,7 +28,10 @@ pub use glam;
 pub use immutable_lists::*;
 pub use rand;
 pub mod nom {
+    mod report;
+
     pub use nom::*;
+    pub use report::{parse_all, LabeledError, ParseReport, ReportableError};
     pub fn parse_usize(input: &str) -> nom::IResult<&str, usize> {
         nom::character::complete::u64
             .map(|x| x as usize)
@@ -1,12 +1,24 @@
 pub mod area;
+#[cfg(feature = "bigint")]
+mod big_rat;
+mod field;
+pub mod gf2;
+pub mod lp;
+mod matrix;
+pub mod modular;
 mod per_non_diagonal;
+pub mod polynomial;
 mod rat;
 mod rat_vec2;
 mod rat_vec3;
 mod systems;
 mod turn;
 
+#[cfg(feature = "bigint")]
+pub use big_rat::BigRat;
+pub use field::Field;
 use glam::{I64Vec2, IVec2};
+pub use matrix::{Echelon, Matrix, Solutions};
 pub use per_non_diagonal::PerNonDiagonalDirection;
 pub use rat::Rat;
 pub use rat_vec2::Vec2;
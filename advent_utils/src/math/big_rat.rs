@@ -0,0 +1,47 @@
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+
+use crate::math::{Field, Rat};
+
+/// Arbitrary-precision fraction for systems whose elimination overflows [`Rat`]
+pub type BigRat = num_rational::BigRational;
+
+impl Field for BigRat {
+    fn zero() -> Self {
+        Zero::zero()
+    }
+    fn one() -> Self {
+        num_traits::One::one()
+    }
+    fn is_zero(&self) -> bool {
+        Zero::is_zero(self)
+    }
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        Some(self - rhs)
+    }
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+    fn checked_recip(&self) -> Option<Self> {
+        (!Zero::is_zero(self)).then(|| self.recip())
+    }
+}
+
+impl From<Rat> for BigRat {
+    fn from(value: Rat) -> Self {
+        BigRat::new(BigInt::from(value.top), BigInt::from(value.bottom))
+    }
+}
+
+impl TryFrom<&BigRat> for Rat {
+    type Error = ();
+
+    fn try_from(value: &BigRat) -> Result<Self, Self::Error> {
+        let top = value.numer().to_i128().ok_or(())?;
+        let bottom = value.denom().to_u128().ok_or(())?;
+        Ok(Rat::new(top, bottom))
+    }
+}
@@ -0,0 +1,44 @@
+use crate::math::Rat;
+
+/// Exact numbers the linear algebra works over.
+///
+/// Operations are checked so a fixed-width type can report overflow
+/// instead of producing a wrong solution.
+pub trait Field: Clone + PartialEq + std::fmt::Debug {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn is_zero(&self) -> bool;
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_sub(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+    /// `None` for zero or on overflow
+    fn checked_recip(&self) -> Option<Self>;
+
+    fn checked_neg(&self) -> Option<Self> {
+        Self::zero().checked_sub(self)
+    }
+}
+
+impl Field for Rat {
+    fn zero() -> Self {
+        Rat::ZERO
+    }
+    fn one() -> Self {
+        Rat::ONE
+    }
+    fn is_zero(&self) -> bool {
+        Rat::is_zero(self)
+    }
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Rat::checked_add(*self, *rhs)
+    }
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        Rat::checked_sub(*self, *rhs)
+    }
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Rat::checked_mul(*self, *rhs)
+    }
+    fn checked_recip(&self) -> Option<Self> {
+        Rat::checked_recip(*self)
+    }
+}
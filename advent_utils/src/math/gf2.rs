@@ -0,0 +1,174 @@
+//! Linear systems over GF(2), where addition is XOR.
+//!
+//! Rows are `u128` bitmasks with a bit per variable, so a system has at most
+//! 128 variables and any number of equations.
+
+/// Equations `xor of the variables in the mask = right`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Gf2System {
+    vars: usize,
+    rows: Vec<(u128, bool)>,
+}
+
+/// Solutions `particular ^ xor of any subset of basis`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gf2Solutions {
+    pub particular: u128,
+    /// Basis of the null space, one vector per free variable
+    pub basis: Vec<u128>,
+}
+
+impl Gf2System {
+    /// # Panics
+    /// If there are more than 128 variables
+    pub fn new(vars: usize) -> Self {
+        assert!(vars <= 128, "at most 128 variables are supported");
+        Self {
+            vars,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn vars(&self) -> usize {
+        self.vars
+    }
+
+    pub fn add_equation(&mut self, coefficients: u128, right: bool) -> &mut Self {
+        debug_assert!(
+            self.vars == 128 || coefficients >> self.vars == 0,
+            "coefficient of an unknown variable"
+        );
+        self.rows.push((coefficients, right));
+        self
+    }
+
+    /// Reduced row echelon form with the pivot variable of each remaining row
+    fn eliminate(&self) -> (Vec<(u128, bool)>, Vec<usize>) {
+        let mut rows = self.rows.clone();
+        let mut pivots = Vec::new();
+        for var in 0..self.vars {
+            let bit = 1 << var;
+            let row = pivots.len();
+            let Some(pivot_row) = (row..rows.len()).find(|i| rows[*i].0 & bit != 0) else {
+                continue;
+            };
+            rows.swap(row, pivot_row);
+            let (pivot, right) = rows[row];
+            for (i, other) in rows.iter_mut().enumerate() {
+                if i != row && other.0 & bit != 0 {
+                    other.0 ^= pivot;
+                    other.1 ^= right;
+                }
+            }
+            pivots.push(var);
+        }
+        (rows, pivots)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.eliminate().1.len()
+    }
+
+    /// All solutions, `None` if the system is inconsistent
+    pub fn solve(&self) -> Option<Gf2Solutions> {
+        let (rows, pivots) = self.eliminate();
+        if rows[pivots.len()..].iter().any(|(_, right)| *right) {
+            return None;
+        }
+        let particular = pivots
+            .iter()
+            .zip(&rows)
+            .filter(|(_, (_, right))| *right)
+            .fold(0, |acc, (var, _)| acc | (1 << var));
+        let pivot_mask = pivots.iter().fold(0u128, |acc, var| acc | (1 << var));
+        let basis = (0..self.vars)
+            .filter(|var| pivot_mask & (1 << var) == 0)
+            .map(|free| {
+                let bit = 1 << free;
+                pivots
+                    .iter()
+                    .zip(&rows)
+                    .filter(|(_, (row, _))| row & bit != 0)
+                    .fold(bit, |acc, (var, _)| acc | (1 << var))
+            })
+            .collect();
+        Some(Gf2Solutions { particular, basis })
+    }
+}
+
+impl Gf2Solutions {
+    pub fn is_unique(&self) -> bool {
+        self.basis.is_empty()
+    }
+
+    /// Every solution once, in Gray code order so each one is a single XOR away
+    pub fn iter(&self) -> impl Iterator<Item = u128> + '_ {
+        assert!(self.basis.len() < 64, "too many solutions to enumerate");
+        let mut current = self.particular;
+        (0..1u64 << self.basis.len()).map(move |i| {
+            if i > 0 {
+                current ^= self.basis[i.trailing_zeros() as usize];
+            }
+            current
+        })
+    }
+
+    /// Solution with the fewest variables set, checks `2^free` candidates
+    pub fn min_weight(&self) -> u128 {
+        self.iter().min_by_key(|x| x.count_ones()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Gf2System;
+
+    #[test]
+    fn test_unique() {
+        // x0 ^ x1 = 1, x1 ^ x2 = 0, x0 ^ x2 = 1, x2 = 1
+        let mut system = Gf2System::new(3);
+        system
+            .add_equation(0b011, true)
+            .add_equation(0b110, false)
+            .add_equation(0b101, true)
+            .add_equation(0b100, true);
+        let solutions = system.solve().unwrap();
+        assert!(solutions.is_unique());
+        assert_eq!(solutions.particular, 0b110);
+        assert_eq!(system.rank(), 3);
+    }
+
+    #[test]
+    fn test_min_weight() {
+        // Lights toggled by the buttons: (3) (1,3) (2) (2,3) (0,2) (0,1), target .##.
+        let buttons: [u128; 6] = [0b1000, 0b1010, 0b0100, 0b1100, 0b0101, 0b0011];
+        let target = 0b0110;
+        let mut system = Gf2System::new(buttons.len());
+        for light in 0..4 {
+            let coefficients = buttons
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| *b & (1 << light) != 0)
+                .fold(0, |acc, (i, _)| acc | (1 << i));
+            system.add_equation(coefficients, target & (1 << light) != 0);
+        }
+        let solutions = system.solve().unwrap();
+        assert_eq!(solutions.basis.len(), 2);
+        assert_eq!(solutions.iter().count(), 4);
+        for x in solutions.iter() {
+            let lights = (0..6)
+                .filter(|i| x & (1 << i) != 0)
+                .fold(0, |acc, i| acc ^ buttons[i]);
+            assert_eq!(lights, target);
+        }
+        assert_eq!(solutions.min_weight().count_ones(), 2);
+    }
+
+    #[test]
+    fn test_inconsistent() {
+        let mut system = Gf2System::new(2);
+        system.add_equation(0b11, true).add_equation(0b11, false);
+        assert_eq!(system.solve(), None);
+        assert_eq!(system.rank(), 1);
+    }
+}
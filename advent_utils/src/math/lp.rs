@@ -0,0 +1,593 @@
+//! Linear and integer linear programming.
+//!
+//! Relaxations are solved exactly by a two-phase simplex over [`Rat`] with Bland's rule,
+//! integrality is reached by branching on fractional variables.
+use std::cell::Cell;
+
+use thiserror::Error;
+
+use crate::{
+    math::Rat,
+    search::{branch_and_bound, BranchAndBound, BranchAndBoundOptions},
+};
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum LpError {
+    #[error("the constraints cannot be satisfied")]
+    Infeasible,
+    #[error("the objective is unbounded")]
+    Unbounded,
+    #[error("the numbers overflowed during the simplex")]
+    Overflow,
+}
+
+type LpResult<T> = Result<T, LpError>;
+
+fn add(a: Rat, b: Rat) -> LpResult<Rat> {
+    a.checked_add(b).ok_or(LpError::Overflow)
+}
+fn sub(a: Rat, b: Rat) -> LpResult<Rat> {
+    a.checked_sub(b).ok_or(LpError::Overflow)
+}
+fn mul(a: Rat, b: Rat) -> LpResult<Rat> {
+    a.checked_mul(b).ok_or(LpError::Overflow)
+}
+fn div(a: Rat, b: Rat) -> LpResult<Rat> {
+    a.checked_div(b).ok_or(LpError::Overflow)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Variable(usize);
+
+/// Bounds and integrality of a variable, non-negative and continuous by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VariableDef {
+    lower: Rat,
+    upper: Option<Rat>,
+    integer: bool,
+}
+
+impl Default for VariableDef {
+    fn default() -> Self {
+        Self {
+            lower: Rat::ZERO,
+            upper: None,
+            integer: false,
+        }
+    }
+}
+
+impl VariableDef {
+    pub fn integer(mut self) -> Self {
+        self.integer = true;
+        self
+    }
+
+    /// Variables always have a finite lower bound
+    pub fn min(mut self, lower: impl Into<Rat>) -> Self {
+        self.lower = lower.into();
+        self
+    }
+
+    pub fn max(mut self, upper: impl Into<Rat>) -> Self {
+        self.upper = Some(upper.into());
+        self
+    }
+}
+
+/// `sum(coefficient * variable) + constant`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinearExpr {
+    terms: Vec<(Variable, Rat)>,
+    constant: Rat,
+}
+
+impl LinearExpr {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_term(&mut self, variable: Variable, coefficient: impl Into<Rat>) -> &mut Self {
+        self.terms.push((variable, coefficient.into()));
+        self
+    }
+
+    pub fn with_term(mut self, variable: Variable, coefficient: impl Into<Rat>) -> Self {
+        self.add_term(variable, coefficient);
+        self
+    }
+
+    pub fn with_constant(mut self, constant: impl Into<Rat>) -> Self {
+        self.constant = constant.into();
+        self
+    }
+
+    /// Value of the expression for the values of the variables
+    pub fn eval(&self, values: &[Rat]) -> LpResult<Rat> {
+        self.terms
+            .iter()
+            .try_fold(self.constant, |acc, (variable, coefficient)| {
+                add(acc, mul(*coefficient, values[variable.0])?)
+            })
+    }
+}
+
+/// Sum of the variables
+impl FromIterator<Variable> for LinearExpr {
+    fn from_iter<I: IntoIterator<Item = Variable>>(iter: I) -> Self {
+        iter.into_iter().map(|v| (v, Rat::ONE)).collect()
+    }
+}
+
+impl FromIterator<(Variable, Rat)> for LinearExpr {
+    fn from_iter<I: IntoIterator<Item = (Variable, Rat)>>(iter: I) -> Self {
+        Self {
+            terms: iter.into_iter().collect(),
+            constant: Rat::ZERO,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Le,
+    Ge,
+    Eq,
+}
+
+impl Relation {
+    fn flipped(self) -> Self {
+        match self {
+            Relation::Le => Relation::Ge,
+            Relation::Ge => Relation::Le,
+            Relation::Eq => Relation::Eq,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Problem {
+    vars: Vec<VariableDef>,
+    constraints: Vec<(LinearExpr, Relation, Rat)>,
+    objective: LinearExpr,
+    maximize: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    values: Vec<Rat>,
+    objective: Rat,
+}
+
+impl Solution {
+    pub fn value(&self, variable: Variable) -> Rat {
+        self.values[variable.0]
+    }
+
+    pub fn objective(&self) -> Rat {
+        self.objective
+    }
+
+    pub fn eval(&self, expr: &LinearExpr) -> LpResult<Rat> {
+        expr.eval(&self.values)
+    }
+}
+
+impl Problem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_variable(&mut self, def: VariableDef) -> Variable {
+        self.vars.push(def);
+        Variable(self.vars.len() - 1)
+    }
+
+    pub fn add_constraint(&mut self, expr: LinearExpr, relation: Relation, right: impl Into<Rat>) {
+        self.constraints.push((expr, relation, right.into()));
+    }
+
+    pub fn minimize(&mut self, objective: LinearExpr) {
+        self.objective = objective;
+        self.maximize = false;
+    }
+
+    pub fn maximize(&mut self, objective: LinearExpr) {
+        self.objective = objective;
+        self.maximize = true;
+    }
+
+    /// Optimal solution, integer variables take integer values
+    pub fn solve(&self) -> LpResult<Solution> {
+        let bounds = self.vars.iter().map(|v| (v.lower, v.upper)).collect();
+        let root = self.relaxation(bounds)?.ok_or(LpError::Infeasible)?;
+        let node = if self.vars.iter().any(|v| v.integer) {
+            let search = IntegerSearch {
+                problem: self,
+                root,
+                error: Cell::new(None),
+            };
+            let optimum = branch_and_bound(&search, BranchAndBoundOptions::default());
+            if let Some(error) = search.error.take() {
+                return Err(error);
+            }
+            optimum.ok_or(LpError::Infeasible)?.node
+        } else {
+            root
+        };
+        Ok(Solution {
+            objective: if self.maximize {
+                -node.objective
+            } else {
+                node.objective
+            },
+            values: node.values,
+        })
+    }
+
+    /// Cost of each variable when minimizing
+    fn costs(&self) -> LpResult<(Vec<Rat>, Rat)> {
+        let mut costs = vec![Rat::ZERO; self.vars.len()];
+        for (variable, coefficient) in &self.objective.terms {
+            costs[variable.0] = add(costs[variable.0], *coefficient)?;
+        }
+        let mut constant = self.objective.constant;
+        if self.maximize {
+            costs.iter_mut().for_each(|c| *c = -*c);
+            constant = -constant;
+        }
+        Ok((costs, constant))
+    }
+
+    /// Optimum of the linear relaxation within the bounds, `None` if infeasible
+    fn relaxation(&self, bounds: Vec<(Rat, Option<Rat>)>) -> LpResult<Option<Node>> {
+        let n = self.vars.len();
+        // Rows over the shifted variables `x - lower >= 0`
+        let mut rows = Vec::new();
+        for (expr, relation, right) in &self.constraints {
+            let mut coefficients = vec![Rat::ZERO; n];
+            let mut right = sub(*right, expr.constant)?;
+            for (variable, coefficient) in &expr.terms {
+                let j = variable.0;
+                coefficients[j] = add(coefficients[j], *coefficient)?;
+                right = sub(right, mul(*coefficient, bounds[j].0)?)?;
+            }
+            rows.push((coefficients, *relation, right));
+        }
+        for (j, (lower, upper)) in bounds.iter().enumerate() {
+            if let Some(upper) = upper {
+                if upper < lower {
+                    return Ok(None);
+                }
+                let mut coefficients = vec![Rat::ZERO; n];
+                coefficients[j] = Rat::ONE;
+                rows.push((coefficients, Relation::Le, sub(*upper, *lower)?));
+            }
+        }
+        for (coefficients, relation, right) in &mut rows {
+            if !right.is_non_negative() {
+                coefficients.iter_mut().for_each(|c| *c = -*c);
+                *right = -*right;
+                *relation = relation.flipped();
+            }
+        }
+
+        let slacks = rows.iter().filter(|r| r.1 != Relation::Eq).count();
+        let first_artificial = n + slacks;
+        let artificials = rows.iter().filter(|r| r.1 != Relation::Le).count();
+        let mut tableau = Tableau {
+            rows: Vec::with_capacity(rows.len()),
+            basis: Vec::with_capacity(rows.len()),
+            cols: first_artificial + artificials,
+        };
+        let (mut slack, mut artificial) = (n, first_artificial);
+        for (coefficients, relation, right) in rows {
+            let mut row = coefficients;
+            row.resize(tableau.cols + 1, Rat::ZERO);
+            row[tableau.cols] = right;
+            match relation {
+                Relation::Le => {
+                    row[slack] = Rat::ONE;
+                    tableau.basis.push(slack);
+                    slack += 1;
+                }
+                Relation::Ge => {
+                    row[slack] = Rat::MINUS_ONE;
+                    slack += 1;
+                    row[artificial] = Rat::ONE;
+                    tableau.basis.push(artificial);
+                    artificial += 1;
+                }
+                Relation::Eq => {
+                    row[artificial] = Rat::ONE;
+                    tableau.basis.push(artificial);
+                    artificial += 1;
+                }
+            }
+            tableau.rows.push(row);
+        }
+
+        if artificials > 0 {
+            let mut costs = vec![Rat::ZERO; tableau.cols];
+            costs[first_artificial..].fill(Rat::ONE);
+            tableau.simplex(&costs, tableau.cols)?;
+            if !tableau.objective(&costs)?.is_zero() {
+                return Ok(None);
+            }
+            tableau.drive_out(first_artificial)?;
+        }
+
+        let (mut costs, constant) = self.costs()?;
+        costs.resize(tableau.cols, Rat::ZERO);
+        tableau.simplex(&costs, first_artificial)?;
+
+        let mut values: Vec<Rat> = bounds.iter().map(|(lower, _)| *lower).collect();
+        for (row, column) in tableau.basis.iter().enumerate() {
+            if *column < n {
+                values[*column] = add(values[*column], tableau.rows[row][tableau.cols])?;
+            }
+        }
+        let objective = add(tableau.objective(&costs)?, constant)?;
+        let objective = add(
+            objective,
+            (0..n).try_fold(Rat::ZERO, |acc, j| add(acc, mul(costs[j], bounds[j].0)?))?,
+        )?;
+        Ok(Some(Node {
+            bounds,
+            objective,
+            values,
+        }))
+    }
+}
+
+/// Constraint rows with the right side in the last column and a basic column per row
+struct Tableau {
+    rows: Vec<Vec<Rat>>,
+    basis: Vec<usize>,
+    cols: usize,
+}
+
+impl Tableau {
+    fn objective(&self, costs: &[Rat]) -> LpResult<Rat> {
+        self.basis
+            .iter()
+            .zip(&self.rows)
+            .try_fold(Rat::ZERO, |acc, (column, row)| {
+                add(acc, mul(costs[*column], row[self.cols])?)
+            })
+    }
+
+    /// Minimizes the costs, only the first `allowed` columns may enter the basis
+    fn simplex(&mut self, costs: &[Rat], allowed: usize) -> LpResult<()> {
+        loop {
+            let mut entering = None;
+            for j in 0..allowed {
+                let reduced = self
+                    .basis
+                    .iter()
+                    .zip(&self.rows)
+                    .try_fold(costs[j], |acc, (column, row)| {
+                        sub(acc, mul(costs[*column], row[j])?)
+                    })?;
+                if !reduced.is_non_negative() {
+                    entering = Some(j);
+                    break;
+                }
+            }
+            let Some(entering) = entering else {
+                return Ok(());
+            };
+
+            let mut leaving: Option<(usize, Rat)> = None;
+            for (i, row) in self.rows.iter().enumerate() {
+                if !row[entering].is_non_negative() || row[entering].is_zero() {
+                    continue;
+                }
+                let ratio = div(row[self.cols], row[entering])?;
+                let better = leaving.is_none_or(|(best, best_ratio)| {
+                    ratio < best_ratio || (ratio == best_ratio && self.basis[i] < self.basis[best])
+                });
+                if better {
+                    leaving = Some((i, ratio));
+                }
+            }
+            let (leaving, _) = leaving.ok_or(LpError::Unbounded)?;
+            self.pivot(leaving, entering)?;
+        }
+    }
+
+    fn pivot(&mut self, row: usize, column: usize) -> LpResult<()> {
+        let pivot = self.rows[row][column];
+        for value in self.rows[row].iter_mut() {
+            *value = div(*value, pivot)?;
+        }
+        let pivot_row = std::mem::take(&mut self.rows[row]);
+        for other in self.rows.iter_mut() {
+            let factor = other.get(column).copied().unwrap_or(Rat::ZERO);
+            if factor.is_zero() {
+                continue;
+            }
+            for (value, p) in other.iter_mut().zip(&pivot_row) {
+                *value = sub(*value, mul(factor, *p)?)?;
+            }
+        }
+        self.rows[row] = pivot_row;
+        self.basis[row] = column;
+        Ok(())
+    }
+
+    /// Replaces the artificial basic columns left at zero, dropping redundant rows
+    fn drive_out(&mut self, first_artificial: usize) -> LpResult<()> {
+        let mut row = 0;
+        while row < self.rows.len() {
+            if self.basis[row] < first_artificial {
+                row += 1;
+                continue;
+            }
+            match (0..first_artificial).find(|j| !self.rows[row][*j].is_zero()) {
+                Some(column) => {
+                    self.pivot(row, column)?;
+                    row += 1;
+                }
+                None => {
+                    self.rows.remove(row);
+                    self.basis.remove(row);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bounds of a branch with the optimum of its relaxation
+#[derive(Debug, Clone)]
+struct Node {
+    bounds: Vec<(Rat, Option<Rat>)>,
+    objective: Rat,
+    values: Vec<Rat>,
+}
+
+struct IntegerSearch<'p> {
+    problem: &'p Problem,
+    root: Node,
+    /// First failure of a relaxation, the search cannot report it otherwise
+    error: Cell<Option<LpError>>,
+}
+
+impl BranchAndBound for IntegerSearch<'_> {
+    type Node = Node;
+    type Value = Rat;
+
+    fn root(&self) -> Node {
+        self.root.clone()
+    }
+
+    fn branches(&self, node: &Node, out: &mut Vec<Node>) {
+        let Some(j) = (0..node.values.len())
+            .find(|j| self.problem.vars[*j].integer && !node.values[*j].is_integer())
+        else {
+            return;
+        };
+        let value = node.values[j];
+        let (lower, upper) = node.bounds[j];
+        let mut down = node.bounds.clone();
+        down[j] = (lower, Some(Rat::from(value.floor())));
+        let mut up = node.bounds.clone();
+        up[j] = (Rat::from(value.ceil()), upper);
+        for bounds in [down, up] {
+            match self.problem.relaxation(bounds) {
+                Ok(Some(child)) => out.push(child),
+                Ok(None) => {}
+                Err(error) => self.error.set(Some(error)),
+            }
+        }
+    }
+
+    fn value(&self, node: &Node) -> Option<Rat> {
+        (0..node.values.len())
+            .all(|j| !self.problem.vars[j].integer || node.values[j].is_integer())
+            .then_some(node.objective)
+    }
+
+    fn bound(&self, node: &Node) -> Rat {
+        node.objective
+    }
+
+    fn is_better(&self, candidate: Rat, incumbent: Rat) -> bool {
+        candidate < incumbent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LinearExpr, LpError, Problem, Relation, VariableDef};
+    use crate::math::Rat;
+
+    #[test]
+    fn test_linear() {
+        let mut problem = Problem::new();
+        let x = problem.add_variable(VariableDef::default().max(3));
+        let y = problem.add_variable(VariableDef::default());
+        problem.add_constraint([x, y].into_iter().collect(), Relation::Le, 4);
+        problem.add_constraint(
+            LinearExpr::new().with_term(x, 1).with_term(y, 3),
+            Relation::Le,
+            6,
+        );
+        problem.maximize(LinearExpr::new().with_term(x, 3).with_term(y, 2));
+        let solution = problem.solve().unwrap();
+        assert_eq!(solution.objective(), Rat::from(11));
+        assert_eq!(solution.value(x), Rat::from(3));
+        assert_eq!(solution.value(y), Rat::from(1));
+
+        // The same with a fractional optimum
+        problem.add_constraint(
+            LinearExpr::new().with_term(x, 2).with_term(y, -1),
+            Relation::Le,
+            4,
+        );
+        let solution = problem.solve().unwrap();
+        assert_eq!(solution.value(x), Rat::new(18, 7));
+        assert_eq!(solution.value(y), Rat::new(8, 7));
+        assert_eq!(solution.objective(), Rat::from(10));
+    }
+
+    #[test]
+    fn test_integer() {
+        // Presses of the buttons (3) (1,3) (2) (2,3) (0,2) (0,1) adding up to {3,5,4,7}
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        let targets = [3, 5, 4, 7];
+        let mut problem = Problem::new();
+        let presses = buttons
+            .iter()
+            .map(|_| problem.add_variable(VariableDef::default().integer()))
+            .collect::<Vec<_>>();
+        for (counter, target) in targets.into_iter().enumerate() {
+            let expr = buttons
+                .iter()
+                .zip(&presses)
+                .filter(|(b, _)| b.contains(&counter))
+                .map(|(_, v)| *v)
+                .collect();
+            problem.add_constraint(expr, Relation::Eq, target);
+        }
+        problem.minimize(presses.iter().copied().collect());
+        let solution = problem.solve().unwrap();
+        assert_eq!(solution.objective(), Rat::from(10));
+        assert!(presses.iter().all(|v| solution.value(*v).is_integer()));
+    }
+
+    #[test]
+    fn test_branching() {
+        // max x + y with 2x + 2y <= 7 has the LP optimum 7/2
+        let mut problem = Problem::new();
+        let x = problem.add_variable(VariableDef::default().integer());
+        let y = problem.add_variable(VariableDef::default().integer().min(1));
+        problem.add_constraint(
+            LinearExpr::new().with_term(x, 2).with_term(y, 2),
+            Relation::Le,
+            7,
+        );
+        problem.maximize([x, y].into_iter().collect());
+        let solution = problem.solve().unwrap();
+        assert_eq!(solution.objective(), Rat::from(3));
+        assert!(solution.value(y) >= Rat::ONE);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut problem = Problem::new();
+        let x = problem.add_variable(VariableDef::default());
+        problem.maximize([x].into_iter().collect());
+        assert_eq!(problem.solve(), Err(LpError::Unbounded));
+
+        problem.add_constraint([x].into_iter().collect(), Relation::Ge, 2);
+        problem.add_constraint([x].into_iter().collect(), Relation::Le, 1);
+        assert_eq!(problem.solve(), Err(LpError::Infeasible));
+
+        let mut problem = Problem::new();
+        let x = problem.add_variable(VariableDef::default().integer());
+        problem.add_constraint(LinearExpr::new().with_term(x, 2), Relation::Eq, 1);
+        assert_eq!(problem.solve(), Err(LpError::Infeasible));
+    }
+}
@@ -0,0 +1,404 @@
+use std::ops::{Index, IndexMut};
+
+use crate::math::{Field, Rat, SolveError};
+
+/// Dense row-major matrix over an exact field
+#[derive(Clone, PartialEq, Debug)]
+pub struct Matrix<T = Rat> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+/// Reduced row echelon form
+#[derive(Clone, PartialEq, Debug)]
+pub struct Echelon<T = Rat> {
+    pub matrix: Matrix<T>,
+    /// Pivot column of each of the first `rank` rows
+    pub pivots: Vec<usize>,
+}
+
+impl<T> Echelon<T> {
+    pub fn rank(&self) -> usize {
+        self.pivots.len()
+    }
+
+    /// Columns without a pivot, their variables can take any value
+    pub fn free_columns(&self) -> Vec<usize> {
+        let mut pivots = self.pivots.iter().peekable();
+        (0..self.matrix.cols)
+            .filter(|col| {
+                if pivots.peek() == Some(&col) {
+                    pivots.next();
+                    false
+                } else {
+                    true
+                }
+            })
+            .collect()
+    }
+}
+
+/// Solutions of `A x = b`: `particular + sum(t_k * basis[k])` for any `t`.
+///
+/// The basis vector `k` has `1` at `free[k]` and `0` at the other free variables,
+/// so `t_k` is the value of the free variable `free[k]`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Solutions<T = Rat> {
+    pub particular: Vec<T>,
+    pub free: Vec<usize>,
+    pub basis: Vec<Vec<T>>,
+}
+
+impl<T: Field> Solutions<T> {
+    pub fn is_unique(&self) -> bool {
+        self.basis.is_empty()
+    }
+
+    /// The solution with the free variables set to `params`
+    pub fn at(&self, params: &[T]) -> Result<Vec<T>, SolveError> {
+        if params.len() != self.basis.len() {
+            return Err(SolveError::IncompatibleLeftAndRight);
+        }
+        let mut res = self.particular.clone();
+        for (t, vector) in params.iter().zip(&self.basis) {
+            for (value, v) in res.iter_mut().zip(vector) {
+                let term = t.checked_mul(v).ok_or(SolveError::Overflow)?;
+                *value = value.checked_add(&term).ok_or(SolveError::Overflow)?;
+            }
+        }
+        Ok(res)
+    }
+}
+
+impl<T: Field> Matrix<T> {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![T::zero(); rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut res = Self::zeros(n, n);
+        for i in 0..n {
+            res[(i, i)] = T::one();
+        }
+        res
+    }
+
+    /// # Panics
+    /// If the rows have different lengths
+    pub fn from_rows<R: AsRef<[T]>>(rows: impl IntoIterator<Item = R>) -> Self {
+        let mut data = Vec::new();
+        let mut rows_count = 0;
+        let mut cols = None;
+        for row in rows {
+            let row = row.as_ref();
+            assert_eq!(
+                *cols.get_or_insert(row.len()),
+                row.len(),
+                "rows should have the same length"
+            );
+            data.extend_from_slice(row);
+            rows_count += 1;
+        }
+        Self {
+            rows: rows_count,
+            cols: cols.unwrap_or_default(),
+            data,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut res = Self::zeros(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                res[(j, i)] = self[(i, j)].clone();
+            }
+        }
+        res
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, SolveError> {
+        if self.cols != other.rows {
+            return Err(SolveError::IncompatibleLeftAndRight);
+        }
+        let mut res = Self::zeros(self.rows, other.cols);
+        for i in 0..self.rows {
+            for j in 0..other.cols {
+                res[(i, j)] = dot((0..self.cols).map(|k| (&self[(i, k)], &other[(k, j)])))?;
+            }
+        }
+        Ok(res)
+    }
+
+    /// `A x`
+    pub fn apply(&self, vector: &[T]) -> Result<Vec<T>, SolveError> {
+        if self.cols != vector.len() {
+            return Err(SolveError::IncompatibleLeftAndRight);
+        }
+        (0..self.rows)
+            .map(|i| dot(self.row(i).iter().zip(vector)))
+            .collect()
+    }
+
+    pub fn rref(&self) -> Result<Echelon<T>, SolveError> {
+        let mut matrix = self.clone();
+        let (pivots, _) = matrix.eliminate(self.cols)?;
+        Ok(Echelon { matrix, pivots })
+    }
+
+    pub fn rank(&self) -> Result<usize, SolveError> {
+        Ok(self.rref()?.rank())
+    }
+
+    pub fn determinant(&self) -> Result<T, SolveError> {
+        if self.rows != self.cols {
+            return Err(SolveError::NotSquare);
+        }
+        let (pivots, determinant) = self.clone().eliminate(self.cols)?;
+        Ok(if pivots.len() == self.rows {
+            determinant
+        } else {
+            T::zero()
+        })
+    }
+
+    pub fn inverse(&self) -> Result<Self, SolveError> {
+        if self.rows != self.cols {
+            return Err(SolveError::NotSquare);
+        }
+        let n = self.rows;
+        let mut augmented = Self::zeros(n, 2 * n);
+        for i in 0..n {
+            augmented.data[i * 2 * n..i * 2 * n + n].clone_from_slice(self.row(i));
+            augmented[(i, n + i)] = T::one();
+        }
+        let (pivots, _) = augmented.eliminate(n)?;
+        if pivots.len() < n {
+            return Err(SolveError::Singular);
+        }
+        Ok(Self::from_rows((0..n).map(|i| &augmented.row(i)[n..])))
+    }
+
+    /// Basis of the vectors `x` with `A x = 0`
+    pub fn null_space(&self) -> Result<Vec<Vec<T>>, SolveError> {
+        let echelon = self.rref()?;
+        null_space(&echelon.matrix, &echelon.pivots, self.cols)
+    }
+
+    /// All solutions of `A x = b`, [`SolveError::Inconsistent`] if there are none
+    pub fn solve(&self, rights: &[T]) -> Result<Solutions<T>, SolveError> {
+        if rights.len() != self.rows {
+            return Err(SolveError::IncompatibleLeftAndRight);
+        }
+        let mut augmented = Self::zeros(self.rows, self.cols + 1);
+        for (i, right) in rights.iter().enumerate() {
+            let start = i * (self.cols + 1);
+            augmented.data[start..start + self.cols].clone_from_slice(self.row(i));
+            augmented[(i, self.cols)] = right.clone();
+        }
+        let (pivots, _) = augmented.eliminate(self.cols)?;
+        if (pivots.len()..self.rows).any(|i| !augmented[(i, self.cols)].is_zero()) {
+            return Err(SolveError::Inconsistent);
+        }
+        let mut particular = vec![T::zero(); self.cols];
+        for (i, pivot) in pivots.iter().enumerate() {
+            particular[*pivot] = augmented[(i, self.cols)].clone();
+        }
+        let basis = null_space(&augmented, &pivots, self.cols)?;
+        let free = Echelon {
+            matrix: augmented,
+            pivots,
+        }
+        .free_columns()
+        .into_iter()
+        .filter(|col| *col < self.cols)
+        .collect();
+        Ok(Solutions {
+            particular,
+            free,
+            basis,
+        })
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for j in 0..self.cols {
+            self.data.swap(a * self.cols + j, b * self.cols + j);
+        }
+    }
+
+    /// Gauss-Jordan elimination over the first `columns` columns.
+    ///
+    /// Returns the pivot columns and the determinant of the eliminated square block.
+    fn eliminate(&mut self, columns: usize) -> Result<(Vec<usize>, T), SolveError> {
+        let mut pivots = Vec::new();
+        let mut determinant = T::one();
+        for col in 0..columns {
+            let row = pivots.len();
+            if row == self.rows {
+                break;
+            }
+            let Some(pivot_row) = (row..self.rows).find(|i| !self[(*i, col)].is_zero()) else {
+                continue;
+            };
+            if pivot_row != row {
+                self.swap_rows(pivot_row, row);
+                determinant = determinant.checked_neg().ok_or(SolveError::Overflow)?;
+            }
+            let pivot = self[(row, col)].clone();
+            determinant = determinant
+                .checked_mul(&pivot)
+                .ok_or(SolveError::Overflow)?;
+            let k = pivot.checked_recip().ok_or(SolveError::Overflow)?;
+            for j in col..self.cols {
+                let value = &mut self[(row, j)];
+                *value = value.checked_mul(&k).ok_or(SolveError::Overflow)?;
+            }
+            for i in 0..self.rows {
+                let factor = self[(i, col)].clone();
+                if i == row || factor.is_zero() {
+                    continue;
+                }
+                for j in col..self.cols {
+                    let term = self[(row, j)]
+                        .checked_mul(&factor)
+                        .ok_or(SolveError::Overflow)?;
+                    let value = &mut self[(i, j)];
+                    *value = value.checked_sub(&term).ok_or(SolveError::Overflow)?;
+                }
+            }
+            pivots.push(col);
+        }
+        Ok((pivots, determinant))
+    }
+}
+
+fn dot<'a, T: Field + 'a>(
+    mut pairs: impl Iterator<Item = (&'a T, &'a T)>,
+) -> Result<T, SolveError> {
+    pairs.try_fold(T::zero(), |acc, (a, b)| {
+        let product = a.checked_mul(b).ok_or(SolveError::Overflow)?;
+        acc.checked_add(&product).ok_or(SolveError::Overflow)
+    })
+}
+
+/// Null space of the first `columns` columns of a matrix in reduced row echelon form
+fn null_space<T: Field>(
+    rref: &Matrix<T>,
+    pivots: &[usize],
+    columns: usize,
+) -> Result<Vec<Vec<T>>, SolveError> {
+    let mut res = Vec::new();
+    let mut next_pivot = pivots.iter().peekable();
+    for free in 0..columns {
+        if next_pivot.peek() == Some(&&free) {
+            next_pivot.next();
+            continue;
+        }
+        let mut vector = vec![T::zero(); columns];
+        vector[free] = T::one();
+        for (row, pivot) in pivots.iter().enumerate() {
+            vector[*pivot] = rref[(row, free)]
+                .checked_neg()
+                .ok_or(SolveError::Overflow)?;
+        }
+        res.push(vector);
+    }
+    Ok(res)
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Matrix;
+    use crate::math::{Rat, SolveError};
+
+    fn matrix<const C: usize>(rows: &[[i32; C]]) -> Matrix {
+        Matrix::from_rows(rows.iter().map(|row| row.map(Rat::from)))
+    }
+
+    fn vector(values: &[i32]) -> Vec<Rat> {
+        values.iter().map(|x| Rat::from(*x)).collect()
+    }
+
+    #[test]
+    fn test_rref_and_rank() {
+        let a = matrix(&[[1, 2, 3], [2, 4, 6], [1, 0, 1]]);
+        let echelon = a.rref().unwrap();
+        assert_eq!(echelon.pivots, vec![0, 1]);
+        assert_eq!(echelon.free_columns(), vec![2]);
+        assert_eq!(
+            echelon.matrix,
+            Matrix::from_rows([vector(&[1, 0, 1]), vector(&[0, 1, 1]), vector(&[0, 0, 0])])
+        );
+        assert_eq!(a.rank().unwrap(), 2);
+        assert_eq!(a.transpose().rank().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_determinant_and_inverse() {
+        let a = matrix(&[[0, 2, 1], [1, 1, 0], [3, 0, 1]]);
+        assert_eq!(a.determinant().unwrap(), Rat::from(-5));
+        let inverse = a.inverse().unwrap();
+        assert_eq!(a.checked_mul(&inverse).unwrap(), Matrix::identity(3));
+        assert_eq!(inverse[(0, 0)], Rat::new(-1, 5));
+
+        let singular = matrix(&[[1, 2], [2, 4]]);
+        assert_eq!(singular.determinant().unwrap(), Rat::ZERO);
+        assert!(matches!(singular.inverse(), Err(SolveError::Singular)));
+        assert!(matches!(
+            matrix(&[[1, 2, 3]]).determinant(),
+            Err(SolveError::NotSquare)
+        ));
+    }
+
+    #[test]
+    fn test_underdetermined() {
+        // x + y + z = 6, y - z = 1
+        let a = matrix(&[[1, 1, 1], [0, 1, -1]]);
+        let solutions = a.solve(&vector(&[6, 1])).unwrap();
+        assert!(!solutions.is_unique());
+        assert_eq!(solutions.free, vec![2]);
+        for t in [-3, 0, 7] {
+            let x = solutions.at(&vector(&[t])).unwrap();
+            assert_eq!(x[2], Rat::from(t));
+            assert_eq!(a.apply(&x).unwrap(), vector(&[6, 1]));
+        }
+        for v in a.null_space().unwrap() {
+            assert_eq!(a.apply(&v).unwrap(), vector(&[0, 0]));
+        }
+
+        let unique = matrix(&[[1, 1], [1, -1], [2, 0]]).solve(&vector(&[4, 2, 6]));
+        assert_eq!(unique.unwrap().particular, vector(&[3, 1]));
+        let inconsistent = matrix(&[[1, 1], [1, 1]]).solve(&vector(&[1, 2]));
+        assert!(matches!(inconsistent, Err(SolveError::Inconsistent)));
+    }
+}
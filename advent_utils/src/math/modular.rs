@@ -0,0 +1,328 @@
+//! Modular arithmetic and the Chinese remainder theorem.
+use std::{
+    fmt,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// `(g, x, y)` with `a * x + b * y = g` where `g = gcd(a, b) >= 0`
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `x` in `0..modulus` with `a * x = 1 (mod modulus)`, `None` unless `a` and
+/// `modulus` are coprime
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    assert!(modulus > 0, "modulus must be positive");
+    let (g, x, _) = ext_gcd(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// `base ^ exp (mod modulus)` by squaring
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    assert!(modulus > 0, "modulus must be positive");
+    let modulus = u128::from(modulus);
+    let mut base = u128::from(base) % modulus;
+    let mut result = 1 % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+/// `(residue, modulus)` of all `x` satisfying every `x = r (mod m)`, the moduli
+/// need not be coprime. `None` if the congruences contradict each other.
+///
+/// # Panics
+/// If a modulus is not positive or the combined modulus overflows
+pub fn crt(congruences: impl IntoIterator<Item = (i128, i128)>) -> Option<(i128, i128)> {
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(r1, m1), (r2, m2)| {
+            assert!(m2 > 0, "modulus must be positive");
+            let (g, p, _) = ext_gcd(m1, m2);
+            let diff = r2 - r1;
+            if diff % g != 0 {
+                return None;
+            }
+            let step = m2 / g;
+            let modulus = m1.checked_mul(step).expect("combined modulus overflowed");
+            // p is the inverse of m1 / g modulo m2 / g
+            let k = (diff / g % step) * (p % step) % step;
+            Some(((r1 + m1 * k).rem_euclid(modulus), modulus))
+        })
+}
+
+/// Residue modulo the constant `M`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1 % M);
+
+    pub fn new(value: i128) -> Self {
+        Self(value.rem_euclid(i128::from(M)) as u64)
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn modulus(self) -> u64 {
+        M
+    }
+
+    fn with_value(self, value: u64) -> Self {
+        Self(value)
+    }
+
+    fn check(self, _other: Self) {}
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> Self {
+        Self(value % M)
+    }
+}
+
+impl<const M: u64> From<i64> for ModInt<M> {
+    fn from(value: i64) -> Self {
+        Self::new(value.into())
+    }
+}
+
+/// Residue modulo a modulus known at runtime, operands must share it
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DynModInt {
+    value: u64,
+    modulus: u64,
+}
+
+impl DynModInt {
+    pub fn new(value: i128, modulus: u64) -> Self {
+        assert!(modulus > 0, "modulus must be positive");
+        Self {
+            value: value.rem_euclid(i128::from(modulus)) as u64,
+            modulus,
+        }
+    }
+
+    pub fn value(self) -> u64 {
+        self.value
+    }
+
+    pub fn modulus(self) -> u64 {
+        self.modulus
+    }
+
+    fn with_value(self, value: u64) -> Self {
+        Self {
+            value,
+            modulus: self.modulus,
+        }
+    }
+
+    fn check(self, other: Self) {
+        assert_eq!(self.modulus, other.modulus, "different moduli");
+    }
+}
+
+macro_rules! impl_mod_int_ops {
+    ($($t:ty, [$($generics:tt)*]);*) => {
+        $(
+            impl<$($generics)*> $t {
+                pub fn pow(self, exp: u64) -> Self {
+                    self.with_value(mod_pow(self.value(), exp, self.modulus()))
+                }
+
+                /// `None` unless the value is coprime with the modulus
+                pub fn inv(self) -> Option<Self> {
+                    mod_inverse(self.value().into(), self.modulus().into())
+                        .map(|x| self.with_value(x as u64))
+                }
+            }
+
+            impl<$($generics)*> Add for $t {
+                type Output = Self;
+                fn add(self, rhs: Self) -> Self {
+                    self.check(rhs);
+                    let sum = u128::from(self.value()) + u128::from(rhs.value());
+                    self.with_value((sum % u128::from(self.modulus())) as u64)
+                }
+            }
+
+            impl<$($generics)*> Sub for $t {
+                type Output = Self;
+                fn sub(self, rhs: Self) -> Self {
+                    self + -rhs
+                }
+            }
+
+            impl<$($generics)*> Mul for $t {
+                type Output = Self;
+                fn mul(self, rhs: Self) -> Self {
+                    self.check(rhs);
+                    let product = u128::from(self.value()) * u128::from(rhs.value());
+                    self.with_value((product % u128::from(self.modulus())) as u64)
+                }
+            }
+
+            /// # Panics
+            /// If `rhs` has no inverse
+            impl<$($generics)*> Div for $t {
+                type Output = Self;
+                #[allow(clippy::suspicious_arithmetic_impl)]
+                fn div(self, rhs: Self) -> Self {
+                    self * rhs.inv().expect("division by a non-invertible residue")
+                }
+            }
+
+            impl<$($generics)*> Neg for $t {
+                type Output = Self;
+                fn neg(self) -> Self {
+                    if self.value() == 0 {
+                        self
+                    } else {
+                        self.with_value(self.modulus() - self.value())
+                    }
+                }
+            }
+
+            impl<$($generics)*> AddAssign for $t {
+                fn add_assign(&mut self, rhs: Self) {
+                    *self = *self + rhs;
+                }
+            }
+
+            impl<$($generics)*> SubAssign for $t {
+                fn sub_assign(&mut self, rhs: Self) {
+                    *self = *self - rhs;
+                }
+            }
+
+            impl<$($generics)*> MulAssign for $t {
+                fn mul_assign(&mut self, rhs: Self) {
+                    *self = *self * rhs;
+                }
+            }
+
+            impl<$($generics)*> DivAssign for $t {
+                fn div_assign(&mut self, rhs: Self) {
+                    *self = *self / rhs;
+                }
+            }
+
+            impl<$($generics)*> fmt::Display for $t {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "{}", self.value())
+                }
+            }
+
+            impl<$($generics)*> fmt::Debug for $t {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "{} (mod {})", self.value(), self.modulus())
+                }
+            }
+        )*
+    };
+}
+
+impl_mod_int_ops! {
+    ModInt<M>, [const M: u64];
+    DynModInt, []
+}
+
+impl<const M: u64> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl<const M: u64> Product for ModInt<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, Mul::mul)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crt, ext_gcd, mod_inverse, mod_pow, DynModInt, ModInt};
+
+    #[test]
+    fn test_ext_gcd() {
+        for (a, b) in [(240, 46), (-240, 46), (17, 0), (0, -5), (12, 18)] {
+            let (g, x, y) = ext_gcd(a, b);
+            assert!(g >= 0);
+            assert_eq!(a * x + b * y, g);
+        }
+        assert_eq!(ext_gcd(240, 46).0, 2);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(7, 0, 13), 1);
+        assert_eq!(mod_pow(7, 0, 1), 0);
+        // Fermat's little theorem with a modulus near 2^63
+        let p = 9_223_372_036_854_775_783;
+        assert_eq!(mod_pow(123_456_789, p - 1, p), 1);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Not coprime
+        assert_eq!(crt([(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([(-1, 10), (3, 4)]), Some((19, 20)));
+        assert_eq!(crt([]), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_mod_int() {
+        type M = ModInt<7>;
+        let a = M::new(-3);
+        assert_eq!(a.value(), 4);
+        assert_eq!(a + M::from(5u64), M::new(2));
+        assert_eq!(a - M::new(6), M::new(5));
+        assert_eq!(a * a, M::new(2));
+        assert_eq!(M::ONE / a * a, M::ONE);
+        assert_eq!(a.pow(6), M::ONE);
+        assert_eq!((1..=6).map(M::new).product::<M>(), M::new(-1));
+        assert_eq!(format!("{a} {a:?}"), "4 4 (mod 7)");
+
+        let b = DynModInt::new(10, 6);
+        assert_eq!((b * b).value(), 4);
+        assert_eq!(b.inv(), None);
+        let mut c = DynModInt::new(3, 10);
+        c /= DynModInt::new(7, 10);
+        assert_eq!(c.value(), 9);
+    }
+
+    #[test]
+    #[should_panic(expected = "different moduli")]
+    fn test_different_moduli() {
+        let _ = DynModInt::new(1, 5) + DynModInt::new(1, 7);
+    }
+}
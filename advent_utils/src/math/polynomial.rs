@@ -0,0 +1,206 @@
+//! Polynomials over [`Rat`] for fitting and extrapolating sequences.
+//!
+//! Operations panic on overflow like the [`Rat`] operators, `checked_*`
+//! variants report it.
+use crate::math::Rat;
+
+/// Extra points a detected degree has to agree with
+const CONFIRMATIONS: usize = 2;
+
+/// Coefficients from the constant term up, without trailing zeros
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Polynomial {
+    coefficients: Vec<Rat>,
+}
+
+impl Polynomial {
+    pub fn new<T: Into<Rat>>(coefficients: impl IntoIterator<Item = T>) -> Self {
+        let mut coefficients = coefficients.into_iter().map(Into::into).collect::<Vec<_>>();
+        while coefficients.last().is_some_and(Rat::is_zero) {
+            coefficients.pop();
+        }
+        Self { coefficients }
+    }
+
+    pub fn coefficients(&self) -> &[Rat] {
+        &self.coefficients
+    }
+
+    /// `None` for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn checked_eval(&self, x: impl Into<Rat>) -> Option<Rat> {
+        let x = x.into();
+        self.coefficients
+            .iter()
+            .rev()
+            .try_fold(Rat::ZERO, |acc, c| acc.checked_mul(x)?.checked_add(*c))
+    }
+
+    pub fn eval(&self, x: impl Into<Rat>) -> Rat {
+        self.checked_eval(x)
+            .expect("Rat polynomial evaluation overflowed")
+    }
+
+    /// The value when it is an integer
+    pub fn eval_integer(&self, x: impl Into<Rat>) -> Option<i128> {
+        self.checked_eval(x)?.try_into().ok()
+    }
+
+    /// Lagrange polynomial of the lowest degree through the points
+    ///
+    /// # Panics
+    /// If two points share `x` or on overflow
+    pub fn interpolate(points: &[(Rat, Rat)]) -> Self {
+        let mut coefficients = vec![Rat::ZERO; points.len()];
+        for (i, (xi, yi)) in points.iter().enumerate() {
+            // prod (x - xj) / (xi - xj) over the other points
+            let mut basis = vec![Rat::ONE];
+            let mut denominator = Rat::ONE;
+            for (xj, _) in points
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, p)| p)
+            {
+                assert!(xi != xj, "points share x = {xi:?}");
+                basis.push(Rat::ZERO);
+                for k in (0..basis.len()).rev() {
+                    let lower = if k > 0 { basis[k - 1] } else { Rat::ZERO };
+                    basis[k] = lower - basis[k] * *xj;
+                }
+                denominator *= *xi - *xj;
+            }
+            let scale = *yi / denominator;
+            for (c, b) in coefficients.iter_mut().zip(basis) {
+                *c += b * scale;
+            }
+        }
+        Self::new(coefficients)
+    }
+
+    /// Polynomial `p` with `p(i) = values[i]`, `None` unless the finite
+    /// differences show a degree that leaves values to confirm it
+    pub fn from_sequence(values: &[Rat]) -> Option<Self> {
+        let degree = DifferenceTable::new(values).degree()?;
+        let points = values[..=degree]
+            .iter()
+            .enumerate()
+            .map(|(i, y)| (Rat::from(i), *y))
+            .collect::<Vec<_>>();
+        Some(Self::interpolate(&points))
+    }
+}
+
+/// Values followed by their repeated differences, down to a row of zeros
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifferenceTable {
+    rows: Vec<Vec<Rat>>,
+}
+
+impl DifferenceTable {
+    pub fn new(values: &[Rat]) -> Self {
+        let mut rows = vec![values.to_vec()];
+        while let Some(last) = rows.last() {
+            if last.len() < 2 || last.iter().all(Rat::is_zero) {
+                break;
+            }
+            let next = last.windows(2).map(|w| w[1] - w[0]).collect();
+            rows.push(next);
+        }
+        Self { rows }
+    }
+
+    pub fn rows(&self) -> &[Vec<Rat>] {
+        &self.rows
+    }
+
+    /// Degree of the polynomial through the values, if its differences vanish
+    /// at enough points to confirm it. Zeros count as degree 0.
+    pub fn degree(&self) -> Option<usize> {
+        let last = self.rows.last()?;
+        if !last.iter().all(Rat::is_zero) || last.len() < CONFIRMATIONS {
+            return None;
+        }
+        Some(self.rows.len().saturating_sub(2))
+    }
+
+    /// Value at any index by Newton's forward formula, negative ones go back
+    pub fn extrapolate(&self, index: i128) -> Option<Rat> {
+        let degree = self.degree()?;
+        let k = Rat::from(index);
+        let mut binomial = Rat::ONE;
+        let mut total = Rat::ZERO;
+        for (j, row) in self.rows[..=degree].iter().enumerate() {
+            if j > 0 {
+                let j = Rat::from(j);
+                binomial = binomial
+                    .checked_mul(k.checked_sub(j - Rat::ONE)?)?
+                    .checked_div(j)?;
+            }
+            total = total.checked_add(binomial.checked_mul(row[0])?)?;
+        }
+        Some(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DifferenceTable, Polynomial};
+    use crate::math::Rat;
+
+    fn rats(values: &[i128]) -> Vec<Rat> {
+        values.iter().copied().map(Rat::from).collect()
+    }
+
+    #[test]
+    fn test_interpolate() {
+        // 2x^2 - 3x + 1/2
+        let expected = Polynomial::new([Rat::new(1, 2), Rat::from(-3), Rat::from(2)]);
+        let points = [(-1, 6), (0, 1), (3, 10)]
+            .map(|(x, y): (i128, i128)| (Rat::from(x), Rat::from(y) - Rat::new(1, 2)));
+        let polynomial = Polynomial::interpolate(&points);
+        assert_eq!(polynomial, expected);
+        assert_eq!(polynomial.degree(), Some(2));
+        assert_eq!(polynomial.eval(Rat::new(1, 2)), Rat::new(-1, 2));
+        assert_eq!(polynomial.eval_integer(2), None);
+
+        // Collinear points give a line
+        let points =
+            [(0, 1), (1, 3), (2, 5)].map(|(x, y): (i128, i128)| (Rat::from(x), Rat::from(y)));
+        assert_eq!(Polynomial::interpolate(&points), Polynomial::new([1, 2]));
+        assert_eq!(Polynomial::new([0, 0]).degree(), None);
+    }
+
+    #[test]
+    fn test_sequence() {
+        let values = rats(&[10, 13, 16, 21, 30, 45]);
+        let table = DifferenceTable::new(&values);
+        assert_eq!(table.rows().len(), 5);
+        assert_eq!(table.degree(), Some(3));
+        assert_eq!(table.extrapolate(6), Some(Rat::from(68)));
+        assert_eq!(table.extrapolate(-1), Some(Rat::from(5)));
+
+        let polynomial = Polynomial::from_sequence(&values).unwrap();
+        assert_eq!(polynomial.eval_integer(6), Some(68));
+        // Far points stay exact
+        assert_eq!(
+            polynomial.eval_integer(1_000_000),
+            table.extrapolate(1_000_000).and_then(|x| x.try_into().ok())
+        );
+
+        // Too few values to confirm the degree
+        assert_eq!(Polynomial::from_sequence(&rats(&[1, 4, 9, 16])), None);
+        assert_eq!(
+            Polynomial::from_sequence(&rats(&[1, 4, 9, 16, 25])),
+            Some(Polynomial::new([1, 2, 1]))
+        );
+        assert_eq!(DifferenceTable::new(&rats(&[0, 0])).degree(), Some(0));
+        assert_eq!(
+            DifferenceTable::new(&rats(&[1, 2, 4, 8, 16])).degree(),
+            None
+        );
+    }
+}
@@ -1,27 +1,78 @@
 use std::{
+    cmp::Ordering,
     num::TryFromIntError,
     ops::{AddAssign, DivAssign, RangeInclusive},
 };
 
-use crate::math::{get_gcd, get_gcd_i, get_lcm};
+use crate::math::get_gcd;
 
+/// Exact fraction, operators panic on overflow, `checked_*` methods report it
 #[derive(Copy, Clone)]
 pub struct Rat {
     pub top: i128,
     pub bottom: u128,
 }
 
+/// Signed magnitude with the given sign, `None` if it does not fit
+fn signed(magnitude: u128, negative: bool) -> Option<i128> {
+    if negative {
+        0i128.checked_sub_unsigned(magnitude)
+    } else {
+        i128::try_from(magnitude).ok()
+    }
+}
+
+/// Full 256-bit product as `(high, low)` halves
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a1, a0) = (a >> 64, a & MASK);
+    let (b1, b0) = (b >> 64, b & MASK);
+    let low = a0 * b0;
+    let cross_a = a0 * b1;
+    let cross_b = a1 * b0;
+    let middle = (low >> 64) + (cross_a & MASK) + (cross_b & MASK);
+    let high = a1 * b1 + (cross_a >> 64) + (cross_b >> 64) + (middle >> 64);
+    (high, (low & MASK) | (middle << 64))
+}
+
+/// Exact comparison of `sign * magnitude / bottom` fractions
+fn compare(a: (i128, u128, u128), b: (i128, u128, u128)) -> Ordering {
+    let (a_sign, a_magnitude, a_bottom) = a;
+    let (b_sign, b_magnitude, b_bottom) = b;
+    match a_sign.cmp(&b_sign) {
+        Ordering::Equal => {}
+        ordering => return ordering,
+    }
+    let left = mul_wide(a_magnitude, b_bottom);
+    let right = mul_wide(b_magnitude, a_bottom);
+    if a_sign < 0 {
+        right.cmp(&left)
+    } else {
+        left.cmp(&right)
+    }
+}
+
 impl Rat {
-    #[inline(always)]
-    fn checked_set(&mut self, top: i128, bottom: u128) {
+    fn reduced(top: i128, bottom: u128) -> Self {
         let gcd = get_gcd(top.unsigned_abs(), bottom);
-        if gcd == 1 {
-            self.top = top;
-            self.bottom = bottom;
-        } else {
-            self.top = top / (gcd as i128);
-            self.bottom = bottom / gcd;
+        if gcd <= 1 {
+            return Self { top, bottom };
         }
+        let magnitude = top.unsigned_abs() / gcd;
+        Self {
+            top: signed(magnitude, top < 0).unwrap(),
+            bottom: bottom / gcd,
+        }
+    }
+
+    fn parts(&self) -> (i128, u128, u128) {
+        (self.top.signum(), self.top.unsigned_abs(), self.bottom)
+    }
+}
+
+impl Default for Rat {
+    fn default() -> Self {
+        Self::ZERO
     }
 }
 
@@ -39,21 +90,19 @@ impl Rat {
     pub const ZERO: Self = Self { top: 0, bottom: 1 };
     pub const ONE: Self = Self { top: 1, bottom: 1 };
     pub const MINUS_ONE: Self = Self { top: -1, bottom: 1 };
+    pub const MAX: Self = Self {
+        top: i128::MAX,
+        bottom: 1,
+    };
+    pub const MIN: Self = Self {
+        top: i128::MIN,
+        bottom: 1,
+    };
     pub fn new(top: i128, bottom: u128) -> Self {
         if top == 0 {
             return Self::ZERO;
         }
-        if top == bottom as i128 {
-            return Self::ONE;
-        }
-        if top == -(bottom as i128) {
-            return Self::MINUS_ONE;
-        }
-        let gcd = get_gcd_i(top, bottom as i128);
-        Self {
-            top: top / gcd as i128,
-            bottom: bottom / gcd,
-        }
+        Self::reduced(top, bottom)
     }
     pub fn reverse(&self) -> Rat {
         if self.top >= 0 {
@@ -76,10 +125,36 @@ impl Rat {
     }
 
     pub fn in_range(&self, range: &RangeInclusive<i128>) -> bool {
-        let min = *range.start();
-        let max = *range.end();
-        let bottom = self.bottom as i128;
-        min * bottom <= self.top && self.top <= max * bottom
+        Rat::from(*range.start()) <= *self && *self <= Rat::from(*range.end())
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.bottom == 1
+    }
+
+    /// Largest integer not greater than the number
+    pub fn floor(&self) -> i128 {
+        match i128::try_from(self.bottom) {
+            Ok(bottom) => self.top.div_euclid(bottom),
+            // The magnitude is below one
+            Err(_) => {
+                if self.top < 0 {
+                    -1
+                } else {
+                    0
+                }
+            }
+        }
+    }
+
+    /// Smallest integer not less than the number
+    pub fn ceil(&self) -> i128 {
+        let floor = self.floor();
+        if self.is_integer() {
+            floor
+        } else {
+            floor + 1
+        }
     }
 
     pub fn signum(&self) -> i128 {
@@ -89,6 +164,194 @@ impl Rat {
             _ => -1,
         }
     }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            top: self.top.checked_neg()?,
+            bottom: self.bottom,
+        })
+    }
+
+    /// Only the denominators' cofactors are multiplied in, not the full product
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        if self.bottom == rhs.bottom {
+            return Some(Self::reduced(self.top.checked_add(rhs.top)?, self.bottom));
+        }
+        let gcd = get_gcd(self.bottom, rhs.bottom);
+        let left = self
+            .top
+            .checked_mul(i128::try_from(rhs.bottom / gcd).ok()?)?;
+        let right = rhs
+            .top
+            .checked_mul(i128::try_from(self.bottom / gcd).ok()?)?;
+        let bottom = (self.bottom / gcd).checked_mul(rhs.bottom)?;
+        Some(Self::reduced(left.checked_add(right)?, bottom))
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        if self.bottom == rhs.bottom {
+            return Some(Self::reduced(self.top.checked_sub(rhs.top)?, self.bottom));
+        }
+        self.checked_add(rhs.checked_neg()?)
+    }
+
+    /// Tops and bottoms are cross-reduced before multiplying
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let left_gcd = get_gcd(self.top.unsigned_abs(), rhs.bottom).max(1);
+        let right_gcd = get_gcd(rhs.top.unsigned_abs(), self.bottom).max(1);
+        let top =
+            (self.top.unsigned_abs() / left_gcd).checked_mul(rhs.top.unsigned_abs() / right_gcd)?;
+        let bottom = (self.bottom / right_gcd).checked_mul(rhs.bottom / left_gcd)?;
+        let negative = (self.top < 0) != (rhs.top < 0);
+        Some(Self::reduced(signed(top, negative)?, bottom))
+    }
+
+    /// `None` for zero or if the denominator does not fit into the top
+    pub fn checked_recip(self) -> Option<Self> {
+        if self.top == 0 {
+            return None;
+        }
+        Some(Self {
+            top: signed(self.bottom, self.top < 0)?,
+            bottom: self.top.unsigned_abs(),
+        })
+    }
+
+    /// `None` on division by zero or overflow
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.checked_mul(rhs.checked_recip()?)
+    }
+
+    /// Never fails: results beyond the bounds clamp to [`Rat::MIN`] or [`Rat::MAX`],
+    /// other results whose parts do not fit are rounded towards zero
+    /// to a fraction with a power of two denominator.
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        self.checked_add(rhs)
+            .unwrap_or_else(|| approximate_sum(self.parts(), rhs.parts()))
+    }
+
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        self.checked_sub(rhs).unwrap_or_else(|| {
+            let (sign, magnitude, bottom) = rhs.parts();
+            approximate_sum(self.parts(), (-sign, magnitude, bottom))
+        })
+    }
+
+    pub fn saturating_mul(self, rhs: Self) -> Self {
+        self.checked_mul(rhs)
+            .unwrap_or_else(|| approximate_product(self.parts(), rhs.parts()))
+    }
+
+    /// # Panics
+    /// On division by zero
+    pub fn saturating_div(self, rhs: Self) -> Self {
+        assert!(!rhs.is_zero(), "division by zero");
+        self.checked_div(rhs).unwrap_or_else(|| {
+            let (sign, magnitude, bottom) = rhs.parts();
+            approximate_product(self.parts(), (sign, bottom, magnitude))
+        })
+    }
+}
+
+/// 256-bit unsigned number as `(high, low)` halves
+type Wide = (u128, u128);
+
+fn add_wide(a: Wide, b: Wide) -> Wide {
+    let (low, carry) = a.1.overflowing_add(b.1);
+    (a.0 + b.0 + carry as u128, low)
+}
+
+/// `a - b` modulo `2^256`
+fn sub_wide(a: Wide, b: Wide) -> Wide {
+    let (low, borrow) = a.1.overflowing_sub(b.1);
+    (a.0.wrapping_sub(b.0).wrapping_sub(borrow as u128), low)
+}
+
+/// One step of long division: appends the bit to the remainder
+/// and tells whether the divisor could be subtracted
+fn division_step(remainder: &mut Wide, bit: bool, divisor: Wide) -> bool {
+    let carry = remainder.0 >> 127 == 1;
+    *remainder = (
+        (remainder.0 << 1) | (remainder.1 >> 127),
+        (remainder.1 << 1) | u128::from(bit),
+    );
+    let subtract = carry || *remainder >= divisor;
+    if subtract {
+        *remainder = sub_wide(*remainder, divisor);
+    }
+    subtract
+}
+
+/// Integer part of `top / bottom` with the first 128 bits of the fractional part
+fn divide_wide(top: Wide, bottom: Wide) -> (Wide, u128) {
+    let mut remainder = (0, 0);
+    let mut quotient: Wide = (0, 0);
+    for i in (0..256).rev() {
+        let bit = if i >= 128 {
+            top.0 >> (i - 128) & 1 == 1
+        } else {
+            top.1 >> i & 1 == 1
+        };
+        let next = division_step(&mut remainder, bit, bottom);
+        quotient = (
+            (quotient.0 << 1) | (quotient.1 >> 127),
+            (quotient.1 << 1) | u128::from(next),
+        );
+    }
+    let mut fraction = 0;
+    for _ in 0..128 {
+        fraction = (fraction << 1) | u128::from(division_step(&mut remainder, false, bottom));
+    }
+    (quotient, fraction)
+}
+
+/// Exact fraction if it fits, otherwise the closest one towards zero
+/// with a power of two denominator
+fn approximate(sign: i128, top: Wide, bottom: Wide) -> Rat {
+    if top.0 == 0 && bottom.0 == 0 && top.1 <= i128::MAX as u128 {
+        return Rat::new(signed(top.1, sign < 0).unwrap(), bottom.1);
+    }
+    let (integer, fraction) = divide_wide(top, bottom);
+    if integer.0 != 0 || integer.1 > i128::MAX as u128 {
+        return if sign < 0 { Rat::MIN } else { Rat::MAX };
+    }
+    let integer = integer.1;
+    // As many fractional bits as keep the top below 2^127
+    let shift = integer.leading_zeros() - 1;
+    let top = if shift == 0 {
+        integer
+    } else {
+        (integer << shift) | (fraction >> (128 - shift))
+    };
+    Rat::new(signed(top, sign < 0).unwrap(), 1 << shift)
+}
+
+fn approximate_product(a: (i128, u128, u128), b: (i128, u128, u128)) -> Rat {
+    let (a_sign, a_magnitude, a_bottom) = a;
+    let (b_sign, b_magnitude, b_bottom) = b;
+    let left_gcd = get_gcd(a_magnitude, b_bottom).max(1);
+    let right_gcd = get_gcd(b_magnitude, a_bottom).max(1);
+    approximate(
+        a_sign * b_sign,
+        mul_wide(a_magnitude / left_gcd, b_magnitude / right_gcd),
+        mul_wide(a_bottom / right_gcd, b_bottom / left_gcd),
+    )
+}
+
+fn approximate_sum(a: (i128, u128, u128), b: (i128, u128, u128)) -> Rat {
+    let (a_sign, a_magnitude, a_bottom) = a;
+    let (b_sign, b_magnitude, b_bottom) = b;
+    let gcd = get_gcd(a_bottom, b_bottom).max(1);
+    let left = mul_wide(a_magnitude, b_bottom / gcd);
+    let right = mul_wide(b_magnitude, a_bottom / gcd);
+    let bottom = mul_wide(a_bottom / gcd, b_bottom);
+    if a_sign == b_sign {
+        approximate(a_sign, add_wide(left, right), bottom)
+    } else if left >= right {
+        approximate(a_sign, sub_wide(left, right), bottom)
+    } else {
+        approximate(b_sign, sub_wide(right, left), bottom)
+    }
 }
 
 impl std::ops::Neg for Rat {
@@ -109,9 +372,7 @@ impl std::cmp::PartialOrd for Rat {
 }
 impl std::cmp::Ord for Rat {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let a = self.top * other.bottom as i128;
-        let b = other.top * self.bottom as i128;
-        a.cmp(&b)
+        compare(self.parts(), other.parts())
     }
 }
 
@@ -143,35 +404,15 @@ impl std::ops::Mul for Rat {
 
 impl std::ops::AddAssign for Rat {
     fn add_assign(&mut self, rhs: Self) {
-        if self.bottom == rhs.bottom {
-            self.checked_set(self.top + rhs.top, self.bottom);
-            return;
-        }
-        let bottom = get_lcm(self.bottom, rhs.bottom);
-        let top =
-            self.top * ((bottom / self.bottom) as i128) + rhs.top * ((bottom / rhs.bottom) as i128);
-        self.checked_set(top, bottom);
+        *self = self.checked_add(rhs).expect("Rat addition overflowed");
     }
 }
 
-macro_rules! happy_path_mul {
-    ($a:expr, $b:expr, $d:expr) => {
-        if $a.bottom == 1 {
-            $d.checked_set($a.top * $b.top, $b.bottom);
-            return;
-        }
-    };
-}
 impl std::ops::MulAssign for Rat {
     fn mul_assign(&mut self, rhs: Self) {
-        happy_path_mul!(self, rhs, self);
-        happy_path_mul!(rhs, self, self);
-
-        let left_g = get_gcd_i(self.top, rhs.bottom as i128) as i128;
-        let right_g = get_gcd_i(self.bottom as i128, rhs.top);
-        let top = self.top / left_g * (rhs.top / (right_g as i128));
-        let bottom = self.bottom / right_g * (rhs.bottom / left_g as u128);
-        self.checked_set(top, bottom);
+        *self = self
+            .checked_mul(rhs)
+            .expect("Rat multiplication overflowed");
     }
 }
 
@@ -186,13 +427,10 @@ impl std::ops::Div for Rat {
 impl std::ops::DivAssign for Rat {
     fn div_assign(&mut self, rhs: Self) {
         if rhs.top == 0 && self.top == 0 {
-            self.top = 1;
-            self.bottom = 1;
+            *self = Self::ONE;
         } else {
-            let new_sign = self.signum() * rhs.signum();
-            let top = self.top.abs() * (rhs.bottom as i128) * new_sign;
-            let bottom = self.bottom * (rhs.top.unsigned_abs());
-            self.checked_set(top, bottom);
+            assert!(rhs.top != 0, "Rat division by zero");
+            *self = self.checked_div(rhs).expect("Rat division overflowed");
         }
     }
 }
@@ -200,24 +438,15 @@ impl std::ops::DivAssign for Rat {
 impl std::ops::Sub for Rat {
     type Output = Self;
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        let new_bottom = get_lcm(self.bottom, rhs.bottom);
-        let top = self.top * ((new_bottom / self.bottom) as i128)
-            - rhs.top * ((new_bottom / rhs.bottom) as i128);
-        Self::new(top, new_bottom)
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        self -= rhs;
+        self
     }
 }
 
 impl std::ops::SubAssign for Rat {
     fn sub_assign(&mut self, rhs: Self) {
-        if self.bottom == rhs.bottom {
-            self.checked_set(self.top - rhs.top, self.bottom);
-            return;
-        }
-        let bottom = get_lcm(self.bottom, rhs.bottom);
-        let top =
-            self.top * ((bottom / self.bottom) as i128) - rhs.top * ((bottom / rhs.bottom) as i128);
-        self.checked_set(top, bottom);
+        *self = self.checked_sub(rhs).expect("Rat subtraction overflowed");
     }
 }
 
@@ -280,10 +509,96 @@ impl TryFrom<Rat> for i128 {
 
 impl std::cmp::PartialEq for Rat {
     fn eq(&self, other: &Self) -> bool {
-        let new_bottom = get_lcm(self.bottom, other.bottom);
-        let a = self.top * ((new_bottom / self.bottom) as i128);
-        let b = other.top * ((new_bottom / other.bottom) as i128);
-        a == b
+        self.cmp(other).is_eq()
     }
 }
 impl std::cmp::Eq for Rat {}
+
+#[cfg(test)]
+mod tests {
+    use super::Rat;
+
+    #[test]
+    fn test_cross_reduction() {
+        let big = Rat::new(i128::MAX / 3, 7);
+        let small = Rat::new(7, i128::MAX as u128 / 3);
+        assert_eq!(big * small, Rat::ONE);
+        assert_eq!(big.checked_mul(big), None);
+        assert_eq!(
+            Rat::new(1, u128::MAX / 2).checked_add(Rat::new(1, u128::MAX / 2)),
+            Some(Rat::new(2, u128::MAX / 2))
+        );
+    }
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(Rat::MAX.checked_add(Rat::ONE), None);
+        assert_eq!(Rat::MIN.checked_sub(Rat::ONE), None);
+        assert_eq!(Rat::MIN.checked_neg(), None);
+        assert_eq!(Rat::ONE.checked_div(Rat::ZERO), None);
+        assert_eq!(
+            Rat::new(3, 4).checked_div(Rat::new(-9, 2)),
+            Some(Rat::new(-1, 6))
+        );
+        assert_eq!(
+            Rat::new(1, 6).checked_sub(Rat::new(1, 10)),
+            Some(Rat::new(1, 15))
+        );
+    }
+
+    #[test]
+    fn test_saturating() {
+        assert_eq!(Rat::MAX.saturating_add(Rat::ONE), Rat::MAX);
+        assert_eq!(Rat::MIN.saturating_sub(Rat::ONE), Rat::MIN);
+        assert_eq!(
+            Rat::MAX.saturating_mul(Rat::MINUS_ONE * Rat::from(2)),
+            Rat::MIN
+        );
+        assert_eq!(Rat::MIN.saturating_div(Rat::new(1, 2)), Rat::MIN);
+        assert_eq!(
+            Rat::new(1, 2).saturating_add(Rat::new(1, 3)),
+            Rat::new(5, 6)
+        );
+
+        // 2^127 / 3 is in range but its top is not, 2^127 = 3 * third + 2
+        let third = (i128::MAX - 1) / 3;
+        let sum = Rat::new(i128::MAX, 3).saturating_add(Rat::new(1, 3));
+        assert_eq!(sum, Rat::new(2 * third + 1, 2));
+        assert_eq!(sum.floor(), third);
+        // The negative top still fits
+        assert_eq!(
+            Rat::new(-i128::MAX, 3).saturating_sub(Rat::new(1, 3)),
+            Rat::new(i128::MIN, 3)
+        );
+        // A denominator which does not fit rounds towards zero
+        let tiny = Rat::new(1, u128::MAX).saturating_mul(Rat::new(1, 3));
+        assert_eq!(tiny, Rat::ZERO);
+        // Two fractional bits fit next to the integer part of 5 * (2^127 - 1) / 21
+        let product = Rat::new(i128::MAX, 7).saturating_mul(Rat::new(5, 3));
+        assert_eq!(product.floor(), i128::MAX / 21 * 5);
+        assert!(product.bottom <= 4);
+    }
+
+    #[test]
+    fn test_cmp_does_not_overflow() {
+        let a = Rat::new(i128::MAX, 3);
+        let b = Rat::new(i128::MAX - 1, 3);
+        assert!(a > b);
+        assert!(-a < -b);
+        assert!(Rat::new(-1, u128::MAX) < Rat::ZERO);
+        assert!(Rat::new(5, 2).in_range(&(2..=3)));
+        assert!(!Rat::new(7, 2).in_range(&(2..=3)));
+    }
+
+    #[test]
+    fn test_rounding() {
+        assert_eq!(Rat::new(7, 2).floor(), 3);
+        assert_eq!(Rat::new(7, 2).ceil(), 4);
+        assert_eq!(Rat::new(-7, 2).floor(), -4);
+        assert_eq!(Rat::new(-7, 2).ceil(), -3);
+        assert_eq!(Rat::from(-3).floor(), -3);
+        assert_eq!(Rat::from(-3).ceil(), -3);
+        assert_eq!(Rat::new(-1, u128::MAX).floor(), -1);
+        assert_eq!(Rat::new(1, u128::MAX).ceil(), 1);
+    }
+}
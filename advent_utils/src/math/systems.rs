@@ -1,39 +1,62 @@
-use crate::math::Rat;
+use crate::math::{Field, Rat};
 
 use thiserror::Error;
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
 pub enum SolveError {
     #[error("lefts should have the same len as rights")]
     IncompatibleLeftAndRight,
     #[error("failed to determine the variable at index {0}")]
     CannotSolveFor(usize),
+    #[error("the numbers overflowed during the elimination")]
+    Overflow,
+    #[error("the matrix is not square")]
+    NotSquare,
+    #[error("the matrix is singular")]
+    Singular,
+    #[error("the system has no solutions")]
+    Inconsistent,
 }
 
-pub struct Equations<'t> {
-    pub lefts: &'t mut [&'t mut [Rat]],
-    pub rights: &'t mut [Rat],
+pub struct Equations<'t, T = Rat> {
+    pub lefts: &'t mut [&'t mut [T]],
+    pub rights: &'t mut [T],
 }
 
-impl Equations<'_> {
-    fn multiply_row(&mut self, row_index: usize, k: &Rat) {
-        let vars = self.lefts[0].len();
-        for j in 0..vars {
-            self.lefts[row_index][j] *= *k;
+impl<T: Field> Equations<'_, T> {
+    fn multiply_row(&mut self, row_index: usize, k: &T) -> Result<(), SolveError> {
+        for value in self.lefts[row_index].iter_mut() {
+            *value = value.checked_mul(k).ok_or(SolveError::Overflow)?;
         }
-        self.rights[row_index] *= *k;
+        let right = &mut self.rights[row_index];
+        *right = right.checked_mul(k).ok_or(SolveError::Overflow)?;
+        Ok(())
     }
 
-    fn sub_row(&mut self, row_index: usize, other_row_index: usize) {
+    /// Subtracts `k` times the other row
+    fn sub_row(
+        &mut self,
+        row_index: usize,
+        other_row_index: usize,
+        k: &T,
+    ) -> Result<(), SolveError> {
         let vars = self.lefts[0].len();
         for j in 0..vars {
-            let new_value = self.lefts[row_index][j] - self.lefts[other_row_index][j];
-            self.lefts[row_index][j] = new_value;
+            let scaled = self.lefts[other_row_index][j]
+                .checked_mul(k)
+                .ok_or(SolveError::Overflow)?;
+            let value = &mut self.lefts[row_index][j];
+            *value = value.checked_sub(&scaled).ok_or(SolveError::Overflow)?;
         }
-
-        self.rights[row_index] -= self.rights[other_row_index];
+        let scaled = self.rights[other_row_index]
+            .checked_mul(k)
+            .ok_or(SolveError::Overflow)?;
+        let right = &mut self.rights[row_index];
+        *right = right.checked_sub(&scaled).ok_or(SolveError::Overflow)?;
+        Ok(())
     }
 
+    /// Gauss-Jordan elimination, the first `vars` rights become the values of the variables
     pub fn solve(mut self) -> Result<(), SolveError> {
         let vars = self.lefts[0].len();
         let eqs = self.rights.len();
@@ -52,30 +75,17 @@ impl Equations<'_> {
             else {
                 return Err(SolveError::CannotSolveFor(var_index));
             };
-            self.multiply_row(non_zero_row, &self.lefts[non_zero_row][var_index].reverse());
+            let k = self.lefts[non_zero_row][var_index]
+                .checked_recip()
+                .ok_or(SolveError::Overflow)?;
+            self.multiply_row(non_zero_row, &k)?;
             self.swap_rows(var_index, non_zero_row);
-            for eq_index in (var_index + 1)..eqs {
-                let own_coef = self.lefts[eq_index][var_index];
-                if own_coef.is_zero() {
-                    continue;
-                }
-                let k = own_coef.reverse();
-
-                self.multiply_row(eq_index, &k);
-                self.sub_row(eq_index, var_index);
-            }
-        }
-
-        for var_index in (0..vars).rev() {
-            for eq in (0..var_index).rev() {
-                let own_coef = self.lefts[eq][var_index];
-                if own_coef.is_zero() {
+            for eq_index in 0..eqs {
+                let own_coef = self.lefts[eq_index][var_index].clone();
+                if eq_index == var_index || own_coef.is_zero() {
                     continue;
                 }
-                let k = own_coef.reverse();
-                self.multiply_row(var_index, &own_coef);
-                self.sub_row(eq, var_index);
-                self.multiply_row(var_index, &k);
+                self.sub_row(eq_index, var_index, &own_coef)?;
             }
         }
         Ok(())
@@ -85,29 +95,74 @@ impl Equations<'_> {
         if var_index == non_zero_row {
             return;
         }
-        let vars = self.lefts[0].len();
-        for j in 0..vars {
-            let tmp = self.lefts[var_index][j];
-            self.lefts[var_index][j] = self.lefts[non_zero_row][j];
-            self.lefts[non_zero_row][j] = tmp;
-        }
+        self.lefts.swap(var_index, non_zero_row);
         self.rights.swap(var_index, non_zero_row);
     }
 }
 
-pub fn solve_system<const VARS: usize, const EQS: usize>(
-    mut lefts: [[Rat; VARS]; EQS],
-    mut rights: [Rat; EQS],
-) -> Option<[Rat; EQS]> {
-    let mut lefts: [&mut [Rat]; EQS] =
+/// Solution of the system, [`SolveError::Overflow`] tells an overflow apart
+/// from a solution which is not unique
+pub fn solve_system<T: Field, const VARS: usize, const EQS: usize>(
+    mut lefts: [[T; VARS]; EQS],
+    mut rights: [T; EQS],
+) -> Result<[T; EQS], SolveError> {
+    let mut lefts: [&mut [T]; EQS] =
         array_init::from_iter(lefts.iter_mut().map(|x| x.as_mut())).unwrap();
     let rights_mut = rights.as_mut();
     Equations {
         lefts: &mut lefts,
         rights: rights_mut,
     }
-    .solve()
-    .ok()?;
+    .solve()?;
+
+    Ok(rights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve_system, SolveError};
+    use crate::math::Rat;
+
+    #[test]
+    fn test_solve_system() {
+        let solution = solve_system(
+            [
+                [Rat::from(2), Rat::from(1), Rat::from(-1)],
+                [Rat::from(-3), Rat::from(-1), Rat::from(2)],
+                [Rat::from(-2), Rat::from(1), Rat::from(2)],
+            ],
+            [Rat::from(8), Rat::from(-11), Rat::from(-3)],
+        );
+        assert_eq!(solution, Ok([Rat::from(2), Rat::from(3), Rat::from(-1)]));
 
-    Some(rights)
+        let singular = solve_system(
+            [[Rat::from(1), Rat::from(2)], [Rat::from(2), Rat::from(4)]],
+            [Rat::from(3), Rat::from(6)],
+        );
+        assert_eq!(singular, Err(SolveError::CannotSolveFor(1)));
+    }
+
+    #[test]
+    fn test_overflow_is_reported() {
+        let huge = Rat::from(i128::MAX / 2);
+        let lefts = [[huge, Rat::ONE], [Rat::ONE, huge]];
+        let rights = [Rat::ONE, Rat::from(2)];
+        assert_eq!(solve_system(lefts, rights), Err(SolveError::Overflow));
+
+        #[cfg(feature = "bigint")]
+        {
+            use crate::math::BigRat;
+            let solution = solve_system(
+                lefts.map(|row| row.map(BigRat::from)),
+                rights.map(BigRat::from),
+            )
+            .expect("the system has a unique solution");
+            let x = &solution[0];
+            let y = &solution[1];
+            let huge = BigRat::from(huge);
+            assert_eq!(&huge * x + y, BigRat::from(Rat::ONE));
+            assert_eq!(x + &huge * y, BigRat::from(Rat::from(2)));
+            assert!(Rat::try_from(x).is_err());
+        }
+    }
 }
@@ -0,0 +1,228 @@
+//! Memoization for recursive counting and minimization.
+//!
+//! A [`Memo`] is passed down the recursion explicitly instead of living in a
+//! static, so cached functions may borrow from the puzzle input.
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+};
+
+/// Storage of the cached values
+pub trait MemoStore<K, V> {
+    fn get(&self, key: &K) -> Option<&V>;
+    fn insert(&mut self, key: K, value: V);
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> MemoStore<K, V> for HashMap<K, V, S> {
+    fn get(&self, key: &K) -> Option<&V> {
+        HashMap::get(self, key)
+    }
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+/// Values in a `Vec` for keys with a small dense index
+#[derive(Debug, Clone)]
+pub struct DenseStore<K, V> {
+    values: Vec<Option<V>>,
+    index: fn(&K) -> usize,
+    len: usize,
+}
+
+impl<K, V: Clone> DenseStore<K, V> {
+    /// Space for `size` keys is reserved upfront, larger indices grow the storage
+    pub fn new(size: usize, index: fn(&K) -> usize) -> Self {
+        Self {
+            values: vec![None; size],
+            index,
+            len: 0,
+        }
+    }
+}
+
+impl<K, V: Clone> MemoStore<K, V> for DenseStore<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.values.get((self.index)(key))?.as_ref()
+    }
+    fn insert(&mut self, key: K, value: V) {
+        let index = (self.index)(&key);
+        if index >= self.values.len() {
+            self.values.resize(index + 1, None);
+        }
+        if self.values[index].replace(value).is_none() {
+            self.len += 1;
+        }
+    }
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// Cache of a recursive function, keyed by its arguments
+#[derive(Debug, Clone)]
+pub struct Memo<K, V, S = HashMap<K, V>> {
+    store: S,
+    stats: MemoStats,
+    _entries: std::marker::PhantomData<fn(K) -> V>,
+}
+
+impl<K: Hash + Eq, V> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::with_store(HashMap::new())
+    }
+}
+
+impl<K: Hash + Eq, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V: Clone> Memo<K, V, DenseStore<K, V>> {
+    pub fn dense(size: usize, index: fn(&K) -> usize) -> Self {
+        Self::with_store(DenseStore::new(size, index))
+    }
+}
+
+impl<K, V, S: MemoStore<K, V>> Memo<K, V, S> {
+    pub fn with_store(store: S) -> Self {
+        Self {
+            store,
+            stats: MemoStats::default(),
+            _entries: std::marker::PhantomData,
+        }
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    /// Number of cached values
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.store.get(key)
+    }
+
+    /// Cached value of the key or the computed one.
+    ///
+    /// The memo is handed to `f` so it can recurse through it, the key only
+    /// identifies the computation and `f` may capture whatever it needs.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V
+    where
+        V: Clone,
+    {
+        if let Some(value) = self.store.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+        self.stats.misses += 1;
+        let value = f(self);
+        self.store.insert(key, value.clone());
+        value
+    }
+
+    /// Value of a recursive function of the key, `f` recurses with [`Recurse::call`]
+    pub fn call(&mut self, key: K, f: &RecursiveFn<'_, K, V, S>) -> V
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.get_or_insert_with(key.clone(), |memo| f(&mut Recurse { memo, f }, key))
+    }
+}
+
+/// Body of a function memoized by [`Memo::call`]
+pub type RecursiveFn<'f, K, V, S> = dyn Fn(&mut Recurse<'_, K, V, S>, K) -> V + 'f;
+
+/// Handle for the recursive calls of a function memoized by [`Memo::call`]
+pub struct Recurse<'m, K, V, S> {
+    memo: &'m mut Memo<K, V, S>,
+    f: &'m RecursiveFn<'m, K, V, S>,
+}
+
+impl<K: Clone, V: Clone, S: MemoStore<K, V>> Recurse<'_, K, V, S> {
+    pub fn call(&mut self, key: K) -> V {
+        self.memo.call(key, self.f)
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.memo.stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Memo, MemoStats, MemoStore, Recurse};
+
+    fn fib<S: MemoStore<u64, u64>>(rec: &mut Recurse<'_, u64, u64, S>, n: u64) -> u64 {
+        if n < 2 {
+            n
+        } else {
+            rec.call(n - 1) + rec.call(n - 2)
+        }
+    }
+
+    #[test]
+    fn test_recursion() {
+        let mut memo = Memo::new();
+        assert_eq!(memo.call(90, &fib), 2880067194370816120);
+        assert_eq!(memo.len(), 91);
+        assert_eq!(
+            memo.stats(),
+            MemoStats {
+                hits: 88,
+                misses: 91
+            }
+        );
+
+        let mut dense = Memo::dense(10, |n: &u64| *n as usize);
+        assert_eq!(dense.call(90, &fib), 2880067194370816120);
+        assert_eq!(dense.stats(), memo.stats());
+        assert_eq!(dense.get(&10), Some(&55));
+    }
+
+    #[test]
+    fn test_borrowed_context() {
+        fn ways<'t>(memo: &mut Memo<&'t str, usize>, parts: &[&str], word: &'t str) -> usize {
+            if word.is_empty() {
+                return 1;
+            }
+            memo.get_or_insert_with(word, |memo| {
+                parts
+                    .iter()
+                    .filter_map(|part| word.strip_prefix(part))
+                    .map(|rest| ways(memo, parts, rest))
+                    .sum()
+            })
+        }
+        let word = String::from("abcabc");
+        let mut memo = Memo::new();
+        assert_eq!(
+            ways(&mut memo, &["a", "b", "c", "ab", "bc", "abc"], &word),
+            16
+        );
+        assert_eq!(memo.get(&"bc"), Some(&2));
+    }
+}
@@ -0,0 +1,325 @@
+use std::fmt::Display;
+
+use nom::{
+    error::{ContextError, ErrorKind, FromExternalError, ParseError},
+    Parser,
+};
+
+/// Error that knows where it happened and how to describe itself
+pub trait ReportableError<'t> {
+    /// Remaining input at the place of the error
+    fn position(&self) -> &'t str;
+    fn describe(&self) -> String;
+    /// Context labels, the outermost first
+    fn contexts(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+}
+
+impl<'t> ReportableError<'t> for nom::error::Error<&'t str> {
+    fn position(&self) -> &'t str {
+        self.input
+    }
+    fn describe(&self) -> String {
+        format!("parser {:?} failed", self.code.description())
+    }
+}
+
+impl<'t> ReportableError<'t> for (&'t str, ErrorKind) {
+    fn position(&self) -> &'t str {
+        self.0
+    }
+    fn describe(&self) -> String {
+        format!("parser {:?} failed", self.1.description())
+    }
+}
+
+/// Error type which keeps labels of [`nom::error::context`].
+///
+/// Existing parsers can use it by changing their return type to
+/// `IResult<&str, T, LabeledError<'_>>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabeledError<'t> {
+    pub input: &'t str,
+    pub kind: ErrorKind,
+    pub expected: Option<char>,
+    pub external: Option<String>,
+    /// Context labels, the innermost first
+    pub labels: Vec<&'static str>,
+}
+
+impl<'t> ParseError<&'t str> for LabeledError<'t> {
+    fn from_error_kind(input: &'t str, kind: ErrorKind) -> Self {
+        Self {
+            input,
+            kind,
+            expected: None,
+            external: None,
+            labels: Vec::new(),
+        }
+    }
+
+    fn append(_: &'t str, _: ErrorKind, other: Self) -> Self {
+        // The deepest error is the most precise one
+        other
+    }
+
+    fn from_char(input: &'t str, c: char) -> Self {
+        Self {
+            expected: Some(c),
+            ..Self::from_error_kind(input, ErrorKind::Char)
+        }
+    }
+
+    fn or(self, other: Self) -> Self {
+        // Prefer the alternative which got further
+        if other.input.len() <= self.input.len() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl<'t> ContextError<&'t str> for LabeledError<'t> {
+    fn add_context(_: &'t str, ctx: &'static str, mut other: Self) -> Self {
+        other.labels.push(ctx);
+        other
+    }
+}
+
+impl<'t, E: Display> FromExternalError<&'t str, E> for LabeledError<'t> {
+    fn from_external_error(input: &'t str, kind: ErrorKind, e: E) -> Self {
+        Self {
+            external: Some(e.to_string()),
+            ..Self::from_error_kind(input, kind)
+        }
+    }
+}
+
+impl<'t> ReportableError<'t> for LabeledError<'t> {
+    fn position(&self) -> &'t str {
+        self.input
+    }
+    fn describe(&self) -> String {
+        match (&self.external, self.expected) {
+            (Some(e), _) => e.clone(),
+            (None, Some(c)) => format!("expected {c:?}"),
+            (None, None) => format!("parser {:?} failed", self.kind.description()),
+        }
+    }
+    fn contexts(&self) -> Vec<&'static str> {
+        self.labels.iter().rev().copied().collect()
+    }
+}
+
+/// Failure of [`parse_all`] rendered like a compiler error:
+///
+/// ```text
+/// parser "Tag" failed at line 3, column 8
+///   |
+/// 3 | cpy 41 c
+///   |        ^
+///   = while parsing: instruction > register
+/// ```
+///
+/// `Debug` renders the same report, so `.unwrap()` prints it nicely.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParseReport {
+    pub message: String,
+    /// 1-based
+    pub line: usize,
+    /// 1-based, in chars
+    pub column: usize,
+    pub line_text: String,
+    /// Context labels, the outermost first
+    pub contexts: Vec<&'static str>,
+}
+
+impl ParseReport {
+    fn new(input: &str, position: &str, message: String, contexts: Vec<&'static str>) -> Self {
+        let offset = (position.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|offset| *offset <= input.len())
+            .unwrap_or(input.len().saturating_sub(position.len()));
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        Self {
+            message,
+            line: before.matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            line_text: input[line_start..line_end].trim_end().to_string(),
+            contexts,
+        }
+    }
+}
+
+impl Display for ParseReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let gutter = self.line.to_string().len();
+        writeln!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.line, self.column
+        )?;
+        writeln!(f, "{:gutter$} |", "")?;
+        writeln!(f, "{} | {}", self.line, self.line_text)?;
+        write!(f, "{:gutter$} | {:>column$}", "", "^", column = self.column)?;
+        if !self.contexts.is_empty() {
+            write!(
+                f,
+                "\n{:gutter$} = while parsing: {}",
+                "",
+                self.contexts.join(" > ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for ParseReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f)?;
+        Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for ParseReport {}
+
+/// Runs the parser over the whole input.
+///
+/// Only whitespace may remain after the parser,
+/// otherwise the remaining part is reported from its first line.
+///
+/// ```ignore
+/// let instructions = parse_all(parse_instructions, input).unwrap();
+/// ```
+pub fn parse_all<'t, O, E, P>(mut parser: P, input: &'t str) -> Result<O, ParseReport>
+where
+    P: Parser<&'t str, Output = O, Error = E>,
+    E: ReportableError<'t>,
+{
+    match parser.parse(input) {
+        Ok((rest, output)) if rest.trim().is_empty() => Ok(output),
+        Ok((rest, _)) => Err(ParseReport::new(
+            input,
+            rest.trim_start_matches(['\r', '\n']),
+            "expected end of input".to_string(),
+            Vec::new(),
+        )),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(ParseReport::new(
+            input,
+            e.position(),
+            e.describe(),
+            e.contexts(),
+        )),
+        Err(nom::Err::Incomplete(_)) => Err(ParseReport::new(
+            input,
+            &input[input.len()..],
+            "incomplete input".to_string(),
+            Vec::new(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::{
+        branch::alt,
+        bytes::complete::tag,
+        character::complete::{char, i32, line_ending},
+        combinator::{cut, map_res, value},
+        error::context,
+        multi::separated_list1,
+        sequence::preceded,
+        IResult, Parser,
+    };
+
+    use super::{parse_all, LabeledError, ParseReport};
+
+    fn plain_line(input: &str) -> IResult<&str, i32> {
+        preceded(tag("add "), i32).parse(input)
+    }
+
+    fn plain(input: &str) -> IResult<&str, Vec<i32>> {
+        separated_list1(line_ending, plain_line).parse(input)
+    }
+
+    fn register(input: &str) -> IResult<&str, u8, LabeledError<'_>> {
+        context("register", alt((value(0, char('a')), value(1, char('b'))))).parse(input)
+    }
+
+    fn instruction(input: &str) -> IResult<&str, (u8, u8), LabeledError<'_>> {
+        context(
+            "instruction",
+            preceded(tag("cpy "), cut((register, preceded(char(' '), register)))),
+        )
+        .parse(input)
+    }
+
+    fn labeled(input: &str) -> IResult<&str, Vec<(u8, u8)>, LabeledError<'_>> {
+        separated_list1(line_ending, instruction).parse(input)
+    }
+
+    #[test]
+    fn test_success() {
+        assert_eq!(parse_all(plain, "add 1\nadd -2\n"), Ok(vec![1, -2]));
+    }
+
+    #[test]
+    fn test_unconsumed() {
+        let report = parse_all(plain, "add 1\nadd 2\nsub 3\n").unwrap_err();
+        assert_eq!(
+            report,
+            ParseReport {
+                message: "expected end of input".to_string(),
+                line: 3,
+                column: 1,
+                line_text: "sub 3".to_string(),
+                contexts: vec![],
+            }
+        );
+        assert_eq!(
+            report.to_string(),
+            "expected end of input at line 3, column 1\n  |\n3 | sub 3\n  | ^"
+        );
+    }
+
+    #[test]
+    fn test_plain_error() {
+        let report = parse_all(plain_line, "add x").unwrap_err();
+        assert_eq!(
+            report.to_string(),
+            "parser \"Digit\" failed at line 1, column 5\n  |\n1 | add x\n  |     ^"
+        );
+    }
+
+    #[test]
+    fn test_labels() {
+        let report = parse_all(labeled, "cpy a b\ncpy b a\ncpy a c").unwrap_err();
+        assert_eq!(
+            report.to_string(),
+            [
+                "expected 'b' at line 3, column 7",
+                "  |",
+                "3 | cpy a c",
+                "  |       ^",
+                "  = while parsing: instruction > register",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_external_error() {
+        fn byte(input: &str) -> IResult<&str, u8, LabeledError<'_>> {
+            map_res(nom::character::complete::digit1, str::parse::<u8>).parse(input)
+        }
+        let report = parse_all(byte, "300").unwrap_err();
+        assert_eq!(report.message, "number too large to fit in target type");
+        assert_eq!(report.column, 1);
+    }
+}
@@ -0,0 +1,226 @@
+//! Recognition of the block letters some puzzles draw on a screen.
+//!
+//! Two fonts are known: the small one (4x6, 5 pixels apart)
+//! and the large one (6x10, 8 pixels apart).
+//! Letters are found by cropping the lit pixels to their bounding box
+//! and splitting it by empty columns, so the position of the text does not matter.
+use std::collections::HashSet;
+
+use glam::UVec2;
+use thiserror::Error;
+
+use crate::grid::Grid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum OcrError {
+    #[error("there are no lit pixels")]
+    Empty,
+    #[error("letters are 6 or 10 pixels tall, but the text is {0} pixels tall")]
+    UnsupportedHeight(u32),
+    #[error("unrecognized glyphs at {positions:?}, recognized: {text:?}")]
+    Unrecognized {
+        /// Recognized letters, `?` in place of unrecognized ones
+        text: String,
+        /// Top left corners of unrecognized glyphs
+        positions: Vec<UVec2>,
+    },
+}
+
+const SMALL_FONT: &[(char, &[&str])] = &[
+    ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', &["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[rustfmt::skip]
+const LARGE_FONT: &[(char, &[&str])] = &[
+    ('A', &["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', &["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', &[".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', &[".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', &["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', &["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', &["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', &["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', &["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', &["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', &["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', &["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', &["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Recognizes the text drawn by lit pixels
+pub fn ocr_points(points: &HashSet<UVec2>) -> Result<String, OcrError> {
+    recognize(points.iter().copied())
+}
+
+/// Recognizes the text drawn by `true` cells
+pub fn ocr_grid(grid: &Grid<bool>) -> Result<String, OcrError> {
+    recognize(
+        grid.entries()
+            .filter(|(_, lit)| **lit)
+            .map(|(pos, _)| pos.as_uvec2()),
+    )
+}
+
+/// Recognizes the text drawn by `#` in a rendered screen,
+/// like the output of [`Grid::render_ascii`]
+pub fn ocr_str(screen: &str) -> Result<String, OcrError> {
+    recognize(screen.lines().enumerate().flat_map(|(y, line)| {
+        line.chars()
+            .enumerate()
+            .filter(|(_, c)| *c == '#')
+            .map(move |(x, _)| UVec2::new(x as u32, y as u32))
+    }))
+}
+
+fn recognize(points: impl Iterator<Item = UVec2>) -> Result<String, OcrError> {
+    let points: HashSet<UVec2> = points.collect();
+    let min = points
+        .iter()
+        .copied()
+        .reduce(UVec2::min)
+        .ok_or(OcrError::Empty)?;
+    let max = points.iter().copied().fold(min, UVec2::max);
+    let size = max - min + UVec2::ONE;
+    let font = match size.y {
+        6 => SMALL_FONT,
+        10 => LARGE_FONT,
+        h => return Err(OcrError::UnsupportedHeight(h)),
+    };
+    let is_lit = |x: u32, y: u32| points.contains(&(min + UVec2::new(x, y)));
+    let is_empty_column = |x: u32| x >= size.x || (0..size.y).all(|y| !is_lit(x, y));
+    let matches = |x: u32, glyph: &[&str]| {
+        let width = glyph[0].len() as u32;
+        x + width <= size.x
+            && is_empty_column(x + width)
+            && glyph.iter().enumerate().all(|(y, row)| {
+                row.bytes()
+                    .enumerate()
+                    .all(|(dx, b)| (b == b'#') == is_lit(x + dx as u32, y as u32))
+            })
+    };
+
+    let mut text = String::new();
+    let mut positions = Vec::new();
+    let mut x = 0;
+    while x < size.x {
+        if is_empty_column(x) {
+            x += 1;
+            continue;
+        }
+        match font.iter().find(|(_, glyph)| matches(x, glyph)) {
+            Some((letter, glyph)) => {
+                text.push(*letter);
+                x += glyph[0].len() as u32;
+            }
+            None => {
+                text.push('?');
+                positions.push(min + UVec2::new(x, 0));
+                while !is_empty_column(x) {
+                    x += 1;
+                }
+            }
+        }
+    }
+
+    if positions.is_empty() {
+        Ok(text)
+    } else {
+        Err(OcrError::Unrecognized { text, positions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use glam::{IVec2, UVec2};
+
+    use super::{ocr_grid, ocr_points, ocr_str, OcrError, LARGE_FONT};
+    use crate::grid::Grid;
+
+    const SCREEN: &str = "
+.##..####.###..#..#.###..####.###....##.###...###.
+#..#.#....#..#.#..#.#..#....#.#..#....#.#..#.#....
+#..#.###..###..#..#.#..#...#..###.....#.#..#.#....
+####.#....#..#.#..#.###...#...#..#....#.###...##..
+#..#.#....#..#.#..#.#....#....#..#.#..#.#.......#.
+#..#.#....###...##..#....####.###...##..#....###..
+";
+
+    #[test]
+    fn test_small_font() {
+        assert_eq!(ocr_str(SCREEN), Ok("AFBUPZBJPS".to_string()));
+    }
+
+    #[test]
+    fn test_points_anywhere() {
+        let shift = UVec2::new(17, 3);
+        let points: HashSet<UVec2> = SCREEN
+            .lines()
+            .skip(1)
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.match_indices('#')
+                    .map(move |(x, _)| UVec2::new(x as u32, y as u32) + shift)
+            })
+            .collect();
+        assert_eq!(ocr_points(&points), Ok("AFBUPZBJPS".to_string()));
+    }
+
+    #[test]
+    fn test_large_font() {
+        let mut grid = Grid::new(IVec2::new(8 * LARGE_FONT.len() as i32 + 4, 12), false);
+        for (i, (_, glyph)) in LARGE_FONT.iter().enumerate() {
+            for (y, row) in glyph.iter().enumerate() {
+                for (x, _) in row.match_indices('#') {
+                    grid.set_at(y + 1, 8 * i + x + 2, true);
+                }
+            }
+        }
+        assert_eq!(ocr_grid(&grid), Ok("ABCEFGHJKLNPRXZ".to_string()));
+    }
+
+    #[test]
+    fn test_unrecognized() {
+        let screen = "
+.##..#..#.###.
+#..#.##.#.#..#
+#..#.#.##.###.
+####.#..#.#..#
+#..#.#..#.#..#
+#..#.#..#.###.
+";
+        assert_eq!(
+            ocr_str(screen),
+            Err(OcrError::Unrecognized {
+                text: "A?B".to_string(),
+                positions: vec![UVec2::new(5, 1)],
+            })
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(ocr_str("...\n..."), Err(OcrError::Empty));
+        assert_eq!(ocr_str("#\n#\n#"), Err(OcrError::UnsupportedHeight(3)));
+    }
+}
@@ -1,3 +1,6 @@
+mod checked_nums;
+mod sections;
+
 use core::str;
 use std::marker::PhantomData;
 
@@ -6,6 +9,12 @@ use nom::{AsBytes, AsChar};
 
 use crate::grid::Grid;
 
+pub use checked_nums::{
+    checked_nums, num_ranges, nums_array, CheckedNum, CheckedNumsIter, NumRangesIter, NumToken,
+    NumsOptions, ParseNumError, ParseNumErrorKind, SignPolicy,
+};
+pub use sections::{sections, sections_by_header, Section, SectionError, Sections};
+
 /// Trims a string and returns a grid built over it.
 pub fn ascii_grid(file_content: &str) -> Grid<u8> {
     file_content
@@ -24,6 +33,10 @@ impl<T: Iterator<Item = u8>> OnlyDecDigits for T {
     }
 }
 
+/// Iterates over numbers of the line, panics on overflow.
+///
+/// Minus signs are dropped for unsigned types,
+/// see [`checked_nums`] and [`NumsOptions`] for the non-panicking version.
 pub fn nums<T>(line: &str) -> ParseNumsIter<'_, T> {
     ParseNumsIter {
         bytes: line.as_bytes(),
@@ -0,0 +1,504 @@
+use std::{marker::PhantomData, ops::RangeInclusive};
+
+use thiserror::Error;
+
+/// What to do with `-` and `+` in front of the digits
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum SignPolicy {
+    /// `-` and `+` are separators, numbers are never negative
+    Ignore,
+    /// `-` directly before a digit makes the number negative.
+    /// Negative numbers are errors for unsigned types.
+    #[default]
+    Minus,
+    /// Same as `Minus`, but a `+` directly before a digit is also allowed
+    PlusMinus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseNumErrorKind {
+    #[error("{text} does not fit into {ty}")]
+    Overflow { text: String, ty: &'static str },
+    #[error("{text} is negative, but {ty} is unsigned")]
+    Negative { text: String, ty: &'static str },
+    #[error("{0} is not a valid float")]
+    InvalidFloat(String),
+    #[error("expected {expected} numbers, found {found}")]
+    Count { expected: usize, found: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{kind} (at byte {offset})")]
+pub struct ParseNumError {
+    /// Byte offset of the number in the parsed line
+    pub offset: usize,
+    pub kind: ParseNumErrorKind,
+}
+
+/// Number found in the line
+pub struct NumToken<'t> {
+    /// Whole number as it is written: sign, radix prefix and digits
+    pub text: &'t str,
+    pub negative: bool,
+    pub radix: u32,
+    /// Digits without sign and radix prefix
+    pub digits: &'t [u8],
+}
+
+pub trait CheckedNum: Sized {
+    /// Floats consume the fraction and the exponent
+    const FLOAT: bool = false;
+    fn from_token(token: &NumToken<'_>) -> Result<Self, ParseNumErrorKind>;
+}
+
+macro_rules! checked_uint {
+    ($($typ:ty),+) => {
+        $(
+            impl CheckedNum for $typ {
+                fn from_token(token: &NumToken<'_>) -> Result<Self, ParseNumErrorKind> {
+                    let overflow = || ParseNumErrorKind::Overflow {
+                        text: token.text.to_string(),
+                        ty: stringify!($typ),
+                    };
+                    let mut res: $typ = 0;
+                    for d in token.digits {
+                        let digit = char::from(*d).to_digit(token.radix).unwrap() as $typ;
+                        res = res
+                            .checked_mul(token.radix as $typ)
+                            .and_then(|x| x.checked_add(digit))
+                            .ok_or_else(overflow)?;
+                    }
+                    if token.negative && res != 0 {
+                        return Err(ParseNumErrorKind::Negative {
+                            text: token.text.to_string(),
+                            ty: stringify!($typ),
+                        });
+                    }
+                    Ok(res)
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! checked_int {
+    ($($typ:ty),+) => {
+        $(
+            impl CheckedNum for $typ {
+                fn from_token(token: &NumToken<'_>) -> Result<Self, ParseNumErrorKind> {
+                    let overflow = || ParseNumErrorKind::Overflow {
+                        text: token.text.to_string(),
+                        ty: stringify!($typ),
+                    };
+                    let mut res: $typ = 0;
+                    for d in token.digits {
+                        let digit = char::from(*d).to_digit(token.radix).unwrap() as $typ;
+                        let shifted = res.checked_mul(token.radix as $typ);
+                        // Accumulating negative numbers downwards allows parsing MIN
+                        res = if token.negative {
+                            shifted.and_then(|x| x.checked_sub(digit))
+                        } else {
+                            shifted.and_then(|x| x.checked_add(digit))
+                        }
+                        .ok_or_else(overflow)?;
+                    }
+                    Ok(res)
+                }
+            }
+        )+
+    };
+}
+
+checked_uint! {u128, u64, u32, u16, u8, usize}
+checked_int! {i128, i64, i32, i16, i8, isize}
+
+macro_rules! checked_float {
+    ($($typ:ty),+) => {
+        $(
+            impl CheckedNum for $typ {
+                const FLOAT: bool = true;
+                fn from_token(token: &NumToken<'_>) -> Result<Self, ParseNumErrorKind> {
+                    token
+                        .text
+                        .parse()
+                        .map_err(|_| ParseNumErrorKind::InvalidFloat(token.text.to_string()))
+                }
+            }
+        )+
+    };
+}
+
+checked_float! {f64, f32}
+
+/// Options of the checked number extraction.
+///
+/// ```ignore
+/// let [a, b] = NumsOptions::new()
+///     .radix_prefixes(true)
+///     .array::<u32, 2>("mask: 0xff, value: 0b101")?;
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct NumsOptions {
+    sign: SignPolicy,
+    radix_prefixes: bool,
+}
+
+impl NumsOptions {
+    pub const fn new() -> Self {
+        Self {
+            sign: SignPolicy::Minus,
+            radix_prefixes: false,
+        }
+    }
+    pub const fn sign(mut self, sign: SignPolicy) -> Self {
+        self.sign = sign;
+        self
+    }
+    /// Enables `0x`, `0o` and `0b` prefixed integers
+    pub const fn radix_prefixes(mut self, enabled: bool) -> Self {
+        self.radix_prefixes = enabled;
+        self
+    }
+    pub fn nums<T: CheckedNum>(self, line: &str) -> CheckedNumsIter<'_, T> {
+        CheckedNumsIter {
+            line,
+            pos: 0,
+            options: self,
+            phantom: PhantomData,
+        }
+    }
+    pub fn array<T: CheckedNum, const N: usize>(self, line: &str) -> Result<[T; N], ParseNumError> {
+        self.nums(line).into_array()
+    }
+    pub fn ranges<T: CheckedNum>(self, line: &str) -> NumRangesIter<'_, T> {
+        NumRangesIter {
+            nums: self.nums(line),
+        }
+    }
+
+    fn is_sign(&self, c: u8) -> bool {
+        match self.sign {
+            SignPolicy::Ignore => false,
+            SignPolicy::Minus => c == b'-',
+            SignPolicy::PlusMinus => c == b'-' || c == b'+',
+        }
+    }
+
+    /// Returns the next number and the offset of its first byte
+    fn next_token<'t>(
+        &self,
+        line: &'t str,
+        mut pos: usize,
+        float: bool,
+    ) -> Option<(usize, NumToken<'t>)> {
+        let bytes = line.as_bytes();
+        let is_digit_at = |i: usize| bytes.get(i).is_some_and(u8::is_ascii_digit);
+        let start = loop {
+            let c = *bytes.get(pos)?;
+            if c.is_ascii_digit() {
+                break pos;
+            }
+            if self.is_sign(c) && is_digit_at(pos + 1) {
+                break pos;
+            }
+            pos += 1;
+        };
+        let negative = bytes[start] == b'-';
+        if !bytes[start].is_ascii_digit() {
+            pos += 1;
+        }
+
+        let mut radix = 10;
+        if self.radix_prefixes && !float && bytes[pos] == b'0' {
+            let prefixed = match bytes.get(pos + 1) {
+                Some(b'x' | b'X') => 16,
+                Some(b'o' | b'O') => 8,
+                Some(b'b' | b'B') => 2,
+                _ => 10,
+            };
+            let has_digit = bytes
+                .get(pos + 2)
+                .is_some_and(|c| char::from(*c).is_digit(prefixed));
+            if prefixed != 10 && has_digit {
+                radix = prefixed;
+                pos += 2;
+            }
+        }
+
+        let digits_start = pos;
+        while bytes
+            .get(pos)
+            .is_some_and(|c| char::from(*c).is_digit(radix))
+        {
+            pos += 1;
+        }
+        let digits_end = pos;
+
+        if float {
+            if bytes.get(pos) == Some(&b'.') && is_digit_at(pos + 1) {
+                pos += 1;
+                while is_digit_at(pos) {
+                    pos += 1;
+                }
+            }
+            if matches!(bytes.get(pos), Some(b'e' | b'E')) {
+                let exp_digit = if matches!(bytes.get(pos + 1), Some(b'-' | b'+')) {
+                    pos + 2
+                } else {
+                    pos + 1
+                };
+                if is_digit_at(exp_digit) {
+                    pos = exp_digit;
+                    while is_digit_at(pos) {
+                        pos += 1;
+                    }
+                }
+            }
+        }
+
+        Some((
+            start,
+            NumToken {
+                text: &line[start..pos],
+                negative,
+                radix,
+                digits: &bytes[digits_start..digits_end],
+            },
+        ))
+    }
+}
+
+/// Like [`super::nums`], but returns errors instead of panicking
+/// and does not drop minus signs of negative numbers for unsigned types.
+pub fn checked_nums<T: CheckedNum>(line: &str) -> CheckedNumsIter<'_, T> {
+    NumsOptions::new().nums(line)
+}
+
+/// Returns exactly `N` numbers of the line or an error
+pub fn nums_array<T: CheckedNum, const N: usize>(line: &str) -> Result<[T; N], ParseNumError> {
+    NumsOptions::new().array(line)
+}
+
+/// Parses ranges like `3-7`, standalone numbers are returned as `n..=n`
+pub fn num_ranges<T: CheckedNum>(line: &str) -> NumRangesIter<'_, T> {
+    NumsOptions::new().ranges(line)
+}
+
+pub struct CheckedNumsIter<'t, T> {
+    line: &'t str,
+    pos: usize,
+    options: NumsOptions,
+    phantom: PhantomData<T>,
+}
+
+impl<'t, T: CheckedNum> CheckedNumsIter<'t, T> {
+    pub fn rest_str(&self) -> &'t str {
+        &self.line[self.pos..]
+    }
+
+    /// Consumes the iterator, returns an error
+    /// if there are not exactly `N` numbers left
+    pub fn into_array<const N: usize>(mut self) -> Result<[T; N], ParseNumError> {
+        let mut res = Vec::with_capacity(N);
+        while res.len() < N {
+            match self.next() {
+                Some(x) => res.push(x?),
+                None => break,
+            }
+        }
+        let found = res.len();
+        if found < N {
+            return Err(ParseNumError {
+                offset: self.line.len(),
+                kind: ParseNumErrorKind::Count { expected: N, found },
+            });
+        }
+        if let Some((offset, _)) = self.options.next_token(self.line, self.pos, T::FLOAT) {
+            let found = N + self.count();
+            return Err(ParseNumError {
+                offset,
+                kind: ParseNumErrorKind::Count { expected: N, found },
+            });
+        }
+        Ok(res.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+
+    fn next_with_offset(&mut self) -> Option<(usize, Result<T, ParseNumError>)> {
+        let (offset, token) = self.options.next_token(self.line, self.pos, T::FLOAT)?;
+        self.pos = offset + token.text.len();
+        let res = T::from_token(&token).map_err(|kind| ParseNumError { offset, kind });
+        Some((offset, res))
+    }
+}
+
+impl<T: CheckedNum> Iterator for CheckedNumsIter<'_, T> {
+    type Item = Result<T, ParseNumError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_offset().map(|(_, res)| res)
+    }
+}
+
+pub struct NumRangesIter<'t, T> {
+    nums: CheckedNumsIter<'t, T>,
+}
+
+impl<'t, T: CheckedNum> NumRangesIter<'t, T> {
+    pub fn rest_str(&self) -> &'t str {
+        self.nums.rest_str()
+    }
+}
+
+impl<T: CheckedNum + Clone> Iterator for NumRangesIter<'_, T> {
+    type Item = Result<RangeInclusive<T>, ParseNumError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = match self.nums.next()? {
+            Ok(x) => x,
+            Err(e) => return Some(Err(e)),
+        };
+        let dash = self.nums.pos;
+        if self.nums.line.as_bytes().get(dash) != Some(&b'-') {
+            return Some(Ok(start.clone()..=start));
+        }
+        // The end of the range should go right after the dash
+        let mut rest = CheckedNumsIter {
+            pos: dash + 1,
+            ..self.nums
+        };
+        match rest.next_with_offset() {
+            Some((offset, end)) if offset == dash + 1 => {
+                self.nums.pos = rest.pos;
+                Some(end.map(|end| start..=end))
+            }
+            _ => Some(Ok(start.clone()..=start)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::{
+        checked_nums, num_ranges, nums_array, NumsOptions, ParseNumError, ParseNumErrorKind,
+        SignPolicy,
+    };
+
+    #[test]
+    fn test_checked_nums() {
+        assert_eq!(
+            checked_nums::<u8>("1,2,3").collect_vec(),
+            vec![Ok(1), Ok(2), Ok(3)]
+        );
+        assert_eq!(
+            checked_nums::<i8>("x=-128, y=127").collect_vec(),
+            vec![Ok(-128), Ok(127)]
+        );
+        assert_eq!(
+            checked_nums::<u8>("255,256,7").collect_vec(),
+            vec![
+                Ok(255),
+                Err(ParseNumError {
+                    offset: 4,
+                    kind: ParseNumErrorKind::Overflow {
+                        text: "256".to_string(),
+                        ty: "u8"
+                    }
+                }),
+                Ok(7)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsigned_negative() {
+        assert_eq!(
+            checked_nums::<u8>("ab -10").collect_vec(),
+            vec![Err(ParseNumError {
+                offset: 3,
+                kind: ParseNumErrorKind::Negative {
+                    text: "-10".to_string(),
+                    ty: "u8"
+                }
+            })]
+        );
+        let ignore = NumsOptions::new().sign(SignPolicy::Ignore);
+        assert_eq!(ignore.nums::<u8>("-10").collect_vec(), vec![Ok(10)]);
+        assert_eq!(ignore.nums::<i32>("3-7").collect_vec(), vec![Ok(3), Ok(7)]);
+    }
+
+    #[test]
+    fn test_plus_minus() {
+        let options = NumsOptions::new().sign(SignPolicy::PlusMinus);
+        let [x, y] = options.array::<u32, 2>("X+94, Y+34").unwrap();
+        assert_eq!((x, y), (94, 34));
+        assert_eq!(
+            options.nums::<i64>("+1 -2 + 3").collect_vec(),
+            vec![Ok(1), Ok(-2), Ok(3)]
+        );
+    }
+
+    #[test]
+    fn test_floats() {
+        assert_eq!(
+            checked_nums::<f64>("a=1.5, b=-2, c=3e2, d=1.25E-1.").collect_vec(),
+            vec![Ok(1.5), Ok(-2.0), Ok(300.0), Ok(0.125)]
+        );
+        assert_eq!(checked_nums::<f64>("1.x").collect_vec(), vec![Ok(1.0)]);
+    }
+
+    #[test]
+    fn test_radix_prefixes() {
+        let options = NumsOptions::new().radix_prefixes(true);
+        assert_eq!(
+            options
+                .nums::<i32>("0xff, -0b101, 0o17, 0x, 12")
+                .collect_vec(),
+            vec![Ok(255), Ok(-5), Ok(15), Ok(0), Ok(12)]
+        );
+        assert_eq!(checked_nums::<i32>("0xff").collect_vec(), vec![Ok(0)]);
+        assert!(options.nums::<u8>("0x100").next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_nums_array() {
+        assert_eq!(nums_array::<u32, 3>("1x2x3"), Ok([1, 2, 3]));
+        assert_eq!(
+            nums_array::<u32, 3>("1x2"),
+            Err(ParseNumError {
+                offset: 3,
+                kind: ParseNumErrorKind::Count {
+                    expected: 3,
+                    found: 2
+                }
+            })
+        );
+        assert_eq!(
+            nums_array::<u32, 2>("1,2,3,4"),
+            Err(ParseNumError {
+                offset: 4,
+                kind: ParseNumErrorKind::Count {
+                    expected: 2,
+                    found: 4
+                }
+            })
+        );
+        assert!(nums_array::<u8, 1>("300").is_err());
+    }
+
+    #[test]
+    fn test_ranges() {
+        assert_eq!(
+            num_ranges::<u32>("2-4,6-8 11 13-13").collect_vec(),
+            vec![Ok(2..=4), Ok(6..=8), Ok(11..=11), Ok(13..=13)]
+        );
+        assert_eq!(
+            num_ranges::<i32>("-5--3, 4- 6").collect_vec(),
+            vec![Ok(-5..=-3), Ok(4..=4), Ok(6..=6)]
+        );
+        let mut it = num_ranges::<u32>("1-2 rest");
+        assert_eq!(it.next(), Some(Ok(1..=2)));
+        assert_eq!(it.rest_str(), " rest");
+    }
+}
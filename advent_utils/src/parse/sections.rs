@@ -0,0 +1,410 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    parse::{CheckedNum, NumsOptions},
+    template::{parse_full, FromTemplate},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionError {
+    /// 1-based index of the section
+    pub section: usize,
+    /// 1-based line number in the whole input
+    pub line: usize,
+    /// 1-based column, if known
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl Display for SectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "section {}, line {}", self.section, self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ", column {column}")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for SectionError {}
+
+/// Part of the input between blank lines or headers
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Section<'t> {
+    /// 0-based index of the section
+    pub index: usize,
+    /// Header line of the section, without trailing whitespace
+    pub header: Option<&'t str>,
+    text: &'t str,
+    first_line: usize,
+}
+
+impl<'t> Section<'t> {
+    /// Raw text of the section, without surrounding blank lines.
+    /// May contain `\r`.
+    pub fn text(&self) -> &'t str {
+        self.text
+    }
+
+    /// 1-based line number of the first line of the section in the whole input
+    pub fn first_line(&self) -> usize {
+        self.first_line
+    }
+
+    /// Lines without trailing whitespace together with their 1-based line numbers
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &'t str)> + 't {
+        let first_line = self.first_line;
+        self.text
+            .lines()
+            .map(str::trim_end)
+            .enumerate()
+            .map(move |(i, line)| (first_line + i, line))
+    }
+
+    pub fn error(&self, line: usize, message: impl Display) -> SectionError {
+        SectionError {
+            section: self.index + 1,
+            line,
+            column: None,
+            message: message.to_string(),
+        }
+    }
+
+    /// Moves the first line of the section into the header
+    pub fn strip_header(&self) -> Section<'t> {
+        let (header, text) = self.text.split_once('\n').unwrap_or((self.text, ""));
+        Section {
+            index: self.index,
+            header: Some(header.trim_end()),
+            text,
+            first_line: self.first_line + 1,
+        }
+    }
+
+    pub fn ascii_grid(&self) -> Grid<u8> {
+        self.lines().map(|(_, line)| line.bytes()).collect()
+    }
+
+    /// Builds a grid converting every byte, errors report the line and column of the byte
+    pub fn grid<T, E: Display>(
+        &self,
+        mut f: impl FnMut(u8) -> Result<T, E>,
+    ) -> Result<Grid<T>, SectionError> {
+        let mut rows = Vec::new();
+        for (line_number, line) in self.lines() {
+            let row = line
+                .bytes()
+                .enumerate()
+                .map(|(i, b)| {
+                    f(b).map_err(|e| SectionError {
+                        column: Some(i + 1),
+                        ..self.error(line_number, e)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.push(row);
+        }
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Parses every non-empty line
+    pub fn parse_lines<T, E: Display>(
+        &self,
+        mut f: impl FnMut(&'t str) -> Result<T, E>,
+    ) -> Result<Vec<T>, SectionError> {
+        self.lines()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(line_number, line)| f(line).map_err(|e| self.error(line_number, e)))
+            .collect()
+    }
+
+    /// Parses every non-empty line with its [`FromTemplate`] implementation
+    pub fn template_lines<T: FromTemplate<'t>>(&self) -> Result<Vec<T>, SectionError> {
+        self.lines()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(line_number, line)| {
+                parse_full(line).map_err(|e| SectionError {
+                    column: Some(e.column),
+                    ..self.error(line_number, format!("cannot parse {:?}", e.text))
+                })
+            })
+            .collect()
+    }
+
+    /// All numbers of the section, see [`crate::parse::checked_nums`]
+    pub fn nums<T: CheckedNum>(&self) -> Result<Vec<T>, SectionError> {
+        self.nums_with(NumsOptions::new())
+    }
+
+    pub fn nums_with<T: CheckedNum>(&self, options: NumsOptions) -> Result<Vec<T>, SectionError> {
+        let mut res = Vec::new();
+        for (line_number, line) in self.lines() {
+            for x in options.nums(line) {
+                let x = x.map_err(|e| SectionError {
+                    column: Some(line[..e.offset].chars().count() + 1),
+                    ..self.error(line_number, e.kind)
+                })?;
+                res.push(x);
+            }
+        }
+        Ok(res)
+    }
+}
+
+/// Sections of the input in order of appearance
+pub struct Sections<'t> {
+    sections: std::vec::IntoIter<Section<'t>>,
+    total: usize,
+    lines: usize,
+}
+
+impl<'t> Sections<'t> {
+    fn new(sections: Vec<Section<'t>>, lines: usize) -> Self {
+        Self {
+            total: sections.len(),
+            sections: sections.into_iter(),
+            lines,
+        }
+    }
+
+    /// Returns an error if there are no more sections
+    pub fn expect_next(&mut self) -> Result<Section<'t>, SectionError> {
+        self.next().ok_or_else(|| SectionError {
+            section: self.total + 1,
+            line: self.lines,
+            column: None,
+            message: format!("expected section {}, found end of input", self.total + 1),
+        })
+    }
+}
+
+impl<'t> Iterator for Sections<'t> {
+    type Item = Section<'t>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sections.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sections.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Sections<'_> {}
+
+/// Byte range of a line without the line ending
+struct Line {
+    start: usize,
+    end: usize,
+    blank: bool,
+}
+
+fn split_lines(input: &str) -> Vec<Line> {
+    let mut start = 0;
+    input
+        .split_inclusive('\n')
+        .map(|raw| {
+            let content = raw.trim_end_matches(['\n', '\r']);
+            let line = Line {
+                start,
+                end: start + content.len(),
+                blank: content.trim().is_empty(),
+            };
+            start += raw.len();
+            line
+        })
+        .collect()
+}
+
+/// Pushes a section of the lines without leading and trailing blank lines.
+/// Sections without a header and without text are skipped.
+fn push_section<'t>(
+    res: &mut Vec<Section<'t>>,
+    input: &'t str,
+    lines: &[Line],
+    first_index: usize,
+    header: Option<&'t str>,
+) {
+    let first = lines.iter().position(|l| !l.blank);
+    let last = lines.iter().rposition(|l| !l.blank);
+    let (text, first_line) = match (first, last) {
+        (Some(first), Some(last)) => (
+            &input[lines[first].start..lines[last].end],
+            first_index + first + 1,
+        ),
+        _ if header.is_some() => ("", first_index + 1),
+        _ => return,
+    };
+    res.push(Section {
+        index: res.len(),
+        header,
+        text,
+        first_line,
+    });
+}
+
+/// Splits the input by blank lines (whitespace-only lines are blank too).
+///
+/// ```ignore
+/// let mut sections = sections(input);
+/// let grid = sections.expect_next()?.ascii_grid();
+/// let moves = sections.expect_next()?.parse_lines(Move::from_str)?;
+/// ```
+pub fn sections(input: &str) -> Sections<'_> {
+    let lines = split_lines(input);
+    let mut res = Vec::new();
+    let mut start = 0;
+    for end in (0..=lines.len()).filter(|&i| i == lines.len() || lines[i].blank) {
+        push_section(&mut res, input, &lines[start..end], start, None);
+        start = end + 1;
+    }
+    Sections::new(res, lines.len())
+}
+
+/// Splits the input by header lines.
+///
+/// Every line for which `is_header` returns `true` starts a new section.
+/// Non-blank lines before the first header form a section without a header.
+pub fn sections_by_header(input: &str, mut is_header: impl FnMut(&str) -> bool) -> Sections<'_> {
+    let lines = split_lines(input);
+    let mut res = Vec::new();
+    let mut start = 0;
+    let mut header = None;
+    for (i, line) in lines.iter().enumerate() {
+        let text = &input[line.start..line.end];
+        if is_header(text) {
+            push_section(&mut res, input, &lines[start..i], start, header);
+            header = Some(text.trim_end());
+            start = i + 1;
+        }
+    }
+    push_section(&mut res, input, &lines[start..], start, header);
+    Sections::new(res, lines.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::{sections, sections_by_header, SectionError};
+
+    #[test]
+    fn test_blank_lines() {
+        let input = "\n\nab\r\ncd  \r\n\r\n   \n1 2\n3\n\n\n";
+        let all = sections(input).collect_vec();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].text(), "ab\r\ncd  ");
+        assert_eq!(all[0].first_line(), 3);
+        assert_eq!(all[0].lines().collect_vec(), vec![(3, "ab"), (4, "cd")]);
+        assert_eq!(all[1].index, 1);
+        assert_eq!(all[1].lines().collect_vec(), vec![(7, "1 2"), (8, "3")]);
+        assert_eq!(all[1].nums::<u32>(), Ok(vec![1, 2, 3]));
+        let grid = all[0].ascii_grid();
+        assert_eq!(grid.render_ascii(), "ab\ncd\n");
+    }
+
+    #[test]
+    fn test_expect_next() {
+        let mut it = sections("a\n\nb\n");
+        assert_eq!(it.expect_next().map(|s| s.text()), Ok("a"));
+        assert_eq!(it.expect_next().map(|s| s.text()), Ok("b"));
+        assert_eq!(
+            it.expect_next(),
+            Err(SectionError {
+                section: 3,
+                line: 3,
+                column: None,
+                message: "expected section 3, found end of input".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_headers() {
+        let input =
+            "preamble\n\nyour ticket:\n7,1,14\n\nnearby tickets:\n7,3,47\n40,4,50\nempty:\n";
+        let all = sections_by_header(input, |line| line.ends_with(':')).collect_vec();
+        assert_eq!(
+            all.iter().map(|s| (s.header, s.text())).collect_vec(),
+            vec![
+                (None, "preamble"),
+                (Some("your ticket:"), "7,1,14"),
+                (Some("nearby tickets:"), "7,3,47\n40,4,50"),
+                (Some("empty:"), ""),
+            ]
+        );
+        assert_eq!(all[2].first_line(), 7);
+        assert_eq!(all[2].nums::<u32>().unwrap().len(), 6);
+
+        let shape = sections("0:\n#.\n.#\n").next().unwrap().strip_header();
+        assert_eq!(shape.header, Some("0:"));
+        assert_eq!(shape.lines().collect_vec(), vec![(2, "#."), (3, ".#")]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let input = "#.\n.x\n\n1 -> 2\n3 => 4\n\n1,300";
+        let mut it = sections(input);
+        let grid = it.expect_next().unwrap().grid(|b| match b {
+            b'#' => Ok(true),
+            b'.' => Ok(false),
+            _ => Err(format!("unexpected {:?}", char::from(b))),
+        });
+        assert_eq!(
+            grid.map(|_| ()).unwrap_err().to_string(),
+            "section 1, line 2, column 2: unexpected 'x'"
+        );
+        let pairs = it
+            .expect_next()
+            .unwrap()
+            .parse_lines(|line| line.split_once(" => ").ok_or("missing \" => \""));
+        assert_eq!(
+            pairs.unwrap_err().to_string(),
+            "section 2, line 4: missing \" => \""
+        );
+        assert_eq!(
+            it.expect_next()
+                .unwrap()
+                .nums::<u8>()
+                .unwrap_err()
+                .to_string(),
+            "section 3, line 7, column 3: 300 does not fit into u8"
+        );
+    }
+
+    crate::line_parser! {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Replacement<'t> = "{from} => {to}" {
+            from: &'t str,
+            to: &'t str,
+        }
+    }
+
+    #[test]
+    fn test_template_lines() {
+        let mut it = sections("H => HO\nH => OH\n\nHOH\n");
+        let replacements = it.expect_next().unwrap().template_lines::<Replacement>();
+        assert_eq!(
+            replacements,
+            Ok(vec![
+                Replacement {
+                    from: "H",
+                    to: "HO"
+                },
+                Replacement {
+                    from: "H",
+                    to: "OH"
+                }
+            ])
+        );
+        let bad = sections("\nH => HO\nH -> OH")
+            .next()
+            .unwrap()
+            .template_lines::<Replacement>();
+        assert_eq!(
+            bad.unwrap_err().to_string(),
+            "section 1, line 3, column 2: cannot parse \"H -> OH\""
+        );
+    }
+}
@@ -0,0 +1,628 @@
+//! A small CDCL SAT solver.
+//!
+//! Two watched literals, first-UIP clause learning, VSIDS branching with phase
+//! saving and Luby restarts. Learnt clauses are kept forever, which is fine for
+//! puzzle sized instances with up to a few hundred thousand clauses.
+use std::ops::Not;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Var(u32);
+
+impl Var {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn lit(self) -> Lit {
+        Lit(self.0 << 1)
+    }
+}
+
+/// A variable or its negation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lit(u32);
+
+impl Lit {
+    pub fn var(self) -> Var {
+        Var(self.0 >> 1)
+    }
+
+    pub fn is_negated(self) -> bool {
+        self.0 & 1 != 0
+    }
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Not for Lit {
+    type Output = Lit;
+
+    fn not(self) -> Lit {
+        Lit(self.0 ^ 1)
+    }
+}
+
+/// Values of the variables in a satisfying assignment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Model {
+    values: Vec<bool>,
+}
+
+impl Model {
+    pub fn value(&self, lit: Lit) -> bool {
+        self.values[lit.var().index()] != lit.is_negated()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Solver {
+    clauses: Vec<Vec<Lit>>,
+    /// Clauses watching each literal, visited when it becomes false
+    watches: Vec<Vec<usize>>,
+    assigns: Vec<Option<bool>>,
+    level: Vec<usize>,
+    reason: Vec<Option<usize>>,
+    trail: Vec<Lit>,
+    trail_lim: Vec<usize>,
+    head: usize,
+    order: VarOrder,
+    phase: Vec<bool>,
+    seen: Vec<bool>,
+    increment: f64,
+    /// Set once the clauses are known to be unsatisfiable
+    conflicting: bool,
+    conflicts: usize,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Self {
+            increment: 1.0,
+            ..Self::default()
+        }
+    }
+
+    pub fn vars(&self) -> usize {
+        self.assigns.len()
+    }
+
+    pub fn clauses(&self) -> usize {
+        self.clauses.len()
+    }
+
+    /// Number of conflicts over all calls to [`Solver::solve`]
+    pub fn conflicts(&self) -> usize {
+        self.conflicts
+    }
+
+    pub fn new_var(&mut self) -> Var {
+        let var = Var(self.assigns.len() as u32);
+        self.assigns.push(None);
+        self.level.push(0);
+        self.reason.push(None);
+        self.phase.push(false);
+        self.seen.push(false);
+        self.watches.extend([Vec::new(), Vec::new()]);
+        self.order.push(var);
+        var
+    }
+
+    pub fn new_lit(&mut self) -> Lit {
+        self.new_var().lit()
+    }
+
+    fn value(&self, lit: Lit) -> Option<bool> {
+        self.assigns[lit.var().index()].map(|v| v != lit.is_negated())
+    }
+
+    fn decision_level(&self) -> usize {
+        self.trail_lim.len()
+    }
+
+    /// At least one of the literals is true
+    pub fn add_clause(&mut self, lits: impl IntoIterator<Item = Lit>) {
+        if self.conflicting {
+            return;
+        }
+        let mut clause = lits.into_iter().collect::<Vec<_>>();
+        clause.sort_unstable();
+        clause.dedup();
+        if clause.windows(2).any(|w| w[0] == !w[1])
+            || clause.iter().any(|l| self.value(*l) == Some(true))
+        {
+            return;
+        }
+        clause.retain(|l| self.value(*l).is_none());
+        match clause.len() {
+            0 => self.conflicting = true,
+            1 => {
+                self.enqueue(clause[0], None);
+                self.conflicting = self.propagate().is_some();
+            }
+            _ => {
+                self.watch(clause);
+            }
+        }
+    }
+
+    /// `a => b`
+    pub fn add_implication(&mut self, a: Lit, b: Lit) {
+        self.add_clause([!a, b]);
+    }
+
+    /// `lit => any of the clause`
+    pub fn add_lit_impl_clause(&mut self, lit: Lit, clause: &[Lit]) {
+        self.add_clause(std::iter::once(!lit).chain(clause.iter().copied()));
+    }
+
+    /// `lit => all of the cube`
+    pub fn add_lit_impl_cube(&mut self, lit: Lit, cube: &[Lit]) {
+        for l in cube {
+            self.add_implication(lit, *l);
+        }
+    }
+
+    /// `any of the clause => lit`
+    pub fn add_clause_impl_lit(&mut self, clause: &[Lit], lit: Lit) {
+        for l in clause {
+            self.add_implication(*l, lit);
+        }
+    }
+
+    /// At most `k` of the literals are true, with a sequential counter
+    pub fn add_at_most(&mut self, lits: &[Lit], k: usize) {
+        let n = lits.len();
+        if k >= n {
+            return;
+        }
+        if k == 0 {
+            for l in lits {
+                self.add_clause([!*l]);
+            }
+            return;
+        }
+        // counter[j] of the prefix: at least j + 1 of the literals so far are true
+        let mut counter = (0..k).map(|_| self.new_lit()).collect::<Vec<_>>();
+        self.add_implication(lits[0], counter[0]);
+        for c in &counter[1..] {
+            self.add_clause([!*c]);
+        }
+        for x in lits[1..n - 1].iter().copied() {
+            let next = (0..k).map(|_| self.new_lit()).collect::<Vec<_>>();
+            self.add_implication(x, next[0]);
+            for j in 0..k {
+                self.add_implication(counter[j], next[j]);
+                if j > 0 {
+                    self.add_clause([!x, !counter[j - 1], next[j]]);
+                }
+            }
+            self.add_clause([!x, !counter[k - 1]]);
+            counter = next;
+        }
+        self.add_clause([!lits[n - 1], !counter[k - 1]]);
+    }
+
+    /// At least `k` of the literals are true
+    pub fn add_at_least(&mut self, lits: &[Lit], k: usize) {
+        if k > lits.len() {
+            self.conflicting = true;
+            return;
+        }
+        let negated = lits.iter().map(|l| !*l).collect::<Vec<_>>();
+        self.add_at_most(&negated, lits.len() - k);
+    }
+
+    pub fn add_exactly(&mut self, lits: &[Lit], k: usize) {
+        self.add_at_most(lits, k);
+        self.add_at_least(lits, k);
+    }
+
+    fn watch(&mut self, clause: Vec<Lit>) -> usize {
+        let index = self.clauses.len();
+        self.watches[clause[0].index()].push(index);
+        self.watches[clause[1].index()].push(index);
+        self.clauses.push(clause);
+        index
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+        let var = lit.var().index();
+        self.assigns[var] = Some(!lit.is_negated());
+        self.level[var] = self.decision_level();
+        self.reason[var] = reason;
+        self.trail.push(lit);
+    }
+
+    /// Index of a conflicting clause
+    fn propagate(&mut self) -> Option<usize> {
+        while self.head < self.trail.len() {
+            let falsified = !self.trail[self.head];
+            self.head += 1;
+            let mut watching = std::mem::take(&mut self.watches[falsified.index()]);
+            let mut kept = 0;
+            let mut conflict = None;
+            let mut i = 0;
+            while i < watching.len() {
+                let index = watching[i];
+                i += 1;
+                let clause = &mut self.clauses[index];
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+                let first = clause[0];
+                if self.assigns[first.var().index()].map(|v| v != first.is_negated()) == Some(true)
+                {
+                    watching[kept] = index;
+                    kept += 1;
+                    continue;
+                }
+                let assigns = &self.assigns;
+                let replacement = (2..clause.len()).find(|k| {
+                    let l = clause[*k];
+                    assigns[l.var().index()].map(|v| v != l.is_negated()) != Some(false)
+                });
+                if let Some(k) = replacement {
+                    clause.swap(1, k);
+                    let watched = clause[1];
+                    self.watches[watched.index()].push(index);
+                    continue;
+                }
+                watching[kept] = index;
+                kept += 1;
+                if self.value(first) == Some(false) {
+                    conflict = Some(index);
+                    break;
+                }
+                self.enqueue(first, Some(index));
+            }
+            while i < watching.len() {
+                watching[kept] = watching[i];
+                kept += 1;
+                i += 1;
+            }
+            watching.truncate(kept);
+            self.watches[falsified.index()] = watching;
+            if conflict.is_some() {
+                self.head = self.trail.len();
+                return conflict;
+            }
+        }
+        None
+    }
+
+    fn bump(&mut self, var: Var) {
+        self.order.activity[var.index()] += self.increment;
+        if self.order.activity[var.index()] > 1e100 {
+            self.order.activity.iter_mut().for_each(|a| *a *= 1e-100);
+            self.increment *= 1e-100;
+        }
+        self.order.increased(var);
+    }
+
+    /// First unique implication point clause with the level to go back to
+    fn analyze(&mut self, conflict: usize) -> (Vec<Lit>, usize) {
+        let mut learnt = vec![Lit(0)];
+        let mut pending = 0;
+        let mut clause = conflict;
+        let mut skip_first = false;
+        let mut index = self.trail.len();
+        let uip = loop {
+            for k in usize::from(skip_first)..self.clauses[clause].len() {
+                let lit = self.clauses[clause][k];
+                let var = lit.var();
+                if self.seen[var.index()] || self.level[var.index()] == 0 {
+                    continue;
+                }
+                self.seen[var.index()] = true;
+                self.bump(var);
+                if self.level[var.index()] == self.decision_level() {
+                    pending += 1;
+                } else {
+                    learnt.push(lit);
+                }
+            }
+            let lit = loop {
+                index -= 1;
+                if self.seen[self.trail[index].var().index()] {
+                    break self.trail[index];
+                }
+            };
+            self.seen[lit.var().index()] = false;
+            pending -= 1;
+            if pending == 0 {
+                break lit;
+            }
+            clause = self.reason[lit.var().index()].expect("implied literal has a reason");
+            skip_first = true;
+        };
+        learnt[0] = !uip;
+        for lit in &learnt[1..] {
+            self.seen[lit.var().index()] = false;
+        }
+        let mut back = 0;
+        if let Some((k, lit)) = learnt
+            .iter()
+            .enumerate()
+            .skip(1)
+            .max_by_key(|(_, l)| self.level[l.var().index()])
+        {
+            back = self.level[lit.var().index()];
+            learnt.swap(1, k);
+        }
+        (learnt, back)
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        if self.decision_level() <= level {
+            return;
+        }
+        let start = self.trail_lim[level];
+        for lit in self.trail.drain(start..).rev() {
+            let var = lit.var();
+            self.assigns[var.index()] = None;
+            self.reason[var.index()] = None;
+            self.phase[var.index()] = !lit.is_negated();
+            self.order.push(var);
+        }
+        self.head = start;
+        self.trail_lim.truncate(level);
+    }
+
+    fn decide(&mut self) -> Option<Lit> {
+        while let Some(var) = self.order.pop() {
+            if self.assigns[var.index()].is_none() {
+                let lit = var.lit();
+                return Some(if self.phase[var.index()] { lit } else { !lit });
+            }
+        }
+        None
+    }
+
+    /// A satisfying assignment, `None` if the clauses are unsatisfiable.
+    ///
+    /// Clauses can still be added afterwards to solve again.
+    pub fn solve(&mut self) -> Option<Model> {
+        if self.conflicting || self.propagate().is_some() {
+            self.conflicting = true;
+            return None;
+        }
+        let mut restart = 0;
+        let mut budget = 100 * luby(restart);
+        loop {
+            if let Some(conflict) = self.propagate() {
+                self.conflicts += 1;
+                if self.decision_level() == 0 {
+                    self.conflicting = true;
+                    return None;
+                }
+                let (learnt, back) = self.analyze(conflict);
+                self.backtrack(back);
+                if learnt.len() == 1 {
+                    self.enqueue(learnt[0], None);
+                } else {
+                    let first = learnt[0];
+                    let index = self.watch(learnt);
+                    self.enqueue(first, Some(index));
+                }
+                self.increment /= 0.95;
+                budget = budget.saturating_sub(1);
+            } else if budget == 0 {
+                restart += 1;
+                budget = 100 * luby(restart);
+                self.backtrack(0);
+            } else if let Some(lit) = self.decide() {
+                self.trail_lim.push(self.trail.len());
+                self.enqueue(lit, None);
+            } else {
+                let values = self.assigns.iter().map(|v| v.unwrap()).collect();
+                self.backtrack(0);
+                return Some(Model { values });
+            }
+        }
+    }
+}
+
+/// `1, 1, 2, 1, 1, 2, 4, 1, ...`
+fn luby(mut i: usize) -> usize {
+    let mut size = 1;
+    let mut power = 1;
+    while size < i + 1 {
+        size = 2 * size + 1;
+        power *= 2;
+    }
+    while size - 1 != i {
+        size = (size - 1) / 2;
+        power /= 2;
+        i %= size;
+    }
+    power
+}
+
+/// Max heap of the variables by activity
+#[derive(Debug, Clone, Default)]
+struct VarOrder {
+    activity: Vec<f64>,
+    heap: Vec<Var>,
+    position: Vec<Option<usize>>,
+}
+
+impl VarOrder {
+    fn push(&mut self, var: Var) {
+        if var.index() >= self.activity.len() {
+            self.activity.resize(var.index() + 1, 0.0);
+            self.position.resize(var.index() + 1, None);
+        }
+        if self.position[var.index()].is_none() {
+            self.heap.push(var);
+            self.position[var.index()] = Some(self.heap.len() - 1);
+            self.sift_up(self.heap.len() - 1);
+        }
+    }
+
+    fn increased(&mut self, var: Var) {
+        if let Some(i) = self.position[var.index()] {
+            self.sift_up(i);
+        }
+    }
+
+    fn pop(&mut self) -> Option<Var> {
+        let top = *self.heap.first()?;
+        let last = self.heap.pop().unwrap();
+        self.position[top.index()] = None;
+        if !self.heap.is_empty() {
+            self.heap[0] = last;
+            self.position[last.index()] = Some(0);
+            self.sift_down(0);
+        }
+        Some(top)
+    }
+
+    fn higher(&self, a: usize, b: usize) -> bool {
+        self.activity[self.heap[a].index()] > self.activity[self.heap[b].index()]
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.position[self.heap[a].index()] = Some(a);
+        self.position[self.heap[b].index()] = Some(b);
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 && self.higher(i, (i - 1) / 2) {
+            self.swap(i, (i - 1) / 2);
+            i = (i - 1) / 2;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let mut top = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < self.heap.len() && self.higher(child, top) {
+                    top = child;
+                }
+            }
+            if top == i {
+                return;
+            }
+            self.swap(i, top);
+            i = top;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{luby, Lit, Solver};
+
+    #[test]
+    fn test_luby() {
+        let sequence = (0..15).map(luby).collect::<Vec<_>>();
+        assert_eq!(sequence, [1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+    }
+
+    #[test]
+    fn test_small() {
+        let mut solver = Solver::new();
+        let [a, b, c] = [0; 3].map(|_| solver.new_lit());
+        solver.add_clause([a, b]);
+        solver.add_clause([!a, c]);
+        solver.add_clause([!b, c]);
+        solver.add_clause([!c, !a]);
+        let model = solver.solve().unwrap();
+        assert!(!model.value(a) && model.value(b) && model.value(c));
+
+        solver.add_clause([!b]);
+        assert_eq!(solver.solve(), None);
+    }
+
+    /// `holes + 1` pigeons in `holes` holes
+    fn pigeons(solver: &mut Solver, holes: usize, pigeons: usize) -> Vec<Vec<Lit>> {
+        let places = (0..pigeons)
+            .map(|_| (0..holes).map(|_| solver.new_lit()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for pigeon in &places {
+            solver.add_clause(pigeon.iter().copied());
+        }
+        for hole in 0..holes {
+            let in_hole = places.iter().map(|p| p[hole]).collect::<Vec<_>>();
+            solver.add_at_most(&in_hole, 1);
+        }
+        places
+    }
+
+    #[test]
+    fn test_pigeonhole() {
+        let mut solver = Solver::new();
+        pigeons(&mut solver, 6, 7);
+        assert_eq!(solver.solve(), None);
+
+        let mut solver = Solver::new();
+        let places = pigeons(&mut solver, 7, 7);
+        let model = solver.solve().unwrap();
+        for hole in 0..7 {
+            assert_eq!(places.iter().filter(|p| model.value(p[hole])).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_cardinality() {
+        for n in 1..7 {
+            for k in 0..=n {
+                let mut solver = Solver::new();
+                let lits = (0..n).map(|_| solver.new_lit()).collect::<Vec<_>>();
+                solver.add_exactly(&lits, k);
+                let mut count = 0;
+                // Enumerate every model by blocking the previous one
+                while let Some(model) = solver.solve() {
+                    assert_eq!(lits.iter().filter(|l| model.value(**l)).count(), k);
+                    solver.add_clause(lits.iter().map(|l| if model.value(*l) { !*l } else { *l }));
+                    count += 1;
+                }
+                let binomial = (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1));
+                assert_eq!(count, binomial, "{k} of {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_queens() {
+        let n = 12;
+        let mut solver = Solver::new();
+        let board = (0..n)
+            .map(|_| (0..n).map(|_| solver.new_lit()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for i in 0..n {
+            solver.add_exactly(&board[i], 1);
+            let file = board.iter().map(|r| r[i]).collect::<Vec<_>>();
+            solver.add_exactly(&file, 1);
+        }
+        for d in 0..2 * n - 1 {
+            let diagonal = (0..n)
+                .filter_map(|r| d.checked_sub(r).filter(|c| *c < n).map(|c| board[r][c]))
+                .collect::<Vec<_>>();
+            solver.add_at_most(&diagonal, 1);
+            let anti = (0..n)
+                .filter_map(|r| {
+                    (r + n - 1)
+                        .checked_sub(d)
+                        .filter(|c| *c < n)
+                        .map(|c| board[r][c])
+                })
+                .collect::<Vec<_>>();
+            solver.add_at_most(&anti, 1);
+        }
+        let model = solver.solve().unwrap();
+        let queens = (0..n)
+            .map(|r| (0..n).position(|c| model.value(board[r][c])).unwrap())
+            .collect::<Vec<_>>();
+        for (r1, c1) in queens.iter().enumerate() {
+            for (r2, c2) in queens.iter().enumerate().skip(r1 + 1) {
+                assert_ne!(c1, c2);
+                assert_ne!(r2 - r1, c1.abs_diff(*c2));
+            }
+        }
+    }
+}
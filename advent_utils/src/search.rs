@@ -0,0 +1,499 @@
+//! Searches over abstract state spaces described by a [`SearchProblem`].
+//!
+//! Every search returns the cheapest (or, for [`bfs`], the shortest) path from one of the
+//! start states to a goal state together with [`SearchStats`].
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+mod branch_bound;
+
+pub use branch_bound::{
+    branch_and_bound, BranchAndBound, BranchAndBoundOptions, BranchStats, Exploration, Optimum,
+};
+
+/// Number of states with a function mapping each of them below it
+pub type DenseIndex<S> = (usize, fn(&S) -> usize);
+
+pub trait SearchProblem {
+    type State: Clone + Eq + Hash;
+    type Cost: Copy + Ord + Default + Add<Output = Self::Cost>;
+
+    fn starts(&self) -> impl IntoIterator<Item = Self::State>;
+
+    /// States reachable by one move, with the cost of the move
+    fn successors(
+        &self,
+        state: &Self::State,
+    ) -> impl IntoIterator<Item = (Self::State, Self::Cost)>;
+
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    /// Lower bound of the cost to a goal, used by [`astar`] and [`ida_star`]
+    fn heuristic(&self, _state: &Self::State) -> Self::Cost {
+        Self::Cost::default()
+    }
+
+    /// Dense indexing of the states, visited states are then kept in a
+    /// vector instead of a hash map
+    fn dense_index(&self) -> Option<DenseIndex<Self::State>> {
+        None
+    }
+}
+
+/// Problems which can be searched from both ends, see [`bidirectional_bfs`]
+pub trait ReversibleProblem: SearchProblem {
+    fn goals(&self) -> impl IntoIterator<Item = Self::State>;
+
+    /// States from which the state is reachable by one move
+    fn predecessors(&self, state: &Self::State) -> impl IntoIterator<Item = Self::State>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchStats {
+    /// States whose successors were generated
+    pub expanded: usize,
+    /// Successors generated, including already visited ones
+    pub generated: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution<S, C> {
+    pub cost: C,
+    /// From a start state to a goal state, both included
+    pub path: Vec<S>,
+    pub stats: SearchStats,
+}
+
+struct Node<S, C> {
+    state: S,
+    parent: Option<u32>,
+    cost: C,
+}
+
+/// Visited states with their node indices
+enum Seen<S> {
+    Dense(Vec<u32>, fn(&S) -> usize),
+    Sparse(HashMap<S, u32>),
+}
+
+impl<S: Eq + Hash + Clone> Seen<S> {
+    fn new<P: SearchProblem<State = S>>(problem: &P) -> Self {
+        match problem.dense_index() {
+            Some((count, index)) => Self::Dense(vec![u32::MAX; count], index),
+            None => Self::Sparse(HashMap::new()),
+        }
+    }
+
+    fn get(&self, state: &S) -> Option<u32> {
+        match self {
+            Self::Dense(nodes, index) => Some(nodes[index(state)]).filter(|n| *n != u32::MAX),
+            Self::Sparse(nodes) => nodes.get(state).copied(),
+        }
+    }
+
+    fn insert(&mut self, state: &S, node: u32) {
+        match self {
+            Self::Dense(nodes, index) => nodes[index(state)] = node,
+            Self::Sparse(nodes) => {
+                nodes.insert(state.clone(), node);
+            }
+        }
+    }
+}
+
+fn unwind<S: Clone, C: Copy>(
+    nodes: &[Node<S, C>],
+    last: u32,
+    stats: SearchStats,
+) -> Solution<S, C> {
+    let cost = nodes[last as usize].cost;
+    let mut path = Vec::new();
+    let mut current = Some(last);
+    while let Some(node) = current {
+        path.push(nodes[node as usize].state.clone());
+        current = nodes[node as usize].parent;
+    }
+    path.reverse();
+    Solution { cost, path, stats }
+}
+
+/// Breadth-first search, the path with the fewest moves.
+///
+/// The reported cost is the sum of the costs of its moves.
+pub fn bfs<P: SearchProblem>(problem: &P) -> Option<Solution<P::State, P::Cost>> {
+    let mut stats = SearchStats::default();
+    let mut seen = Seen::new(problem);
+    let mut nodes = Vec::new();
+    let mut queue = VecDeque::new();
+    for state in problem.starts() {
+        if seen.get(&state).is_none() {
+            seen.insert(&state, nodes.len() as u32);
+            queue.push_back(nodes.len() as u32);
+            nodes.push(Node {
+                state,
+                parent: None,
+                cost: P::Cost::default(),
+            });
+        }
+    }
+    while let Some(index) = queue.pop_front() {
+        let Node { state, cost, .. } = &nodes[index as usize];
+        if problem.is_goal(state) {
+            return Some(unwind(&nodes, index, stats));
+        }
+        stats.expanded += 1;
+        let (state, cost) = (state.clone(), *cost);
+        for (next, step) in problem.successors(&state) {
+            stats.generated += 1;
+            if seen.get(&next).is_some() {
+                continue;
+            }
+            seen.insert(&next, nodes.len() as u32);
+            queue.push_back(nodes.len() as u32);
+            nodes.push(Node {
+                state: next,
+                parent: Some(index),
+                cost: cost + step,
+            });
+        }
+    }
+    None
+}
+
+/// Cheapest path by Dijkstra's algorithm, costs must not be negative
+pub fn dijkstra<P: SearchProblem>(problem: &P) -> Option<Solution<P::State, P::Cost>> {
+    best_first(problem, |_| P::Cost::default())
+}
+
+/// Cheapest path by A* search, the heuristic must be consistent
+pub fn astar<P: SearchProblem>(problem: &P) -> Option<Solution<P::State, P::Cost>> {
+    best_first(problem, |state| problem.heuristic(state))
+}
+
+fn best_first<P: SearchProblem>(
+    problem: &P,
+    heuristic: impl Fn(&P::State) -> P::Cost,
+) -> Option<Solution<P::State, P::Cost>> {
+    let mut stats = SearchStats::default();
+    let mut seen = Seen::new(problem);
+    let mut nodes: Vec<Node<P::State, P::Cost>> = Vec::new();
+    let mut queue = BinaryHeap::new();
+    let push = |state: P::State,
+                parent: Option<u32>,
+                cost: P::Cost,
+                seen: &mut Seen<P::State>,
+                nodes: &mut Vec<Node<P::State, P::Cost>>,
+                queue: &mut BinaryHeap<_>| {
+        if let Some(existing) = seen.get(&state) {
+            if nodes[existing as usize].cost <= cost {
+                return;
+            }
+        }
+        let index = nodes.len() as u32;
+        seen.insert(&state, index);
+        queue.push((Reverse(cost + heuristic(&state)), Reverse(cost), index));
+        nodes.push(Node {
+            state,
+            parent,
+            cost,
+        });
+    };
+    for state in problem.starts() {
+        push(
+            state,
+            None,
+            P::Cost::default(),
+            &mut seen,
+            &mut nodes,
+            &mut queue,
+        );
+    }
+    while let Some((_, Reverse(cost), index)) = queue.pop() {
+        let state = nodes[index as usize].state.clone();
+        // Skip nodes of states reached more cheaply later
+        if seen.get(&state) != Some(index) {
+            continue;
+        }
+        if problem.is_goal(&state) {
+            return Some(unwind(&nodes, index, stats));
+        }
+        stats.expanded += 1;
+        for (next, step) in problem.successors(&state) {
+            stats.generated += 1;
+            push(
+                next,
+                Some(index),
+                cost + step,
+                &mut seen,
+                &mut nodes,
+                &mut queue,
+            );
+        }
+    }
+    None
+}
+
+/// Cheapest path by iterative deepening A*, using memory proportional to the path length.
+///
+/// States are not deduplicated between branches, only cycles on the current path are avoided.
+pub fn ida_star<P: SearchProblem>(problem: &P) -> Option<Solution<P::State, P::Cost>> {
+    struct Ida<'p, P: SearchProblem> {
+        problem: &'p P,
+        path: Vec<P::State>,
+        stats: SearchStats,
+    }
+
+    enum Found<C> {
+        Goal(C),
+        /// Smallest estimate above the bound
+        Exceeded(Option<C>),
+    }
+
+    impl<P: SearchProblem> Ida<'_, P> {
+        fn search(&mut self, cost: P::Cost, bound: P::Cost) -> Found<P::Cost> {
+            let state = self.path.last().unwrap();
+            let estimate = cost + self.problem.heuristic(state);
+            if estimate > bound {
+                return Found::Exceeded(Some(estimate));
+            }
+            if self.problem.is_goal(state) {
+                return Found::Goal(cost);
+            }
+            self.stats.expanded += 1;
+            let mut next_bound = None;
+            let successors = self
+                .problem
+                .successors(state)
+                .into_iter()
+                .collect::<Vec<_>>();
+            for (next, step) in successors {
+                self.stats.generated += 1;
+                if self.path.contains(&next) {
+                    continue;
+                }
+                self.path.push(next);
+                match self.search(cost + step, bound) {
+                    Found::Goal(cost) => return Found::Goal(cost),
+                    Found::Exceeded(Some(b)) => {
+                        next_bound = Some(next_bound.map_or(b, |n: P::Cost| n.min(b)));
+                    }
+                    Found::Exceeded(None) => {}
+                }
+                self.path.pop();
+            }
+            Found::Exceeded(next_bound)
+        }
+    }
+
+    let mut stats = SearchStats::default();
+    let mut best: Option<Solution<P::State, P::Cost>> = None;
+    for start in problem.starts() {
+        let mut bound = problem.heuristic(&start);
+        let mut ida = Ida {
+            problem,
+            path: vec![start],
+            stats,
+        };
+        loop {
+            if best.as_ref().is_some_and(|b| b.cost <= bound) {
+                break;
+            }
+            match ida.search(P::Cost::default(), bound) {
+                Found::Goal(cost) => {
+                    best = Some(Solution {
+                        cost,
+                        path: std::mem::take(&mut ida.path),
+                        stats: SearchStats::default(),
+                    });
+                    break;
+                }
+                Found::Exceeded(Some(next)) => bound = next,
+                Found::Exceeded(None) => break,
+            }
+        }
+        stats = ida.stats;
+    }
+    best.map(|solution| Solution { stats, ..solution })
+}
+
+/// Path with the fewest moves, searched from the starts and the goals at the same time
+pub fn bidirectional_bfs<P: ReversibleProblem>(problem: &P) -> Option<Solution<P::State, P::Cost>> {
+    let mut stats = SearchStats::default();
+    // Parent towards the start and towards the goal
+    let mut forward: HashMap<P::State, Option<P::State>> = HashMap::new();
+    let mut backward: HashMap<P::State, Option<P::State>> = HashMap::new();
+    let mut forward_frontier = Vec::new();
+    let mut backward_frontier = Vec::new();
+    for state in problem.starts() {
+        if forward.insert(state.clone(), None).is_none() {
+            forward_frontier.push(state);
+        }
+    }
+    for state in problem.goals() {
+        if backward.insert(state.clone(), None).is_none() {
+            backward_frontier.push(state);
+        }
+    }
+    let mut meeting = forward_frontier
+        .iter()
+        .find(|s| backward.contains_key(*s))
+        .cloned();
+
+    while meeting.is_none() && !forward_frontier.is_empty() && !backward_frontier.is_empty() {
+        let expand_forward = forward_frontier.len() <= backward_frontier.len();
+        let (frontier, seen, other) = if expand_forward {
+            (&mut forward_frontier, &mut forward, &backward)
+        } else {
+            (&mut backward_frontier, &mut backward, &forward)
+        };
+        let mut next_frontier = Vec::new();
+        'level: for state in std::mem::take(frontier) {
+            stats.expanded += 1;
+            let next_states: Vec<P::State> = if expand_forward {
+                problem
+                    .successors(&state)
+                    .into_iter()
+                    .map(|(s, _)| s)
+                    .collect()
+            } else {
+                problem.predecessors(&state).into_iter().collect()
+            };
+            for next in next_states {
+                stats.generated += 1;
+                if seen.contains_key(&next) {
+                    continue;
+                }
+                seen.insert(next.clone(), Some(state.clone()));
+                if other.contains_key(&next) {
+                    meeting = Some(next);
+                    break 'level;
+                }
+                next_frontier.push(next);
+            }
+        }
+        *frontier = next_frontier;
+    }
+
+    let meeting = meeting?;
+    let mut path = vec![meeting.clone()];
+    while let Some(Some(prev)) = forward.get(path.last().unwrap()) {
+        path.push(prev.clone());
+    }
+    path.reverse();
+    while let Some(Some(next)) = backward.get(path.last().unwrap()) {
+        path.push(next.clone());
+    }
+    let cost = path
+        .windows(2)
+        .map(|pair| {
+            problem
+                .successors(&pair[0])
+                .into_iter()
+                .filter(|(s, _)| *s == pair[1])
+                .map(|(_, c)| c)
+                .min()
+                .expect("predecessors must match successors")
+        })
+        .fold(P::Cost::default(), |acc, c| acc + c);
+    Some(Solution { cost, path, stats })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        astar, bfs, bidirectional_bfs, dijkstra, ida_star, DenseIndex, ReversibleProblem,
+        SearchProblem,
+    };
+
+    /// Walking on a line of numbers, steps of +1 cost 1, doubling costs 3
+    struct Doubling {
+        target: u32,
+        dense: bool,
+    }
+
+    impl SearchProblem for Doubling {
+        type State = u32;
+        type Cost = u32;
+
+        fn starts(&self) -> impl IntoIterator<Item = u32> {
+            [1]
+        }
+        fn successors(&self, state: &u32) -> impl IntoIterator<Item = (u32, u32)> {
+            [(state + 1, 1), (state * 2, 3)]
+                .into_iter()
+                .filter(|(s, _)| *s <= self.target)
+        }
+        fn is_goal(&self, state: &u32) -> bool {
+            *state == self.target
+        }
+        fn heuristic(&self, state: &u32) -> u32 {
+            // At least one more move
+            u32::from(*state != self.target)
+        }
+        fn dense_index(&self) -> Option<DenseIndex<u32>> {
+            self.dense
+                .then_some((self.target as usize + 1, |state| *state as usize))
+        }
+    }
+
+    impl ReversibleProblem for Doubling {
+        fn goals(&self) -> impl IntoIterator<Item = u32> {
+            [self.target]
+        }
+        fn predecessors(&self, state: &u32) -> impl IntoIterator<Item = u32> {
+            let halved = state.is_multiple_of(2).then_some(state / 2);
+            [state - 1].into_iter().filter(|s| *s > 0).chain(halved)
+        }
+    }
+
+    #[test]
+    fn test_cheapest() {
+        for dense in [false, true] {
+            let problem = Doubling { target: 100, dense };
+            // 1 2 3 6 12 24 25 50 100
+            let expected = 18;
+            let solution = dijkstra(&problem).unwrap();
+            assert_eq!(solution.cost, expected);
+            assert_eq!(solution.path.first(), Some(&1));
+            assert_eq!(solution.path.last(), Some(&100));
+            let star = astar(&problem).unwrap();
+            assert_eq!(star.cost, expected);
+            assert!(star.stats.expanded <= solution.stats.expanded);
+            assert!(solution.stats.generated >= solution.stats.expanded);
+        }
+        let problem = Doubling {
+            target: 40,
+            dense: false,
+        };
+        assert_eq!(
+            ida_star(&problem).unwrap().cost,
+            dijkstra(&problem).unwrap().cost
+        );
+    }
+
+    #[test]
+    fn test_fewest_moves() {
+        let problem = Doubling {
+            target: 100,
+            dense: true,
+        };
+        let solution = bfs(&problem).unwrap();
+        assert_eq!(solution.path, [1, 2, 3, 6, 12, 24, 25, 50, 100]);
+        assert_eq!(solution.cost, 18);
+        let both = bidirectional_bfs(&problem).unwrap();
+        assert_eq!(both.path.len(), solution.path.len());
+        assert_eq!(both.path.first(), Some(&1));
+        assert_eq!(both.path.last(), Some(&100));
+
+        let unreachable = Doubling {
+            target: 0,
+            dense: false,
+        };
+        assert_eq!(bfs(&unreachable), None);
+        assert_eq!(dijkstra(&unreachable), None);
+    }
+}
@@ -0,0 +1,410 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+    time::{Duration, Instant},
+};
+
+/// Optimization problem explored as a tree of partial solutions
+pub trait BranchAndBound {
+    type Node: Clone;
+    type Value: Copy + Ord;
+
+    fn root(&self) -> Self::Node;
+
+    /// Children of the node, leaves have none
+    fn branches(&self, node: &Self::Node, out: &mut Vec<Self::Node>);
+
+    /// Value of the solution the node stands for, `None` if it is not a solution yet
+    fn value(&self, node: &Self::Node) -> Option<Self::Value>;
+
+    /// Optimistic estimate: no node below this one has a better value
+    fn bound(&self, node: &Self::Node) -> Self::Value;
+
+    /// Whether `candidate` should replace the `incumbent`, maximizes by default
+    fn is_better(&self, candidate: Self::Value, incumbent: Self::Value) -> bool {
+        candidate > incumbent
+    }
+
+    /// Bucket of nodes which may dominate each other, `None` disables memoization.
+    ///
+    /// Collisions between unrelated nodes only make memoization less effective.
+    fn dominance_key(&self, _node: &Self::Node) -> Option<u64> {
+        None
+    }
+
+    /// Whether no node below `other` is better than the best node below `node`
+    fn dominates(&self, _node: &Self::Node, _other: &Self::Node) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Exploration {
+    /// Children of the most promising one first, memory proportional to the depth
+    #[default]
+    DepthFirst,
+    /// The node with the best bound first
+    BestFirst,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BranchAndBoundOptions {
+    exploration: Exploration,
+    node_limit: Option<usize>,
+    time_limit: Option<Duration>,
+}
+
+impl BranchAndBoundOptions {
+    pub fn exploration(mut self, exploration: Exploration) -> Self {
+        self.exploration = exploration;
+        self
+    }
+
+    /// Stops after expanding the number of nodes
+    pub fn node_limit(mut self, limit: usize) -> Self {
+        self.node_limit = Some(limit);
+        self
+    }
+
+    pub fn time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit = Some(limit);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BranchStats {
+    pub expanded: usize,
+    pub generated: usize,
+    /// Nodes whose bound could not beat the incumbent
+    pub pruned: usize,
+    /// Nodes dominated by an already seen node
+    pub dominated: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Optimum<N, V> {
+    pub value: V,
+    pub node: N,
+    pub stats: BranchStats,
+    /// `false` if a limit stopped the search, the value may not be optimal then
+    pub proven: bool,
+}
+
+struct Engine<'p, P: BranchAndBound> {
+    problem: &'p P,
+    best: Option<(P::Value, P::Node)>,
+    memo: HashMap<u64, Vec<P::Node>>,
+    stats: BranchStats,
+}
+
+impl<P: BranchAndBound> Engine<'_, P> {
+    fn can_improve(&self, bound: P::Value) -> bool {
+        self.best
+            .as_ref()
+            .is_none_or(|(value, _)| self.problem.is_better(bound, *value))
+    }
+
+    /// Records the node as a solution, its bound if it is worth exploring
+    fn admit(&mut self, node: &P::Node) -> Option<P::Value> {
+        if let Some(value) = self.problem.value(node) {
+            if self.can_improve(value) {
+                self.best = Some((value, node.clone()));
+            }
+        }
+        let bound = self.problem.bound(node);
+        if !self.can_improve(bound) {
+            self.stats.pruned += 1;
+            return None;
+        }
+        let Some(key) = self.problem.dominance_key(node) else {
+            return Some(bound);
+        };
+        let bucket = self.memo.entry(key).or_default();
+        if bucket.iter().any(|seen| self.problem.dominates(seen, node)) {
+            self.stats.dominated += 1;
+            return None;
+        }
+        bucket.retain(|seen| !self.problem.dominates(node, seen));
+        bucket.push(node.clone());
+        Some(bound)
+    }
+}
+
+/// Open node of the best-first heap, the most promising one is the greatest
+struct Candidate<'p, P: BranchAndBound> {
+    problem: &'p P,
+    bound: P::Value,
+    /// Insertion order, the newest node wins ties as in depth-first exploration
+    sequence: usize,
+    node: P::Node,
+}
+
+impl<P: BranchAndBound> Ord for Candidate<'_, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        order(self.problem, self.bound, other.bound).then(self.sequence.cmp(&other.sequence))
+    }
+}
+
+impl<P: BranchAndBound> PartialOrd for Candidate<'_, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: BranchAndBound> PartialEq for Candidate<'_, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl<P: BranchAndBound> Eq for Candidate<'_, P> {}
+
+/// Open nodes with their bounds
+enum Frontier<'p, P: BranchAndBound> {
+    /// The next node to expand is the last one
+    Stack(Vec<(P::Value, P::Node)>),
+    Heap {
+        problem: &'p P,
+        heap: BinaryHeap<Candidate<'p, P>>,
+        pushed: usize,
+    },
+}
+
+impl<'p, P: BranchAndBound> Frontier<'p, P> {
+    fn new(problem: &'p P, exploration: Exploration) -> Self {
+        match exploration {
+            Exploration::DepthFirst => Self::Stack(Vec::new()),
+            Exploration::BestFirst => Self::Heap {
+                problem,
+                heap: BinaryHeap::new(),
+                pushed: 0,
+            },
+        }
+    }
+
+    fn push(&mut self, bound: P::Value, node: P::Node) {
+        match self {
+            Self::Stack(stack) => stack.push((bound, node)),
+            Self::Heap {
+                problem,
+                heap,
+                pushed,
+            } => {
+                heap.push(Candidate {
+                    problem,
+                    bound,
+                    sequence: *pushed,
+                    node,
+                });
+                *pushed += 1;
+            }
+        }
+    }
+
+    fn pop(&mut self) -> Option<(P::Value, P::Node)> {
+        match self {
+            Self::Stack(stack) => stack.pop(),
+            Self::Heap { heap, .. } => heap.pop().map(|c| (c.bound, c.node)),
+        }
+    }
+}
+
+/// Best solution by branch and bound, `None` if there is no solution.
+///
+/// Nodes are pruned when their bound cannot beat the best solution found so far.
+pub fn branch_and_bound<P: BranchAndBound>(
+    problem: &P,
+    options: BranchAndBoundOptions,
+) -> Option<Optimum<P::Node, P::Value>> {
+    let started = Instant::now();
+    let mut engine = Engine {
+        problem,
+        best: None,
+        memo: HashMap::new(),
+        stats: BranchStats::default(),
+    };
+    let root = problem.root();
+    let mut open = Frontier::new(problem, options.exploration);
+    if let Some(bound) = engine.admit(&root) {
+        open.push(bound, root);
+    }
+    let mut children = Vec::new();
+    let mut proven = true;
+    while let Some((bound, node)) = open.pop() {
+        if !engine.can_improve(bound) {
+            engine.stats.pruned += 1;
+            continue;
+        }
+        let out_of_nodes = options
+            .node_limit
+            .is_some_and(|limit| engine.stats.expanded >= limit);
+        let out_of_time = engine.stats.expanded.is_multiple_of(1024)
+            && options
+                .time_limit
+                .is_some_and(|limit| started.elapsed() >= limit);
+        if out_of_nodes || out_of_time {
+            proven = false;
+            break;
+        }
+        engine.stats.expanded += 1;
+        problem.branches(&node, &mut children);
+        engine.stats.generated += children.len();
+        let mut admitted = Vec::with_capacity(children.len());
+        for child in children.drain(..) {
+            if let Some(bound) = engine.admit(&child) {
+                admitted.push((bound, child));
+            }
+        }
+        // The most promising node goes last
+        admitted.sort_by(|(a, _), (b, _)| order(problem, *a, *b));
+        for (bound, child) in admitted {
+            open.push(bound, child);
+        }
+    }
+
+    let stats = engine.stats;
+    engine.best.map(|(value, node)| Optimum {
+        value,
+        node,
+        stats,
+        proven,
+    })
+}
+
+/// Ascending order of promise
+fn order<P: BranchAndBound>(problem: &P, a: P::Value, b: P::Value) -> Ordering {
+    if problem.is_better(a, b) {
+        Ordering::Greater
+    } else if problem.is_better(b, a) {
+        Ordering::Less
+    } else {
+        Ordering::Equal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{branch_and_bound, BranchAndBound, BranchAndBoundOptions, Exploration};
+
+    /// 0/1 knapsack, nodes are `(next item, weight, value)`
+    struct Knapsack {
+        items: Vec<(u32, u32)>,
+        capacity: u32,
+        dominance: bool,
+    }
+
+    impl BranchAndBound for Knapsack {
+        type Node = (usize, u32, u32);
+        type Value = u32;
+
+        fn root(&self) -> Self::Node {
+            (0, 0, 0)
+        }
+        fn branches(&self, &(i, weight, value): &Self::Node, out: &mut Vec<Self::Node>) {
+            let Some(&(w, v)) = self.items.get(i) else {
+                return;
+            };
+            out.push((i + 1, weight, value));
+            if weight + w <= self.capacity {
+                out.push((i + 1, weight + w, value + v));
+            }
+        }
+        fn value(&self, node: &Self::Node) -> Option<u32> {
+            Some(node.2)
+        }
+        fn bound(&self, &(i, _, value): &Self::Node) -> u32 {
+            value + self.items[i..].iter().map(|(_, v)| v).sum::<u32>()
+        }
+        fn dominance_key(&self, node: &Self::Node) -> Option<u64> {
+            self.dominance.then_some(node.0 as u64)
+        }
+        fn dominates(&self, a: &Self::Node, b: &Self::Node) -> bool {
+            a.1 <= b.1 && a.2 >= b.2
+        }
+    }
+
+    /// Minimizing the number of coins
+    struct Coins {
+        coins: Vec<u32>,
+        amount: u32,
+    }
+
+    impl BranchAndBound for Coins {
+        type Node = (u32, u32);
+        type Value = u32;
+
+        fn root(&self) -> Self::Node {
+            (0, 0)
+        }
+        fn branches(&self, &(sum, count): &Self::Node, out: &mut Vec<Self::Node>) {
+            out.extend(
+                self.coins
+                    .iter()
+                    .filter(|c| sum + *c <= self.amount)
+                    .map(|c| (sum + c, count + 1)),
+            );
+        }
+        fn value(&self, &(sum, count): &Self::Node) -> Option<u32> {
+            (sum == self.amount).then_some(count)
+        }
+        fn bound(&self, &(sum, count): &Self::Node) -> u32 {
+            let largest = *self.coins.iter().max().unwrap();
+            count + (self.amount - sum).div_ceil(largest)
+        }
+        fn is_better(&self, candidate: u32, incumbent: u32) -> bool {
+            candidate < incumbent
+        }
+        fn dominance_key(&self, node: &Self::Node) -> Option<u64> {
+            Some(node.0 as u64)
+        }
+        fn dominates(&self, a: &Self::Node, b: &Self::Node) -> bool {
+            a.1 <= b.1
+        }
+    }
+
+    #[test]
+    fn test_maximize() {
+        let items = vec![(12, 4), (2, 2), (1, 1), (1, 2), (4, 10), (3, 7), (5, 8)];
+        for dominance in [false, true] {
+            for exploration in [Exploration::DepthFirst, Exploration::BestFirst] {
+                let problem = Knapsack {
+                    items: items.clone(),
+                    capacity: 15,
+                    dominance,
+                };
+                let options = BranchAndBoundOptions::default().exploration(exploration);
+                let optimum = branch_and_bound(&problem, options).unwrap();
+                // Everything but the heaviest and the least valuable item
+                assert_eq!(optimum.value, 29);
+                assert_eq!(optimum.node.1, 15);
+                assert!(optimum.proven);
+                if !dominance {
+                    assert_eq!(optimum.stats.dominated, 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_minimize_and_limits() {
+        let problem = Coins {
+            coins: vec![1, 5, 6, 9],
+            amount: 11,
+        };
+        let optimum = branch_and_bound(&problem, BranchAndBoundOptions::default()).unwrap();
+        assert_eq!(optimum.value, 2);
+        assert!(optimum.proven);
+
+        let limited = branch_and_bound(&problem, BranchAndBoundOptions::default().node_limit(1));
+        assert!(limited.is_none_or(|o| !o.proven));
+        let impossible = Coins {
+            coins: vec![4],
+            amount: 11,
+        };
+        assert_eq!(
+            branch_and_bound(&impossible, BranchAndBoundOptions::default()),
+            None
+        );
+    }
+}
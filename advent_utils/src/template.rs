@@ -0,0 +1,716 @@
+//! Parsers generated from template strings.
+//!
+//! ```ignore
+//! line_parser! {
+//!     #[derive(Debug)]
+//!     struct Button = "Button {name}: X+{x}, Y+{y}" {
+//!         name: char,
+//!         x: i64,
+//!         y: i64,
+//!     }
+//!
+//!     #[derive(Debug)]
+//!     enum Instruction {
+//!         Cpy = "cpy {} {}" (Operand, Register),
+//!         Inc = "inc {}" (Register),
+//!         Jnz = "jnz {value} {offset}" { value: Operand, offset: i32 },
+//!         Nop = "nop",
+//!     }
+//! }
+//!
+//! let buttons: Vec<Button> = parse_lines(input)?;
+//! ```
+//!
+//! - Named fields are referenced as `{name}`, tuple fields are filled by `{}` in order.
+//! - Enum variants are tried in the order of declaration, the first one that matches wins.
+//!   If none matches, the error of the variant that got the furthest is returned.
+//! - `{{` and `}}` are literal braces.
+//! - Templates are checked when the crate compiles: every named field must appear,
+//!   no undeclared name may, and tuple variants need one `{}` per field.
+//! - Every type that implements [`FromTemplate`] can be used as a field,
+//!   including other types declared with [`line_parser!`](crate::line_parser).
+use glam::{I64Vec2, IVec2};
+use nom::{
+    bytes::complete::{tag, take_while1},
+    character::complete::{anychar, char, space0},
+    multi::separated_list1,
+    number::complete::double,
+    IResult, Parser,
+};
+use thiserror::Error;
+
+pub trait FromTemplate<'t>: Sized {
+    fn parse_template(input: &'t str) -> IResult<&'t str, Self>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("line {line}, column {column}: cannot parse {text:?}")]
+pub struct TemplateError {
+    /// 1-based
+    pub line: usize,
+    /// 1-based, in chars
+    pub column: usize,
+    /// The line which failed to parse
+    pub text: String,
+}
+
+impl TemplateError {
+    fn at(input: &str, offset: usize) -> Self {
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            text: input[line_start..line_end].trim_end().to_string(),
+        }
+    }
+}
+
+/// Parses the whole `input` as a single `T`, trailing whitespace is ignored
+pub fn parse_full<'t, T: FromTemplate<'t>>(input: &'t str) -> Result<T, TemplateError> {
+    let offset_of = |rest: &str| input.len() - rest.len();
+    match T::parse_template(input) {
+        Ok((rest, value)) if rest.trim().is_empty() => Ok(value),
+        Ok((rest, _)) => Err(TemplateError::at(input, offset_of(rest))),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+            Err(TemplateError::at(input, offset_of(e.input)))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(TemplateError::at(input, input.len())),
+    }
+}
+
+/// Parses every non-empty line of the `input` as `T`
+pub fn parse_lines<'t, T: FromTemplate<'t>>(input: &'t str) -> Result<Vec<T>, TemplateError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            parse_full(line).map_err(|e| TemplateError {
+                line: e.line + i,
+                ..e
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Segment<'t> {
+    Literal(&'t str),
+    Field(&'t str),
+}
+
+/// Splits the template into literals and `{field}` placeholders
+#[derive(Clone)]
+pub struct Segments<'t> {
+    template: &'t str,
+}
+
+pub fn segments(template: &str) -> Segments<'_> {
+    Segments { template }
+}
+
+impl<'t> Iterator for Segments<'t> {
+    type Item = Segment<'t>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let t = self.template;
+        if t.is_empty() {
+            return None;
+        }
+        if let Some(rest) = t.strip_prefix("{{").or_else(|| t.strip_prefix("}}")) {
+            self.template = rest;
+            return Some(Segment::Literal(&t[..1]));
+        }
+        if let Some(rest) = t.strip_prefix('{') {
+            let end = rest
+                .find('}')
+                .unwrap_or_else(|| panic!("unclosed {{ in template {t:?}"));
+            self.template = &rest[end + 1..];
+            return Some(Segment::Field(&rest[..end]));
+        }
+        let end = t.find(['{', '}']).unwrap_or(t.len());
+        let end = if end == 0 { 1 } else { end };
+        self.template = &t[end..];
+        Some(Segment::Literal(&t[..end]))
+    }
+}
+
+/// Next placeholder of the template from `start`, as `(name start, name end, after it)`
+///
+/// # Panics
+/// If a `{` is not closed
+const fn next_placeholder(template: &[u8], mut start: usize) -> Option<(usize, usize, usize)> {
+    while start < template.len() {
+        let doubled = start + 1 < template.len() && template[start + 1] == template[start];
+        match template[start] {
+            b'{' | b'}' if doubled => start += 2,
+            b'{' => {
+                let mut end = start + 1;
+                while end < template.len() && template[end] != b'}' {
+                    end += 1;
+                }
+                assert!(end < template.len(), "unclosed {{ in template");
+                return Some((start + 1, end, end + 1));
+            }
+            _ => start += 1,
+        }
+    }
+    None
+}
+
+const fn is_name_at(template: &[u8], start: usize, end: usize, name: &str) -> bool {
+    let name = name.as_bytes();
+    if end - start != name.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < name.len() {
+        if template[start + i] != name[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Checks that a template names exactly the declared fields,
+/// [`line_parser!`](crate::line_parser) evaluates it at compile time.
+///
+/// # Panics
+/// If the template names an undeclared field or omits a declared one
+#[doc(hidden)]
+pub const fn check_named_template(template: &str, fields: &[&str]) {
+    let bytes = template.as_bytes();
+    let mut seen = 0u128;
+    assert!(fields.len() <= 128, "too many fields for a template");
+    let mut at = 0;
+    while let Some((start, end, next)) = next_placeholder(bytes, at) {
+        let mut i = 0;
+        while i < fields.len() && !is_name_at(bytes, start, end, fields[i]) {
+            i += 1;
+        }
+        assert!(i < fields.len(), "template names an undeclared field");
+        seen |= 1 << i;
+        at = next;
+    }
+    assert!(
+        seen.count_ones() as usize == fields.len(),
+        "template omits a declared field"
+    );
+}
+
+/// Checks that a template has one `{}` per field,
+/// [`line_parser!`](crate::line_parser) evaluates it at compile time.
+///
+/// # Panics
+/// If the number of placeholders differs
+#[doc(hidden)]
+pub const fn check_tuple_template(template: &str, fields: usize) {
+    let bytes = template.as_bytes();
+    let mut count = 0;
+    let mut at = 0;
+    while let Some((_, _, next)) = next_placeholder(bytes, at) {
+        count += 1;
+        at = next;
+    }
+    assert!(
+        count == fields,
+        "template has a different number of {{}} than fields"
+    );
+}
+
+/// Consumes literals of the template from the input
+/// until the next field placeholder, returns its name.
+///
+/// Returns `None` if the template is over.
+#[doc(hidden)]
+pub fn skip_literals<'i, 't>(
+    segments: &mut Segments<'t>,
+    mut input: &'i str,
+) -> IResult<&'i str, Option<&'t str>> {
+    for segment in segments {
+        match segment {
+            Segment::Literal(lit) => {
+                (input, _) = tag(lit).parse(input)?;
+            }
+            Segment::Field(name) => return Ok((input, Some(name))),
+        }
+    }
+    Ok((input, None))
+}
+
+macro_rules! impl_for_nom_nums {
+    ($($typ:ident),+) => {
+        $(
+            impl<'t> FromTemplate<'t> for $typ {
+                fn parse_template(input: &'t str) -> IResult<&'t str, Self> {
+                    nom::character::complete::$typ(input)
+                }
+            }
+        )+
+    };
+}
+
+impl_for_nom_nums! {u8, u16, u32, u64, u128, i8, i16, i32, i64, i128}
+
+impl<'t> FromTemplate<'t> for usize {
+    fn parse_template(input: &'t str) -> IResult<&'t str, Self> {
+        crate::nom::parse_usize(input)
+    }
+}
+
+impl<'t> FromTemplate<'t> for isize {
+    fn parse_template(input: &'t str) -> IResult<&'t str, Self> {
+        crate::nom::parse_isize(input)
+    }
+}
+
+impl<'t> FromTemplate<'t> for f64 {
+    fn parse_template(input: &'t str) -> IResult<&'t str, Self> {
+        double(input)
+    }
+}
+
+impl<'t> FromTemplate<'t> for char {
+    fn parse_template(input: &'t str) -> IResult<&'t str, Self> {
+        anychar(input)
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// A word of letters, digits and underscores
+impl<'t> FromTemplate<'t> for &'t str {
+    fn parse_template(input: &'t str) -> IResult<&'t str, Self> {
+        take_while1(is_word_char).parse(input)
+    }
+}
+
+/// A word of letters, digits and underscores
+impl<'t> FromTemplate<'t> for String {
+    fn parse_template(input: &'t str) -> IResult<&'t str, Self> {
+        take_while1(is_word_char).map(String::from).parse(input)
+    }
+}
+
+/// Comma separated list, spaces after commas are allowed
+impl<'t, T: FromTemplate<'t>> FromTemplate<'t> for Vec<T> {
+    fn parse_template(input: &'t str) -> IResult<&'t str, Self> {
+        separated_list1((char(','), space0), T::parse_template).parse(input)
+    }
+}
+
+/// `x,y`, spaces after the comma are allowed
+impl<'t> FromTemplate<'t> for IVec2 {
+    fn parse_template(input: &'t str) -> IResult<&'t str, Self> {
+        (i32::parse_template, char(','), space0, i32::parse_template)
+            .map(|(x, _, _, y)| IVec2::new(x, y))
+            .parse(input)
+    }
+}
+
+/// `x,y`, spaces after the comma are allowed
+impl<'t> FromTemplate<'t> for I64Vec2 {
+    fn parse_template(input: &'t str) -> IResult<&'t str, Self> {
+        (i64::parse_template, char(','), space0, i64::parse_template)
+            .map(|(x, _, _, y)| I64Vec2::new(x, y))
+            .parse(input)
+    }
+}
+
+/// Declares structs and enums together with their [`FromTemplate`] implementation.
+///
+/// See the [`template`](crate::template) module for the syntax.
+/// A template which does not match the fields fails to compile:
+///
+/// ```compile_fail
+/// advent_utils::line_parser! {
+///     struct Point = "{x},{z}" {
+///         x: i32,
+///         y: i32,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! line_parser {
+    () => {};
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident<$lt:lifetime> = $template:literal {
+            $($(#[$fmeta:meta])* $fvis:vis $field:ident : $fty:ty),* $(,)?
+        }
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        $vis struct $name<$lt> {
+            $($(#[$fmeta])* $fvis $field: $fty),*
+        }
+        impl<$lt> $crate::template::FromTemplate<$lt> for $name<$lt> {
+            fn parse_template(input: &$lt str) -> $crate::nom::IResult<&$lt str, Self> {
+                $crate::line_parser!(@parse input, $template, [Self], [named { $($field: $fty),* }])
+            }
+        }
+        $crate::line_parser!($($rest)*);
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident = $template:literal {
+            $($(#[$fmeta:meta])* $fvis:vis $field:ident : $fty:ty),* $(,)?
+        }
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$fmeta])* $fvis $field: $fty),*
+        }
+        impl<'input> $crate::template::FromTemplate<'input> for $name {
+            fn parse_template(input: &'input str) -> $crate::nom::IResult<&'input str, Self> {
+                $crate::line_parser!(@parse input, $template, [Self], [named { $($field: $fty),* }])
+            }
+        }
+        $crate::line_parser!($($rest)*);
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident<$lt:lifetime> {
+            $(
+                $(#[$vmeta:meta])*
+                $variant:ident = $template:literal
+                $(($($tty:ty),* $(,)?))?
+                $({$($field:ident : $fty:ty),* $(,)?})?
+            ),* $(,)?
+        }
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        $vis enum $name<$lt> {
+            $(
+                $(#[$vmeta])*
+                $variant $(($($tty),*))? $({$($field: $fty),*})?
+            ),*
+        }
+        impl<$lt> $crate::template::FromTemplate<$lt> for $name<$lt> {
+            fn parse_template(input: &$lt str) -> $crate::nom::IResult<&$lt str, Self> {
+                let mut furthest = $crate::nom::error::Error::new(
+                    input,
+                    $crate::nom::error::ErrorKind::Alt,
+                );
+                $(
+                    let res = (|input: &$lt str| -> $crate::nom::IResult<&$lt str, Self> {
+                        $crate::line_parser!(
+                            @parse input, $template, [Self::$variant],
+                            [$(tuple ($($tty),*))? $(named {$($field: $fty),*})?]
+                        )
+                    })(input);
+                    match res {
+                        Err($crate::nom::Err::Error(e)) => {
+                            if e.input.len() < furthest.input.len() {
+                                furthest = e;
+                            }
+                        }
+                        res => return res,
+                    }
+                )*
+                Err($crate::nom::Err::Error(furthest))
+            }
+        }
+        $crate::line_parser!($($rest)*);
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$vmeta:meta])*
+                $variant:ident = $template:literal
+                $(($($tty:ty),* $(,)?))?
+                $({$($field:ident : $fty:ty),* $(,)?})?
+            ),* $(,)?
+        }
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $(
+                $(#[$vmeta])*
+                $variant $(($($tty),*))? $({$($field: $fty),*})?
+            ),*
+        }
+        impl<'input> $crate::template::FromTemplate<'input> for $name {
+            fn parse_template(input: &'input str) -> $crate::nom::IResult<&'input str, Self> {
+                let mut furthest = $crate::nom::error::Error::new(
+                    input,
+                    $crate::nom::error::ErrorKind::Alt,
+                );
+                $(
+                    let res = (|input: &'input str| -> $crate::nom::IResult<&'input str, Self> {
+                        $crate::line_parser!(
+                            @parse input, $template, [Self::$variant],
+                            [$(tuple ($($tty),*))? $(named {$($field: $fty),*})?]
+                        )
+                    })(input);
+                    match res {
+                        Err($crate::nom::Err::Error(e)) => {
+                            if e.input.len() < furthest.input.len() {
+                                furthest = e;
+                            }
+                        }
+                        res => return res,
+                    }
+                )*
+                Err($crate::nom::Err::Error(furthest))
+            }
+        }
+        $crate::line_parser!($($rest)*);
+    };
+    (@parse $input:ident, $template:literal, [$($ctor:tt)*], []) => {{
+        const _: () = $crate::template::check_tuple_template($template, 0);
+        let mut segments = $crate::template::segments($template);
+        let (rest, field) = $crate::template::skip_literals(&mut segments, $input)?;
+        if let Some(field) = field {
+            panic!("{{{field}}} is not declared for template {:?}", $template);
+        }
+        Ok((rest, $($ctor)* {}))
+    }};
+    (@parse $input:ident, $template:literal, [$($ctor:tt)*], [tuple ($($tty:ty),*)]) => {{
+        const _: () = $crate::template::check_tuple_template(
+            $template,
+            [$(stringify!($tty)),*].len(),
+        );
+        let mut segments = $crate::template::segments($template);
+        let mut rest = $input;
+        let value = $($ctor)*($({
+            let (r, field) = $crate::template::skip_literals(&mut segments, rest)?;
+            if field.is_none() {
+                panic!("not enough {{}} in template {:?}", $template);
+            }
+            let (r, value) = <$tty as $crate::template::FromTemplate>::parse_template(r)?;
+            rest = r;
+            value
+        }),*);
+        let (rest, field) = $crate::template::skip_literals(&mut segments, rest)?;
+        if field.is_some() {
+            panic!("too many {{}} in template {:?}", $template);
+        }
+        Ok((rest, value))
+    }};
+    (@parse $input:ident, $template:literal, [$($ctor:tt)*], [named {$($field:ident : $fty:ty),*}]) => {{
+        const _: () = $crate::template::check_named_template(
+            $template,
+            &[$(stringify!($field)),*],
+        );
+        let mut segments = $crate::template::segments($template);
+        let mut rest = $input;
+        $(let mut $field: Option<$fty> = None;)*
+        loop {
+            let (r, field) = $crate::template::skip_literals(&mut segments, rest)?;
+            rest = r;
+            let Some(field) = field else {
+                break;
+            };
+            match field {
+                $(stringify!($field) => {
+                    let (r, value) = <$fty as $crate::template::FromTemplate>::parse_template(rest)?;
+                    rest = r;
+                    $field = Some(value);
+                })*
+                _ => panic!("{{{field}}} is not declared for template {:?}", $template),
+            }
+        }
+        Ok((rest, $($ctor)* {
+            $($field: $field.unwrap_or_else(|| {
+                panic!("{{{}}} is missing in template {:?}", stringify!($field), $template)
+            })),*
+        }))
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use super::{
+        check_named_template, check_tuple_template, parse_full, parse_lines, segments, Segment,
+        TemplateError,
+    };
+
+    crate::line_parser! {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Button = "Button {name}: X+{x}, Y+{y}" {
+            name: char,
+            x: i64,
+            y: i64,
+        }
+
+        #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+        enum Register {
+            A = "a",
+            B = "b",
+        }
+
+        #[derive(Debug, PartialEq, Eq)]
+        enum Operand {
+            Register = "{}" (Register),
+            Value = "{}" (i32),
+        }
+
+        #[derive(Debug, PartialEq, Eq)]
+        enum Instruction {
+            Cpy = "cpy {} {}" (Operand, Register),
+            Jnz = "jnz {value} {offset}" { value: Operand, offset: i32 },
+            Nop = "nop",
+        }
+
+        #[derive(Debug, PartialEq, Eq)]
+        struct Valve<'t> = "Valve {name} has flow rate={rate}; tunnels lead to valves {next}" {
+            name: &'t str,
+            rate: u32,
+            next: Vec<&'t str>,
+        }
+
+        #[derive(Debug, PartialEq, Eq)]
+        struct Robot = "p={p} v={v}" {
+            p: IVec2,
+            v: IVec2,
+        }
+
+        #[derive(Debug, PartialEq, Eq)]
+        struct Braces = "{{{value}}}" {
+            value: u8,
+        }
+    }
+
+    #[test]
+    fn test_segments() {
+        assert_eq!(
+            segments("a{x}b{{c}}{}").collect::<Vec<_>>(),
+            vec![
+                Segment::Literal("a"),
+                Segment::Field("x"),
+                Segment::Literal("b"),
+                Segment::Literal("{"),
+                Segment::Literal("c"),
+                Segment::Literal("}"),
+                Segment::Field(""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_struct() {
+        assert_eq!(
+            parse_full::<Button>("Button A: X+94, Y+34"),
+            Ok(Button {
+                name: 'A',
+                x: 94,
+                y: 34
+            })
+        );
+        assert_eq!(
+            parse_full::<Robot>("p=0,4 v=3,-3"),
+            Ok(Robot {
+                p: IVec2::new(0, 4),
+                v: IVec2::new(3, -3)
+            })
+        );
+        assert_eq!(parse_full::<Braces>("{42}"), Ok(Braces { value: 42 }));
+    }
+
+    #[test]
+    fn test_borrowed() {
+        let valves = parse_lines::<Valve>(
+            "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB\n\
+             Valve BB has flow rate=13; tunnels lead to valves CC",
+        )
+        .unwrap();
+        assert_eq!(
+            valves,
+            vec![
+                Valve {
+                    name: "AA",
+                    rate: 0,
+                    next: vec!["DD", "II", "BB"]
+                },
+                Valve {
+                    name: "BB",
+                    rate: 13,
+                    next: vec!["CC"]
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum() {
+        assert_eq!(
+            parse_lines::<Instruction>("cpy 41 a\ncpy a b\n\njnz b -2\nnop\n"),
+            Ok(vec![
+                Instruction::Cpy(Operand::Value(41), Register::A),
+                Instruction::Cpy(Operand::Register(Register::A), Register::B),
+                Instruction::Jnz {
+                    value: Operand::Register(Register::B),
+                    offset: -2
+                },
+                Instruction::Nop,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            parse_lines::<Instruction>("cpy 41 a\n\ncpy 41 c\n"),
+            Err(TemplateError {
+                line: 3,
+                column: 8,
+                text: "cpy 41 c".to_string()
+            })
+        );
+        assert_eq!(
+            parse_lines::<Instruction>("nop\nnop nop"),
+            Err(TemplateError {
+                line: 2,
+                column: 4,
+                text: "nop nop".to_string()
+            })
+        );
+        assert_eq!(
+            parse_full::<Button>("Button A: X+94,\n Y+34"),
+            Err(TemplateError {
+                line: 1,
+                column: 15,
+                text: "Button A: X+94,".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_template() {
+        check_named_template("{{{a}}} {b} {a}", &["a", "b"]);
+        check_tuple_template("{} {{}} {}", 2);
+        check_tuple_template("nop", 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "template names an undeclared field")]
+    fn test_undeclared_field() {
+        check_named_template("Button {name}: X+{x}, Y+{z}", &["name", "x", "y"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "template omits a declared field")]
+    fn test_missing_field() {
+        check_named_template("Button {name}: X+{x}", &["name", "x", "y"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "template has a different number of {} than fields")]
+    fn test_wrong_placeholder_count() {
+        check_tuple_template("cpy {} {}", 1);
+    }
+}
@@ -1,44 +1,29 @@
-use std::collections::BTreeMap;
+use advent_utils::{
+    graph::{held_karp, Objective, Route},
+    interner::Interner,
+};
 
-use itertools::Itertools;
-
-fn paths_distances(file_content: &str) -> impl Iterator<Item = u64> + '_ {
-    let mut neighbours = BTreeMap::new();
+fn best_route(file_content: &str, objective: Objective) -> u64 {
+    let mut cities = Interner::new();
+    let mut routes = Vec::new();
     for line in file_content.lines() {
         let (source, b) = line.split_once(" to ").unwrap();
         let (target, value) = b.split_once(" = ").unwrap();
         let value = value.parse::<u64>().unwrap();
-        neighbours
-            .entry(source)
-            .or_insert(BTreeMap::new())
-            .insert(target, value);
-        neighbours
-            .entry(target)
-            .or_insert(BTreeMap::new())
-            .insert(source, value);
+        routes.push((cities.intern(source), cities.intern(target), value));
+    }
+    let mut distances = vec![vec![0; cities.len()]; cities.len()];
+    for (source, target, value) in routes {
+        distances[source as usize][target as usize] = value;
+        distances[target as usize][source as usize] = value;
     }
-    let cities = neighbours.keys().copied().collect_vec();
-    (0..neighbours.len())
-        .permutations(neighbours.len())
-        .map(move |path| {
-            path.iter()
-                .tuple_windows()
-                .map(|(from, target)| {
-                    neighbours
-                        .get(cities[*from])
-                        .unwrap()
-                        .get(cities[*target])
-                        .copied()
-                        .unwrap_or(u64::MAX)
-                })
-                .fold(0u64, |a, b| a.saturating_add(b))
-        })
+    held_karp(&distances, Route::Path, objective).unwrap().cost
 }
 pub fn solve_part_1(file_content: &str) -> u64 {
-    paths_distances(file_content).min().unwrap()
+    best_route(file_content, Objective::Min)
 }
 pub fn solve_part_2(file_content: &str) -> u64 {
-    paths_distances(file_content).max().unwrap()
+    best_route(file_content, Objective::Max)
 }
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 
+use advent_utils::graph::{held_karp, Objective, Route};
 use itertools::Itertools;
 use nom::Parser;
 
@@ -18,29 +19,24 @@ pub fn solve_part_2(file_content: &str) -> i64 {
 
 fn solve(pleasure: BTreeMap<&str, BTreeMap<&str, i64>>) -> i64 {
     let persons = pleasure.keys().copied().collect_vec();
-    (0..persons.len())
-        .permutations(persons.len())
-        .map(|indexes| {
-            indexes
+    let happiness = |i: &str, j: &str| {
+        pleasure
+            .get(i)
+            .and_then(|m| m.get(j).copied())
+            .unwrap_or_default()
+    };
+    let distances = persons
+        .iter()
+        .map(|i| {
+            persons
                 .iter()
-                .copied()
-                .chain(std::iter::once(indexes[0]))
-                .map(|x| persons[x])
-                .tuple_windows()
-                .map(|(i, j)| {
-                    pleasure
-                        .get(i)
-                        .and_then(|m| m.get(j).copied())
-                        .unwrap_or_default()
-                        + pleasure
-                            .get(j)
-                            .and_then(|m| m.get(i).copied())
-                            .unwrap_or_default()
-                })
-                .sum::<i64>()
+                .map(|j| happiness(i, j) + happiness(j, i))
+                .collect_vec()
         })
-        .max()
+        .collect_vec();
+    held_karp(&distances, Route::Cycle, Objective::Max)
         .unwrap()
+        .cost
 }
 
 fn parse_pleasure(file_content: &str) -> BTreeMap<&str, BTreeMap<&str, i64>> {
@@ -95,7 +91,6 @@ mod tests {
     }
 
     #[test]
-    #[ignore] // slow
     fn test_part2_actual() {
         assert_eq!(format!("{}", solve_part_2(ACTUAL)), "640");
     }
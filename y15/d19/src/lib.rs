@@ -1,5 +1,6 @@
 mod trie;
 
+use advent_utils::parse::sections;
 use itertools::Itertools;
 use trie::Trie;
 
@@ -59,14 +60,14 @@ pub fn solve_part_2(file_content: &str) -> usize {
 }
 
 fn parse_input(file_content: &str) -> (Vec<(&str, Vec<&str>)>, &str) {
-    let (replacements_input, molecule) = file_content.split_once("\n\n").unwrap();
+    let mut sections = sections(file_content);
+    let replacements_input = sections.expect_next().unwrap();
+    let molecule = sections.expect_next().unwrap().text();
 
     let mut replacements = replacements_input
-        .lines()
-        .map(|line| {
-            let (left, right) = line.split_once(" => ").unwrap();
-            (left, right)
-        })
+        .parse_lines(|line| line.split_once(" => ").ok_or("expected \" => \""))
+        .unwrap()
+        .into_iter()
         .into_group_map_by(|x| x.0)
         .into_iter()
         .map(|(a, b)| (a, b.into_iter().map(|x| x.1).collect_vec()))
@@ -1,6 +1,7 @@
-use std::collections::{BinaryHeap, HashSet};
-
-use advent_utils::declare_field;
+use advent_utils::{
+    declare_field,
+    search::{self, SearchProblem},
+};
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 struct Player {
@@ -320,20 +321,6 @@ impl BitState {
     }
 }
 
-impl PartialOrd for BitState {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-impl Ord for BitState {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other
-            .get_boss_hp()
-            .cmp(&self.get_boss_hp())
-            .then_with(|| other.get_spent_mana().cmp(&self.get_spent_mana()))
-    }
-}
-
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 enum Game {
     Win(BitState),
@@ -362,116 +349,69 @@ impl Game {
     }
 }
 
-#[tracing::instrument(skip(player, boss))]
-fn least_mana_spent(player: Player, boss: Boss, player_hp_per_move: u8) -> usize {
-    let mut heap = BinaryHeap::new();
-
-    let state = BitState(0)
-        .set_player_hp(player.hp as u8)
-        .set_player_mana(player.mana as u16)
-        .set_boss_hp(boss.hp as u8)
-        .set_boss_damage(boss.damage as u8);
-
-    heap.push(state);
-
-    let mut min_spent_mana_at_boss_death = u16::MAX;
-
-    let mut visited = HashSet::new();
+/// Winning the fight while spending the least mana
+struct Duel {
+    start: BitState,
+    player_hp_per_move: u8,
+}
 
-    while let Some(state) = heap.pop() {
-        if state.get_spent_mana() >= min_spent_mana_at_boss_death {
-            continue;
-        }
-        if !visited.insert(state) {
-            continue;
-        }
+impl SearchProblem for Duel {
+    type State = BitState;
+    type Cost = usize;
 
-        let state = state.dec_player_hp(player_hp_per_move);
+    fn starts(&self) -> impl IntoIterator<Item = BitState> {
+        [self.start]
+    }
 
-        if state.get_player_hp() == 0 {
-            continue;
-        }
+    /// Player move followed by the boss move
+    fn successors(&self, state: &BitState) -> impl IntoIterator<Item = (BitState, usize)> {
+        let state = state.dec_player_hp(self.player_hp_per_move);
+        let spent = state.get_spent_mana();
 
         // before player move we should apply effects
         let mut armor = 0;
+        let game = if state.get_player_hp() == 0 {
+            Game::Loser
+        } else {
+            state.apply_effects(&mut armor)
+        };
 
-        let game = state.apply_effects(&mut armor);
+        [
+            game.and_then_maybe(|s| s.try_cast_magic_missile()),
+            game.and_then_maybe(|s| s.try_cast_drain()),
+            game.map_maybe(|s| s.try_cast_recharge()),
+            game.map_maybe(|s| s.try_cast_poison()),
+            game.map_maybe(|s| s.try_cast_shield()),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|game| match game.and_then(|s| s.boss_move()) {
+            Game::Win(s) | Game::Playing(s) => Some(s),
+            Game::Loser => None,
+        })
+        .map(move |s| (s, (s.get_spent_mana() - spent) as usize))
+    }
 
-        match game
-            .and_then_maybe(|s| s.try_cast_magic_missile())
-            .map(|x| x.and_then(|x| x.boss_move()))
-        {
-            Some(Game::Win(s)) => {
-                if s.get_spent_mana() < min_spent_mana_at_boss_death {
-                    min_spent_mana_at_boss_death = s.get_spent_mana();
-                }
-            }
-            Some(Game::Playing(s)) => {
-                heap.push(s);
-            }
-            _ => {}
-        }
-        match game
-            .and_then_maybe(|s| s.try_cast_drain())
-            .map(|x| x.and_then(|x| x.boss_move()))
-        {
-            Some(Game::Win(s)) => {
-                if s.get_spent_mana() < min_spent_mana_at_boss_death {
-                    min_spent_mana_at_boss_death = s.get_spent_mana();
-                }
-            }
-            Some(Game::Playing(s)) => {
-                heap.push(s);
-            }
-            _ => {}
-        }
-        match game
-            .map_maybe(|s| s.try_cast_recharge())
-            .map(|x| x.and_then(|x| x.boss_move()))
-        {
-            Some(Game::Win(s)) => {
-                if s.get_spent_mana() < min_spent_mana_at_boss_death {
-                    min_spent_mana_at_boss_death = s.get_spent_mana();
-                }
-            }
-            Some(Game::Playing(s)) => {
-                heap.push(s);
-            }
-            _ => {}
-        }
-        match game
-            .map_maybe(|s| s.try_cast_poison())
-            .map(|x| x.and_then(|x| x.boss_move()))
-        {
-            Some(Game::Win(s)) => {
-                if s.get_spent_mana() < min_spent_mana_at_boss_death {
-                    min_spent_mana_at_boss_death = s.get_spent_mana();
-                }
-            }
-            Some(Game::Playing(s)) => {
-                heap.push(s);
-            }
-            _ => {}
-        }
-        match game
-            .map_maybe(|s| s.try_cast_shield())
-            .map(|x| x.and_then(|x| x.boss_move()))
-        {
-            Some(Game::Win(s)) => {
-                if s.get_spent_mana() < min_spent_mana_at_boss_death {
-                    min_spent_mana_at_boss_death = s.get_spent_mana();
-                }
-            }
-            Some(Game::Playing(s)) => {
-                heap.push(s);
-            }
-            _ => {}
-        }
+    fn is_goal(&self, state: &BitState) -> bool {
+        state.get_boss_hp() == 0
     }
+}
+
+#[tracing::instrument(skip(player, boss))]
+fn least_mana_spent(player: Player, boss: Boss, player_hp_per_move: u8) -> usize {
+    let start = BitState(0)
+        .set_player_hp(player.hp as u8)
+        .set_player_mana(player.mana as u16)
+        .set_boss_hp(boss.hp as u8)
+        .set_boss_damage(boss.damage as u8);
 
-    // 5 actions
-    // effects
-    min_spent_mana_at_boss_death as usize
+    let duel = Duel {
+        start,
+        player_hp_per_move,
+    };
+    let solution = search::dijkstra(&duel).unwrap();
+    tracing::info!(?solution.stats);
+    solution.cost
 }
 
 #[cfg(test)]
@@ -55,6 +55,7 @@ fn execute(file_content: &str, screen_size: UVec2) -> HashSet<UVec2> {
     screen
 }
 
+#[cfg(test)]
 fn print_screen(
     screen: &HashSet<UVec2>,
     screen_size: UVec2,
@@ -76,9 +77,7 @@ fn print_screen(
 #[tracing::instrument(skip(file_content))]
 pub fn solve_part_2(file_content: &str) -> String {
     let screen = execute(file_content, UVec2::new(50, 6));
-    let mut output = String::new();
-    print_screen(&screen, UVec2::new(50, 6), &mut output).expect("Should be valid");
-    output
+    advent_utils::ocr::ocr_points(&screen).unwrap()
 }
 
 #[derive(Debug)]
@@ -193,8 +192,12 @@ mod tests {
                 .without_time()
                 .finish(),
         );
+        let mut output = String::new();
+        let screen_size = UVec2::new(50, 6);
+        let screen = execute(ACTUAL, screen_size);
+        super::print_screen(&screen, screen_size, &mut output).unwrap();
         assert_eq!(
-            solve_part_2(ACTUAL).trim(),
+            output.trim(),
             r#"
 .##..####.###..#..#.###..####.###....##.###...###.
 #..#.#....#..#.#..#.#..#....#.#..#....#.#..#.#....
@@ -205,5 +208,6 @@ mod tests {
 "#
             .trim()
         );
+        assert_eq!(solve_part_2(ACTUAL), "AFBUPZBJPS");
     }
 }
@@ -1,6 +1,8 @@
-use advent_utils::{declare_array, declare_field};
+use advent_utils::{
+    declare_array, declare_field,
+    search::{self, SearchProblem},
+};
 use itertools::Itertools;
-use std::collections::VecDeque;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct State<const N: usize>(usize);
@@ -78,31 +80,36 @@ impl<const N: usize> State<N> {
 
 const TARGET_LEVEL: usize = 3;
 
+/// Moving everything to the top floor from the given state
+struct Assembly<const N: usize>(State<N>);
+
+impl<const N: usize> SearchProblem for Assembly<N>
+where
+    State<N>: std::fmt::Display,
+{
+    type State = State<N>;
+    type Cost = usize;
+
+    fn starts(&self) -> impl IntoIterator<Item = State<N>> {
+        [self.0]
+    }
+    fn successors(&self, state: &State<N>) -> impl IntoIterator<Item = (State<N>, usize)> {
+        next_states(state).map(|s| (s, 1))
+    }
+    fn is_goal(&self, state: &State<N>) -> bool {
+        state.done()
+    }
+    fn dense_index(&self) -> Option<search::DenseIndex<State<N>>> {
+        Some((State::<N>::MAX_STATE.0 + 1, |state| state.0))
+    }
+}
+
 #[tracing::instrument(skip(input))]
 pub fn solve<const N: usize>(input: State<N>) -> usize
 where
     State<N>: std::fmt::Display,
 {
-    let max_state = State::<N>::MAX_STATE;
-    let mut min_steps_to = vec![usize::MAX; max_state.0 + 1];
-    let mut to_visit = VecDeque::new();
-    to_visit.push_back(input);
-    min_steps_to[input.0] = 0;
-    while let Some(state) = to_visit.pop_front() {
-        if state.done() {
-            return min_steps_to[state.0];
-        }
-        let prev = min_steps_to[state.0];
-        for s in next_states(&state) {
-            let steps = prev.saturating_add(1);
-            if min_steps_to[s.0] <= steps {
-                continue;
-            }
-            min_steps_to[s.0] = steps;
-            to_visit.push_back(s);
-        }
-    }
-    min_steps_to[max_state.0]
+    search::bfs(&Assembly(input)).map_or(usize::MAX, |solution| solution.cost)
 }
 
 fn next_states<const N: usize>(state: &State<N>) -> impl Iterator<Item = State<N>>
@@ -1,37 +1,30 @@
-use advent_utils::nom::{
-    self,
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{line_ending, multispace1},
-    combinator::{all_consuming, value},
-    multi::separated_list1,
-    parse_usize,
-    sequence::{preceded, separated_pair},
-    Parser,
-};
+use advent_utils::{line_parser, template::parse_lines};
 
 type Int = usize;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-#[repr(u8)]
-enum Register {
-    A,
-    B,
-    C,
-    D,
-}
+line_parser! {
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    #[repr(u8)]
+    enum Register {
+        A = "a",
+        B = "b",
+        C = "c",
+        D = "d",
+    }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Operand {
-    Integer(Int),
-    Register(Register),
-}
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    enum Operand {
+        Integer = "{}" (Int),
+        Register = "{}" (Register),
+    }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Jump {
-    Forward(usize),
-    Backward(usize),
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    enum Jump {
+        Forward = "{}" (usize),
+        Backward = "-{}" (usize),
+    }
 }
+
 impl Jump {
     fn apply(&self, ip: &mut usize) {
         match self {
@@ -45,14 +38,16 @@ impl Jump {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Instruction {
-    /// cpy x y copies x (either an integer or the value of a register) into register y.
-    Cpy(Operand, Register),
-    /// inc x increases the value of register x by one.
-    Inc(Register),
-    Dec(Register),
-    Jnz(Operand, Jump),
+line_parser! {
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    enum Instruction {
+        /// cpy x y copies x (either an integer or the value of a register) into register y.
+        Cpy = "cpy {} {}" (Operand, Register),
+        /// inc x increases the value of register x by one.
+        Inc = "inc {}" (Register),
+        Dec = "dec {}" (Register),
+        Jnz = "jnz {} {}" (Operand, Jump),
+    }
 }
 
 #[derive(Default, Copy, Clone, PartialEq, Eq)]
@@ -117,9 +112,7 @@ impl Cpu {
 
 #[tracing::instrument(skip(file_content))]
 pub fn solve_part_1(file_content: &str) -> Int {
-    let (_, instructions) = all_consuming(parse_instructions)
-        .parse(file_content.trim())
-        .unwrap();
+    let instructions = parse_lines::<Instruction>(file_content).unwrap();
 
     let mut cpu = Cpu::default();
 
@@ -129,9 +122,7 @@ pub fn solve_part_1(file_content: &str) -> Int {
 }
 #[tracing::instrument(skip(file_content))]
 pub fn solve_part_2(file_content: &str) -> Int {
-    let (_, instructions) = all_consuming(parse_instructions)
-        .parse(file_content.trim())
-        .unwrap();
+    let instructions = parse_lines::<Instruction>(file_content).unwrap();
 
     let mut cpu = Cpu {
         c: 1,
@@ -143,62 +134,6 @@ pub fn solve_part_2(file_content: &str) -> Int {
     cpu.a
 }
 
-fn parse_instructions(input: &str) -> nom::IResult<&str, Vec<Instruction>> {
-    separated_list1(line_ending, parse_instruction).parse(input)
-}
-fn parse_instruction(input: &str) -> nom::IResult<&str, Instruction> {
-    alt((parse_cpy, parse_inc, parse_dec, parse_jnz)).parse(input)
-}
-fn parse_cpy(input: &str) -> nom::IResult<&str, Instruction> {
-    preceded(
-        tag("cpy "),
-        separated_pair(parse_operand, multispace1, parse_register)
-            .map(|(operand, register)| Instruction::Cpy(operand, register)),
-    )
-    .parse(input)
-}
-fn parse_register(input: &str) -> nom::IResult<&str, Register> {
-    alt((
-        value(Register::A, tag("a")),
-        value(Register::B, tag("b")),
-        value(Register::C, tag("c")),
-        value(Register::D, tag("d")),
-    ))
-    .parse(input)
-}
-fn parse_operand(input: &str) -> nom::IResult<&str, Operand> {
-    alt((
-        parse_register.map(Operand::Register),
-        parse_usize.map(Operand::Integer),
-    ))
-    .parse(input)
-}
-fn parse_dec(input: &str) -> nom::IResult<&str, Instruction> {
-    preceded((tag("dec"), multispace1), parse_register)
-        .map(Instruction::Dec)
-        .parse(input)
-}
-fn parse_inc(input: &str) -> nom::IResult<&str, Instruction> {
-    preceded((tag("inc"), multispace1), parse_register)
-        .map(Instruction::Inc)
-        .parse(input)
-}
-fn parse_jnz(input: &str) -> nom::IResult<&str, Instruction> {
-    preceded(
-        (tag("jnz"), multispace1),
-        separated_pair(parse_operand, multispace1, parse_jump)
-            .map(|(operand, jump)| Instruction::Jnz(operand, jump)),
-    )
-    .parse(input)
-}
-fn parse_jump(input: &str) -> nom::IResult<&str, Jump> {
-    alt((
-        preceded(tag("-"), parse_usize).map(Jump::Backward),
-        parse_usize.map(Jump::Forward),
-    ))
-    .parse(input)
-}
-
 #[cfg(test)]
 mod tests {
     use super::{solve_part_1, solve_part_2};
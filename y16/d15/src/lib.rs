@@ -1,31 +1,21 @@
-use advent_utils::parse;
-use itertools::Itertools;
+use advent_utils::{math, parse};
 
 #[tracing::instrument(skip(file_content))]
 pub fn solve_part_1(file_content: &str) -> usize {
     let discs = parse_discs(file_content);
     solve(&discs)
 }
+/// The capsule reaches disc `i` at `t + i + 1`, so its position
+/// `start + t + i + 1` must be divisible by the number of positions
 fn solve(discs: &[Disc]) -> usize {
-    let mut positions = discs.iter().map(|x| x.positions()).collect_vec();
-    let mut current_pos = positions
-        .iter_mut()
-        .map(|x| x.nth(1).unwrap())
-        .collect_vec();
-    for t in 0.. {
-        if current_pos
+    let (t, _) = math::modular::crt(
+        discs
             .iter()
-            .copied()
             .enumerate()
-            .all(|(i, p)| (p + i) % discs[i].positions == 0)
-        {
-            return t;
-        }
-        for (dst, src) in current_pos.iter_mut().zip(positions.iter_mut()) {
-            *dst = src.next().unwrap();
-        }
-    }
-    usize::MAX
+            .map(|(i, disc)| (-((disc.start_pos + i + 1) as i128), disc.positions as i128)),
+    )
+    .expect("the discs never align");
+    t as usize
 }
 #[tracing::instrument(skip(file_content))]
 pub fn solve_part_2(file_content: &str) -> usize {
@@ -44,19 +34,10 @@ struct Disc {
     positions: usize,
 }
 
-impl Disc {
-    fn positions(&self) -> impl Iterator<Item = usize> {
-        (0..self.positions).cycle().skip(self.start_pos)
-    }
-}
-
 fn parse_discs(file_content: &str) -> Vec<Disc> {
     let mut nums = parse::nums::<usize>(file_content);
     let mut res = Vec::with_capacity(6);
-    loop {
-        let Some(_) = nums.next() else {
-            break;
-        };
+    while nums.next().is_some() {
         let positions = nums.next().unwrap();
         let start_position = nums.nth(1).unwrap();
         res.push(Disc {
@@ -1,71 +1,75 @@
 use std::fmt::Write;
 
-use advent_utils::{declare_field, glam::IVec2, nom::AsBytes};
+use advent_utils::{
+    declare_field,
+    glam::IVec2,
+    nom::AsBytes,
+    search::{self, SearchProblem},
+};
 use itertools::Itertools;
 use tracing::info;
 
+const VAULT: IVec2 = IVec2::new(3, 3);
+
+/// Walking from the top-left room to the vault, the state is the position with the path so far
+impl SearchProblem for Map<'_> {
+    type State = (IVec2, Vec<Turn>);
+    type Cost = usize;
+
+    fn starts(&self) -> impl IntoIterator<Item = Self::State> {
+        [(IVec2::ZERO, Vec::new())]
+    }
+
+    fn successors(
+        &self,
+        (pos, path): &Self::State,
+    ) -> impl IntoIterator<Item = (Self::State, usize)> {
+        let pos = *pos;
+        let doors = self.get_doors(path.iter().copied());
+        info!(?pos, ?path, %doors);
+        doors
+            .filter(move |_| pos != VAULT)
+            .filter_map(move |t| {
+                let next_pos = pos + t.to_vec();
+
+                (((0..=3i32).contains(&next_pos.y)) && (0..=3i32).contains(&next_pos.x))
+                    .then_some((next_pos, t))
+            })
+            .map(move |(next_pos, t)| {
+                let mut new_path = path.clone();
+                new_path.push(t);
+                ((next_pos, new_path), 1)
+            })
+    }
+
+    fn is_goal(&self, (pos, _): &Self::State) -> bool {
+        *pos == VAULT
+    }
+}
+
 #[tracing::instrument(ret)]
 pub fn part1(passcode: &str) -> String {
     let map = Map {
         passcode: passcode.trim(),
     };
-    for x in
-        pathfinding::prelude::dijkstra_reach(&(IVec2::new(0, 0), Vec::new()), |(pos, path), _| {
-            let pos = *pos;
-            let doors = map.get_doors(path.iter().copied());
-            let prev_path = path.clone();
-            info!(?pos, ?path, %doors);
-            doors
-                .filter_map(move |t| {
-                    let next_pos = pos + t.to_vec();
-
-                    (((0..=3i32).contains(&next_pos.y)) && (0..=3i32).contains(&next_pos.x))
-                        .then_some((next_pos, t))
-                })
-                .map(move |(next_pos, t)| {
-                    let mut new_path = prev_path.clone();
-                    new_path.push(t);
-                    ((next_pos, new_path), 1)
-                })
-        })
-    {
-        if x.node.0 == IVec2::new(3, 3) {
-            return x.node.1.into_iter().map(|x| x.to_letter() as char).join("");
-        }
-    }
-
-    "".to_owned()
+    search::bfs(&map)
+        .and_then(|solution| solution.path.last().cloned())
+        .map(|(_, path)| path.into_iter().map(|x| x.to_letter() as char).join(""))
+        .unwrap_or_default()
 }
 #[tracing::instrument(ret)]
 pub fn part2(passcode: &str) -> usize {
     let map = Map {
         passcode: passcode.trim(),
     };
+    // Every path is a different state, so the longest one is found by visiting all of them
     let mut max_path_len = 0;
-    for x in
-        pathfinding::prelude::dijkstra_reach(&(IVec2::new(0, 0), Vec::new()), |(pos, path), _| {
-            let pos = *pos;
-            let doors = map.get_doors(path.iter().copied());
-            let prev_path = path.clone();
-            info!(?pos, ?path, %doors);
-            doors
-                .filter(move |_| pos != IVec2::new(3, 3))
-                .filter_map(move |t| {
-                    let next_pos = pos + t.to_vec();
-
-                    (((0..=3i32).contains(&next_pos.y)) && (0..=3i32).contains(&next_pos.x))
-                        .then_some((next_pos, t))
-                })
-                .map(move |(next_pos, t)| {
-                    let mut new_path = prev_path.clone();
-                    new_path.push(t);
-                    ((next_pos, new_path), 1)
-                })
-        })
-    {
-        if x.node.0 == IVec2::new(3, 3) {
-            max_path_len = x.total_cost;
+    let mut stack = map.starts().into_iter().collect_vec();
+    while let Some(state) = stack.pop() {
+        if map.is_goal(&state) {
+            max_path_len = max_path_len.max(state.1.len());
         }
+        stack.extend(map.successors(&state).into_iter().map(|(s, _)| s));
     }
 
     max_path_len
@@ -213,7 +217,6 @@ mod tests {
     #[rstest]
     #[case::example("ihgpwlah", "370")]
     #[case::actual(ACTUAL, "526")]
-    #[ignore]
     fn test_part2(#[case] input: &str, #[case] expected: &str) {
         use crate::part2;
         assert_eq!(format!("{}", part2(input)), expected);
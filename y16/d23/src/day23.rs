@@ -3,7 +3,7 @@ use advent_utils::nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{self, line_ending, multispace1},
-    combinator::{all_consuming, value},
+    combinator::value,
     multi::separated_list1,
     sequence::{preceded, separated_pair},
     Parser,
@@ -130,9 +130,7 @@ fn toggled(instruction: Instruction) -> Option<Instruction> {
 
 #[tracing::instrument(skip(file_content))]
 pub fn part1(file_content: &str) -> Int {
-    let (_, mut instructions) = all_consuming(parse_instructions)
-        .parse(file_content.trim())
-        .unwrap();
+    let mut instructions = nom::parse_all(parse_instructions, file_content).unwrap();
 
     let mut cpu = Cpu {
         a: 7,
@@ -146,9 +144,7 @@ pub fn part1(file_content: &str) -> Int {
 
 #[tracing::instrument(skip(file_content))]
 pub fn part2(file_content: &str, start: Int) -> Int {
-    let (_, mut instructions) = all_consuming(parse_instructions)
-        .parse(file_content.trim())
-        .unwrap();
+    let mut instructions = nom::parse_all(parse_instructions, file_content).unwrap();
 
     let mut cpu = Cpu {
         a: start,
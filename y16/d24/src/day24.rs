@@ -1,136 +1,48 @@
-use advent_utils::{glam::IVec2, grid::NonDiagonal, parse};
-use fxhash::FxHashMap;
+use advent_utils::{
+    glam::IVec2,
+    graph::{contract_maze, held_karp, Objective, Route},
+    parse,
+};
+use itertools::Itertools;
 use tracing::info;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct State {
-    pos: IVec2,
-    visited: u16,
-}
+/// Shortest distances between the numbered cells, indexed by their numbers
+fn distances(file_content: &str) -> Vec<Vec<usize>> {
+    let grid = parse::ascii_grid(file_content.trim());
+    let digits = grid
+        .entries()
+        .filter(|(_, d)| d.is_ascii_digit())
+        .sorted_by_key(|(_, d)| **d)
+        .map(|(p, _)| p)
+        .collect_vec();
+    info!(?digits);
 
-impl State {
-    fn new(zero_pos: IVec2) -> Self {
-        Self {
-            pos: zero_pos,
-            visited: 0b1,
-        }
-    }
+    let maze = contract_maze(&grid, &digits, |from, to, _: IVec2| {
+        *from != b'#' && *to != b'#'
+    });
+    let lengths = maze.lengths();
+    (0..digits.len() as u32)
+        .map(|from| {
+            let distances = lengths.dijkstra(from);
+            distances[..digits.len()]
+                .iter()
+                .map(|d| d.expect("numbered cells are connected"))
+                .collect_vec()
+        })
+        .collect_vec()
 }
 
 #[tracing::instrument(skip(file_content))]
 pub fn part1(file_content: &str) -> usize {
-    let grid = parse::ascii_grid(file_content.trim());
-    let mut digits_coords = FxHashMap::default();
-    let mut zero_pos = IVec2::ZERO;
-    for (p, d) in grid.entries() {
-        let digit = match *d {
-            b'0'..=b'9' => *d - b'0',
-            _ => continue,
-        };
-        if digit == 0 {
-            zero_pos = p;
-        }
-        digits_coords.insert(p, digit);
-    }
-
-    let total_visited = digits_coords
-        .values()
-        .copied()
-        .fold(0, |acc, b| acc | (1 << (b as usize)));
-
-    let res = pathfinding::prelude::dijkstra(
-        &State::new(zero_pos),
-        |s| {
-            let s = *s;
-            grid.neighbours(s.pos, NonDiagonal)
-                .filter(|(_, n)| **n != b'#')
-                .map(move |(p, n)| {
-                    let n = *n;
-                    if n == b'.' {
-                        return (
-                            State {
-                                pos: p,
-                                visited: s.visited,
-                            },
-                            1,
-                        );
-                    }
-                    let digit = n - b'0';
-                    let new_visited = s.visited | (1 << digit);
-
-                    (
-                        State {
-                            pos: p,
-                            visited: new_visited,
-                        },
-                        1,
-                    )
-                })
-        },
-        |x| x.visited == total_visited,
-    )
-    .unwrap();
-
-    res.1
+    held_karp(&distances(file_content), Route::PathFrom(0), Objective::Min)
+        .unwrap()
+        .cost
 }
 #[tracing::instrument(skip(file_content))]
 pub fn part2(file_content: &str) -> usize {
-    let grid = parse::ascii_grid(file_content.trim());
-    let mut digits_coords = FxHashMap::default();
-    let mut zero_pos = IVec2::ZERO;
-    for (p, d) in grid.entries() {
-        let digit = match *d {
-            b'0'..=b'9' => *d - b'0',
-            _ => continue,
-        };
-        if digit == 0 {
-            zero_pos = p;
-        }
-        digits_coords.insert(p, digit);
-    }
-
-    info!("grid:\n{}", grid.render_ascii());
-    info!(?digits_coords, ?zero_pos);
-
-    let total_visited = digits_coords
-        .values()
-        .copied()
-        .fold(0, |acc, b| acc | (1 << (b as usize)));
-
-    let res = pathfinding::prelude::dijkstra(
-        &State::new(zero_pos),
-        |s| {
-            let s = *s;
-            grid.neighbours(s.pos, NonDiagonal)
-                .filter(|(_, n)| **n != b'#')
-                .map(move |(p, n)| {
-                    let n = *n;
-                    if n == b'.' {
-                        return (
-                            State {
-                                pos: p,
-                                visited: s.visited,
-                            },
-                            1,
-                        );
-                    }
-                    let digit = n - b'0';
-                    let new_visited = s.visited | (1 << digit);
-
-                    (
-                        State {
-                            pos: p,
-                            visited: new_visited,
-                        },
-                        1,
-                    )
-                })
-        },
-        |x| x.visited == total_visited && x.pos == zero_pos,
-    )
-    .unwrap();
-
-    res.1
+    held_karp(&distances(file_content), Route::Cycle, Objective::Min)
+        .unwrap()
+        .cost
 }
 
 #[cfg(test)]
@@ -1,25 +1,18 @@
-use advent_utils::{glam::IVec2, grid::N8};
+use advent_utils::{
+    coords::{manhattan, spiral_position},
+    glam::IVec2,
+    grid::N8,
+};
 use fxhash::FxHashMap;
 
 #[tracing::instrument(skip(file_content))]
 pub fn part1(file_content: &str) -> usize {
     let n = file_content.trim().parse::<usize>().unwrap();
-    positions().nth(n - 1).unwrap().abs().dot(IVec2::splat(1)) as usize
+    manhattan(spiral_position(n as u64 - 1), IVec2::ZERO) as usize
 }
 
 pub fn positions() -> impl Iterator<Item = IVec2> {
-    let dirs = [IVec2::X, IVec2::NEG_Y, IVec2::NEG_X, IVec2::Y];
-    let sizes = (1..).flat_map(|x| std::iter::repeat_n(x, 2));
-
-    dirs.into_iter()
-        .cycle()
-        .zip(sizes)
-        .flat_map(|(d, n)| std::iter::repeat_n(d, n))
-        .scan(IVec2::ZERO, |p, d| {
-            let res = *p;
-            *p += d;
-            Some(res)
-        })
+    (0..).map(spiral_position)
 }
 
 #[tracing::instrument(skip(file_content))]
@@ -1,35 +1,34 @@
-use std::collections::hash_map::Entry;
-
-use advent_utils::nom::{
-    self,
-    bytes::complete::tag,
-    character::complete::{alpha1, line_ending, multispace1},
-    combinator::all_consuming,
-    multi::separated_list1,
-    parse_usize,
-    sequence::{delimited, preceded},
-    Parser,
+use advent_utils::{
+    graph::{Graph, GraphBuilder},
+    nom::{
+        self,
+        bytes::complete::tag,
+        character::complete::{alpha1, line_ending, multispace1},
+        combinator::all_consuming,
+        multi::separated_list1,
+        parse_usize,
+        sequence::{delimited, preceded},
+        Parser,
+    },
 };
 use fxhash::FxHashMap;
 use itertools::Itertools;
-use petgraph::{graph::NodeIndex, prelude::DiGraph, visit::EdgeRef};
 
 #[tracing::instrument(skip(file_content))]
 pub fn part1(file_content: &str) -> String {
-    let (graph, _, _) = parse_graph(file_content);
+    let (graph, _) = parse_graph(file_content);
 
-    find_root(&graph).unwrap().to_string()
+    let root = find_root(&graph).unwrap();
+    graph.name(root).unwrap().to_string()
 }
 
 #[tracing::instrument(skip(file_content))]
 pub fn part2(file_content: &str) -> usize {
-    let (graph, weights, ids) = parse_graph(file_content);
+    let (graph, weights) = parse_graph(file_content);
 
     let root = find_root(&graph).unwrap();
 
-    let root_id = ids.get(root).copied().unwrap();
-
-    get_weight(&graph, &weights, root_id).unwrap_err()
+    get_weight(&graph, &weights, root).unwrap_err()
 }
 
 #[derive(Debug)]
@@ -38,20 +37,20 @@ struct Weight {
     total: usize,
 }
 fn get_weight(
-    graph: &DiGraph<&str, ()>,
+    graph: &Graph<'_>,
     weights: &FxHashMap<&str, usize>,
-    id: NodeIndex,
+    id: u32,
 ) -> Result<Weight, usize> {
     let mut children_weights: Vec<Weight> = Vec::with_capacity(10);
-    for c in graph.edges(id) {
-        let weight = get_weight(graph, weights, c.target())?;
+    for c in graph.neighbours(id) {
+        let weight = get_weight(graph, weights, *c)?;
         children_weights.push(weight);
     }
     let mut children_totals = children_weights.iter().map(|x| x.total).collect_vec();
 
     children_totals.sort_unstable();
 
-    let name = graph.node_weight(id).copied().unwrap();
+    let name = graph.name(id).unwrap();
     let self_weight = weights.get(name).copied().unwrap();
 
     let children_total = children_totals.iter().copied().sum();
@@ -72,43 +71,24 @@ fn get_weight(
     Err((wrong_weight.total - wrong_weight.children) + middle - actual)
 }
 
-fn find_root<'t>(graph: &DiGraph<&'t str, ()>) -> Option<&'t str> {
-    for id in graph.node_indices() {
-        if graph
-            .edges_directed(id, petgraph::Direction::Incoming)
-            .count()
-            == 0
-        {
-            return Some(graph.node_weight(id).unwrap());
-        }
-    }
-    None
+/// The only program nobody holds
+fn find_root(graph: &Graph<'_>) -> Option<u32> {
+    let parents = graph.reversed();
+    graph.nodes().find(|id| parents.degree(*id) == 0)
 }
-type GraphAndMaps<'t> = (
-    DiGraph<&'t str, ()>,
-    FxHashMap<&'t str, usize>,
-    FxHashMap<&'t str, NodeIndex>,
-);
 
-fn parse_graph(file_content: &str) -> GraphAndMaps<'_> {
+fn parse_graph(file_content: &str) -> (Graph<'_>, FxHashMap<&str, usize>) {
     let node_specs = parse_input(file_content).map(|x| x.1).unwrap();
-    let mut graph = DiGraph::<&str, ()>::new();
+    let mut builder = GraphBuilder::new();
     let mut weights = FxHashMap::default();
-    let mut ids = FxHashMap::default();
     for x in node_specs {
         weights.insert(x.name, x.weight);
-        for name in std::iter::once(x.name).chain(x.children.iter().copied()) {
-            if let Entry::Vacant(e) = ids.entry(name) {
-                e.insert(graph.add_node(name));
-            }
-        }
-        let id = ids.get(x.name).copied().unwrap();
+        builder.node(x.name);
         for c in x.children.iter().copied() {
-            let c_id = ids.get(c).copied().unwrap();
-            graph.add_edge(id, c_id, ());
+            builder.edge(x.name, c);
         }
     }
-    (graph, weights, ids)
+    (builder.directed(), weights)
 }
 
 #[derive(Debug, PartialEq, Eq, Hash)]
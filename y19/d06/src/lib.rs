@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use itertools::Itertools;
+use advent_utils::graph::Graph;
 use nom::{
     bytes::complete::tag,
     character::{self, complete::line_ending},
@@ -8,39 +8,21 @@ use nom::{
     sequence::separated_pair,
     IResult, Parser,
 };
-use petgraph::{
-    graph::{DiGraph, UnGraph},
-    visit::IntoNodeIdentifiers,
-};
 
 #[tracing::instrument(skip(file_content))]
 pub fn solve_part_1(file_content: &str) -> usize {
     let (_, pairs) = parse(file_content).unwrap();
-    let planets = pairs
-        .iter()
-        .flat_map(|(a, b)| [*a, *b].into_iter())
-        .unique()
-        .enumerate()
-        .map(|(i, x)| (x, i))
-        .collect::<HashMap<_, _>>();
-
-    let dir_graph: DiGraph<usize, (), usize> =
-        petgraph::graph::DiGraph::from_edges(pairs.into_iter().map(|(parent, child)| {
-            (
-                planets.get(child).copied().unwrap(),
-                planets.get(parent).copied().unwrap(),
-                (),
-            )
-        }));
+    let dir_graph =
+        Graph::directed_from_pairs(pairs.into_iter().map(|(parent, child)| (child, parent)));
 
     dir_graph
-        .node_identifiers()
+        .nodes()
         .map(|planet_idx| {
-            let mut parent = dir_graph.neighbors(planet_idx).next();
+            let mut parent = dir_graph.neighbours(planet_idx).first();
             let mut res = 0;
             while let Some(p_idx) = parent {
                 res += 1;
-                parent = dir_graph.neighbors(p_idx).next();
+                parent = dir_graph.neighbours(*p_idx).first();
             }
             res
         })
@@ -50,43 +32,20 @@ pub fn solve_part_1(file_content: &str) -> usize {
 #[tracing::instrument(skip(file_content))]
 pub fn solve_part_2(file_content: &str) -> usize {
     let (_, pairs) = parse(file_content).unwrap();
-    let planets_idx = pairs
-        .iter()
-        .flat_map(|(a, b)| [a, b].into_iter().copied())
-        .enumerate()
-        .map(|(i, p)| (p, i))
-        .collect::<HashMap<_, _>>();
     let parent_map = pairs
         .iter()
         .copied()
         .map(|(k, v)| (v, k))
         .collect::<HashMap<_, _>>();
 
-    let graph: UnGraph<usize, (), usize> =
-        petgraph::graph::UnGraph::from_edges(pairs.iter().copied().map(|(a, b)| {
-            (
-                planets_idx.get(a).copied().unwrap(),
-                planets_idx.get(b).copied().unwrap(),
-                (),
-            )
-        }));
+    let graph = Graph::undirected_from_pairs(pairs.iter().copied());
 
     let you_parent = parent_map.get("YOU").copied().unwrap();
     let san_parent = parent_map.get("SAN").copied().unwrap();
-    let you_parent_idx = planets_idx.get(you_parent).copied().unwrap();
-    let san_parent_idx = planets_idx.get(san_parent).copied().unwrap();
+    let you_parent_idx = graph.id(you_parent).unwrap();
+    let san_parent_idx = graph.id(san_parent).unwrap();
 
-    let distances = petgraph::algo::dijkstra::dijkstra(
-        &graph,
-        you_parent_idx.into(),
-        Some(san_parent_idx.into()),
-        |_| 1,
-    );
-
-    distances
-        .get(&san_parent_idx.into())
-        .copied()
-        .unwrap_or(usize::MAX)
+    graph.bfs(you_parent_idx)[san_parent_idx as usize].unwrap_or(usize::MAX)
 }
 
 fn parse(input: &str) -> IResult<&str, Vec<(&str, &str)>> {
@@ -1,3 +1,5 @@
+use advent_utils::ocr::OcrError;
+
 use self::{cpu::Cpu, crt::Crt, parse::parse_commands};
 
 pub mod command;
@@ -14,13 +16,20 @@ pub fn solve_part_1(file_content: &str) -> i32 {
         .sum()
 }
 
-pub fn solve_part_2(file_content: &str) -> String {
+pub fn render_screen(file_content: &str) -> String {
     Cpu::new(parse_commands(file_content))
         .scan(Crt::new(), |c, r| Some(c.draw(r)))
         .collect::<String>()
 }
+
+/// Letters drawn on the screen, unreadable glyphs are reported with their positions
+pub fn solve_part_2(file_content: &str) -> Result<String, OcrError> {
+    advent_utils::ocr::ocr_str(&render_screen(file_content))
+}
 #[cfg(test)]
 mod tests {
+    use advent_utils::glam::UVec2;
+
     use super::*;
 
     const INPUT: &str = "addx 15
@@ -197,7 +206,26 @@ addx -5"
 
     #[test]
     fn test_part_2() {
-        let res = solve_part_2(INPUT);
+        // The example draws stripes, not letters
+        assert_eq!(
+            solve_part_2(INPUT),
+            Err(OcrError::Unrecognized {
+                text: "?".to_string(),
+                positions: vec![UVec2::ZERO]
+            })
+        );
+    }
+
+    #[test]
+    fn test_part_2_actual() {
+        let str = include_str!("../input.txt");
+
+        assert_eq!(solve_part_2(str).as_deref(), Ok("PAPKFKEJ"));
+    }
+
+    #[test]
+    fn test_render_screen() {
+        let res = render_screen(INPUT);
         assert_eq!(
             res,
             "##..##..##..##..##..##..##..##..##..##..
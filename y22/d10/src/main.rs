@@ -2,7 +2,7 @@ use std::io::Read;
 use tracing_chrome::ChromeLayerBuilder;
 use tracing_subscriber::prelude::*;
 
-use y22d10::{solve_part_1, solve_part_2};
+use y22d10::{render_screen, solve_part_1, solve_part_2};
 
 fn main() {
     let (chrome_layer, _guard) = ChromeLayerBuilder::new().build();
@@ -21,7 +21,9 @@ fn main() {
     println!();
 
     instant = std::time::Instant::now();
-    let result = solve_part_2(&input);
-    println!("Part 2: {}", result);
+    match solve_part_2(&input) {
+        Ok(result) => println!("Part 2: {}", result),
+        Err(e) => println!("Part 2: {e}\n{}", render_screen(&input)),
+    }
     println!("Time: {:?}", instant.elapsed());
 }
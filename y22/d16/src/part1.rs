@@ -2,7 +2,7 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use super::{
     parse::{self, parse_id},
-    shortest::precalculate_shortest_paths,
+    shortest::{precalculate_shortest_paths, ShortestPaths},
     step::Step,
     valve::{self, Valve},
 };
@@ -87,7 +87,7 @@ pub fn solve_part_1(file_content: &str, minutes: usize) -> usize {
 
 fn get_possible_plans(
     valves_map: &BTreeMap<usize, Valve>,
-    shortest_paths: &BTreeMap<(usize, usize), Vec<usize>>,
+    shortest_paths: &ShortestPaths,
     state: &FullState,
 ) -> Vec<Vec<Step>> {
     if state.remaining_minutes == 0 {
@@ -98,17 +98,18 @@ fn get_possible_plans(
         .iter()
         .filter(|(n, v)| !state.open_valves.contains(n) && v.rate > 0)
         .map(|(k, _)| k)
-        .flat_map(|&goal| {
-            let dir = (state.valve, goal);
-            let shortest_path = shortest_paths.get(&dir);
-            shortest_path
+        .filter(|&&goal| {
+            shortest_paths
+                .distance(state.valve, goal)
+                .is_some_and(|distance| distance < state.remaining_minutes)
         })
-        .filter(|path| path.len() < state.remaining_minutes)
-        .map(|p| {
-            p.iter()
-                .map(|valve| Step::GoTo(*valve))
-                .chain(std::iter::once(Step::Open))
-                .collect()
+        .map(|&goal| {
+            std::iter::successors(shortest_paths.next_hop(state.valve, goal), |valve| {
+                shortest_paths.next_hop(*valve, goal)
+            })
+            .map(Step::GoTo)
+            .chain(std::iter::once(Step::Open))
+            .collect()
         })
         .collect()
 }
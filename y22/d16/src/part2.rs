@@ -2,7 +2,7 @@ use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
 
 use super::{
     parse::{self, parse_id},
-    shortest::precalculate_shortest_paths,
+    shortest::{precalculate_shortest_paths, ShortestPaths},
     valve::Valve,
 };
 
@@ -33,7 +33,7 @@ struct Node {
     open: BTreeSet<usize>,
 }
 
-type SP = BTreeMap<(usize, usize), Vec<usize>>;
+type SP = ShortestPaths;
 type VS = BTreeMap<usize, Valve>;
 
 impl Node {
@@ -77,11 +77,8 @@ impl Node {
                     self.open_valve(valves, goal_valve);
                     self.elephant_goal = None;
                 } else {
-                    match shortest_paths.get(&(self.elephant, goal_valve)) {
-                        Some(p) => {
-                            let first = *p.first().unwrap();
-                            self.elephant = first;
-                        }
+                    match shortest_paths.next_hop(self.elephant, goal_valve) {
+                        Some(first) => self.elephant = first,
                         None => unreachable!(),
                     }
                 }
@@ -103,11 +100,8 @@ impl Node {
                     self.open_valve(valves, goal_valve);
                     self.my_goal = None;
                 } else {
-                    match shortest_paths.get(&(self.me, goal_valve)) {
-                        Some(p) => {
-                            let first = *p.first().unwrap();
-                            self.me = first;
-                        }
+                    match shortest_paths.next_hop(self.me, goal_valve) {
+                        Some(first) => self.me = first,
                         None => unreachable!(),
                     }
                 }
@@ -162,7 +156,7 @@ impl Node {
     fn interesting_valves<'a>(
         &'a self,
         valves: &'a BTreeMap<usize, Valve>,
-        shortest_paths: &'a BTreeMap<(usize, usize), Vec<usize>>,
+        shortest_paths: &'a SP,
         place: usize,
     ) -> impl Iterator<Item = usize> + 'a {
         valves
@@ -170,9 +164,10 @@ impl Node {
             .filter(|(_, v)| v.rate > 0)
             .map(|(k, _)| *k)
             .filter(|&k| !self.is_open(k))
-            .filter(move |goal| match shortest_paths.get(&(place, *goal)) {
-                Some(p) => p.len() < self.remaining_minutes,
-                None => false,
+            .filter(move |goal| {
+                shortest_paths
+                    .distance(place, *goal)
+                    .is_some_and(|distance| distance < self.remaining_minutes)
             })
     }
 
@@ -1,87 +1,58 @@
-use std::{
-    cmp::Ordering,
-    collections::{BTreeMap, BTreeSet, BinaryHeap},
-};
+use std::collections::BTreeMap;
 
-use super::{parse::parse_id, valve::Valve};
-
-pub fn precalculate_shortest_paths(
-    valves_map: &BTreeMap<usize, Valve>,
-) -> BTreeMap<(usize, usize), Vec<usize>> {
-    let reachable = get_reachable_valves(valves_map, parse_id("AA").unwrap().1);
+use advent_utils::graph::{DistanceMatrix, GraphBuilder};
 
-    let mut memory: BTreeMap<(usize, usize), Option<Vec<usize>>> = BTreeMap::new();
-
-    for &from in valves_map.keys().filter(|k| reachable.contains(*k)) {
-        let mut tasks: BinaryHeap<ShortestPath> = BinaryHeap::new();
-        tasks.push(Vec::new().into());
-        while let Some(ShortestPath(path)) = tasks.pop() {
-            let current = *path.iter().last().unwrap_or(&from);
-            if !memory.contains_key(&(from, current)) && from != current {
-                memory.insert((from, current), Some(path.clone()));
-            }
-            for &neighbour in &valves_map.get(&current).unwrap().paths {
-                if path.contains(&neighbour) {
-                    continue;
-                }
-                if memory.contains_key(&(from, neighbour)) {
-                    continue;
-                }
-                let mut new_task = path.clone();
-                new_task.push(neighbour);
-                tasks.push(new_task.into());
-            }
-        }
-    }
+use super::{parse::parse_id, valve::Valve};
 
-    memory
-        .into_iter()
-        .flat_map(|(k, mp)| mp.map(|p| (k, p)))
-        .collect()
+/// Distances and next hops between valves reachable from `AA`
+pub struct ShortestPaths {
+    /// Sorted valve names, positions are indices into `distances`
+    names: Vec<usize>,
+    distances: DistanceMatrix<usize>,
 }
 
-#[derive(Debug)]
-struct ShortestPath(Vec<usize>);
-
-impl PartialEq for ShortestPath {
-    fn eq(&self, other: &Self) -> bool {
-        self.0.len() == other.0.len()
+impl ShortestPaths {
+    fn index(&self, name: usize) -> Option<u32> {
+        self.names.binary_search(&name).ok().map(|i| i as u32)
     }
-}
-impl Eq for ShortestPath {}
 
-impl PartialOrd for ShortestPath {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-impl Ord for ShortestPath {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let n = self.0.len();
-        let m = other.0.len();
-        match n.cmp(&m) {
-            Ordering::Less => Ordering::Greater,
-            Ordering::Greater => Ordering::Less,
-            x => x,
+    /// Minutes to walk between different valves, `None` for the same valve or
+    /// if `to` cannot be reached
+    pub fn distance(&self, from: usize, to: usize) -> Option<usize> {
+        if from == to {
+            return None;
         }
+        self.distances.get(self.index(from)?, self.index(to)?)
     }
-}
-impl From<Vec<usize>> for ShortestPath {
-    fn from(v: Vec<usize>) -> Self {
-        ShortestPath(v)
+
+    /// Valve to walk to first on the way from `from` to `to`
+    pub fn next_hop(&self, from: usize, to: usize) -> Option<usize> {
+        self.distance(from, to)?;
+        let hop = self
+            .distances
+            .next_hop(self.index(from)?, self.index(to)?)?;
+        Some(self.names[hop as usize])
     }
 }
 
-fn get_reachable_valves(valves_map: &BTreeMap<usize, Valve>, from: usize) -> BTreeSet<usize> {
-    let mut visited = BTreeSet::new();
-    let mut tasks = vec![from];
-    while let Some(valve) = tasks.pop() {
-        visited.insert(valve);
-        for neighbour in &valves_map.get(&valve).unwrap().paths {
-            if !visited.contains(neighbour) {
-                tasks.push(*neighbour)
-            }
+pub fn precalculate_shortest_paths(valves_map: &BTreeMap<usize, Valve>) -> ShortestPaths {
+    let names = valves_map.keys().copied().collect::<Vec<_>>();
+    let index = |name: usize| names.binary_search(&name).unwrap() as u32;
+
+    let mut builder = GraphBuilder::new();
+    for valve in valves_map.values() {
+        for &neighbour in &valve.paths {
+            builder.id_edge(index(valve.name), index(neighbour));
         }
     }
-    visited
+    let graph = builder.directed();
+    let reachable = graph.reachable(index(parse_id("AA").unwrap().1));
+    let interesting = graph
+        .nodes()
+        .filter(|n| reachable[*n as usize])
+        .collect::<Vec<_>>();
+    ShortestPaths {
+        names: interesting.iter().map(|n| names[*n as usize]).collect(),
+        distances: graph.all_pairs_bfs(true).restrict(&interesting),
+    }
 }
@@ -1,10 +1,15 @@
-use advent_utils::nom::{
-    self,
-    bytes::complete::tag,
-    character::complete::{multispace0, newline},
-    multi::separated_list1,
-    sequence::{delimited, separated_pair},
-    IResult, Parser,
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use advent_utils::{
+    nom::{
+        self,
+        bytes::complete::tag,
+        character::complete::{multispace0, newline},
+        multi::separated_list1,
+        sequence::{delimited, separated_pair},
+        IResult, Parser,
+    },
+    search::{branch_and_bound, BranchAndBound, BranchAndBoundOptions},
 };
 
 #[derive(Debug)]
@@ -127,20 +132,6 @@ impl State {
         self.remaining_minutes -= n;
     }
 
-    fn cmp_with_blueprint(&self, other: &Self) -> std::cmp::Ordering {
-        self.geode.cmp(&other.geode).then_with(|| {
-            let total_ore = self.ore + self.remaining_minutes * self.ore_robots;
-            let total_clay = self.clay + self.remaining_minutes * self.clay_robots;
-            let total_obsidian = self.obsidian + self.remaining_minutes * self.obsidian_robots;
-            let other_ore = other.ore + other.remaining_minutes * other.ore_robots;
-            let other_clay = other.clay + other.remaining_minutes * other.clay_robots;
-            let other_obsidian = other.obsidian + other.remaining_minutes * other.obsidian_robots;
-            total_obsidian
-                .cmp(&other_obsidian)
-                .then_with(|| total_clay.cmp(&other_clay))
-                .then_with(|| total_ore.cmp(&other_ore))
-        })
-    }
     fn minutes_until_ore_robot_available(&self, blueprint: &Blueprint) -> Option<usize> {
         steps_to_yt(self.ore_robots, self.ore, blueprint.ore_per_ore_robot)
             .filter(|x| *x < self.remaining_minutes)
@@ -216,15 +207,16 @@ impl State {
 
         let initial_len = states.len();
 
-        // Build ore robot
+        // Build ore robot, only one robot of a kind can be built per minute
+        // so there is no need for more robots than the most expensive recipe
         {
             let mut s = self.clone();
-            let ore = s.ore;
-            if ore < blueprint.ore_per_clay_robot
-                || ore < blueprint.ore_per_ore_robot
-                || ore < blueprint.ore_per_obsidian_robot
-                || ore < blueprint.ore_per_geode_robot
-            {
+            let max_ore = blueprint
+                .ore_per_clay_robot
+                .max(blueprint.ore_per_ore_robot)
+                .max(blueprint.ore_per_obsidian_robot)
+                .max(blueprint.ore_per_geode_robot);
+            if s.ore_robots < max_ore {
                 if let Some(time_to_ore) = s.minutes_until_ore_robot_available(blueprint) {
                     s.do_nothing_n_times(time_to_ore);
                     s.build_ore(blueprint);
@@ -235,8 +227,7 @@ impl State {
 
         {
             let mut s = self.clone();
-            let clay = s.clay;
-            if clay < blueprint.clay_per_obsidian_robot {
+            if s.clay_robots < blueprint.clay_per_obsidian_robot {
                 if let Some(time_to_clay) = s.minutes_until_clay_robot_available(blueprint) {
                     s.do_nothing_n_times(time_to_clay);
                     s.build_clay(blueprint);
@@ -247,8 +238,7 @@ impl State {
 
         {
             let mut s = self.clone();
-            let obsidian = s.obsidian;
-            if obsidian < blueprint.obsidian_per_geode_robot {
+            if s.obsidian_robots < blueprint.obsidian_per_geode_robot {
                 if let Some(time_to_obsidian) = s.minutes_until_obsidian_robot_available(blueprint)
                 {
                     s.do_nothing_n_times(time_to_obsidian);
@@ -276,33 +266,71 @@ impl State {
     }
 }
 
-fn get_best(elements: &mut Vec<State>) -> Option<State> {
-    let position = elements
-        .iter()
-        .enumerate()
-        .max_by(|(_, e), (_, e2)| e.cmp_with_blueprint(e2))
-        .map(|(i, _)| i)?;
-    let res = elements.swap_remove(position);
-    Some(res)
+/// Geodes opened by a blueprint, states jump straight to the next robot being built
+struct Factory<'b> {
+    blueprint: &'b Blueprint,
+    init: State,
 }
 
-fn get_max_geodes(blueprint: &Blueprint, init: State) -> usize {
-    let mut states = vec![init];
+impl BranchAndBound for Factory<'_> {
+    type Node = State;
+    type Value = usize;
 
-    let mut max_geodes = 0;
+    fn root(&self) -> State {
+        self.init.clone()
+    }
+
+    fn branches(&self, state: &State, out: &mut Vec<State>) {
+        state.clone().next_states(self.blueprint, out);
+    }
+
+    /// Geodes of the built robots are counted upfront
+    fn value(&self, state: &State) -> Option<usize> {
+        Some(state.geode)
+    }
 
-    let mut buffer = Vec::new();
-    while let Some(state) = get_best(&mut states) {
-        if state.remaining_minutes == 0 {
-            max_geodes = max_geodes.max(state.geode);
-            continue;
+    /// As if ore and clay were free and an obsidian robot was built every minute
+    fn bound(&self, state: &State) -> usize {
+        let mut geode = state.geode;
+        let mut obsidian = state.obsidian;
+        let minutes = (0..state.remaining_minutes).rev();
+        for (obsidian_robots, minute) in (state.obsidian_robots..).zip(minutes) {
+            if obsidian >= self.blueprint.obsidian_per_geode_robot {
+                obsidian -= self.blueprint.obsidian_per_geode_robot;
+                geode += minute;
+            }
+            obsidian += obsidian_robots;
         }
-        state.next_states(blueprint, &mut buffer);
-        buffer.retain(|s| s.remaining_minutes > 0 || s.geode > max_geodes);
-        states.extend_from_slice(&buffer);
-        buffer.clear();
+        geode
     }
-    max_geodes
+
+    fn dominance_key(&self, state: &State) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        (
+            state.remaining_minutes,
+            state.ore_robots,
+            state.clay_robots,
+            state.obsidian_robots,
+        )
+            .hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
+    fn dominates(&self, state: &State, other: &State) -> bool {
+        state.remaining_minutes == other.remaining_minutes
+            && state.ore_robots >= other.ore_robots
+            && state.clay_robots >= other.clay_robots
+            && state.obsidian_robots >= other.obsidian_robots
+            && state.ore >= other.ore
+            && state.clay >= other.clay
+            && state.obsidian >= other.obsidian
+            && state.geode >= other.geode
+    }
+}
+
+fn get_max_geodes(blueprint: &Blueprint, init: State) -> usize {
+    let factory = Factory { blueprint, init };
+    branch_and_bound(&factory, BranchAndBoundOptions::default()).map_or(0, |optimum| optimum.value)
 }
 
 pub fn solve_part_1(file_content: &str) -> usize {
@@ -489,16 +517,12 @@ mod tests {
         assert_eq!(format!("{}", solve_part_1(INPUT)), "33");
     }
 
-    // Solution for part2 does not solve part1
     #[test]
-    #[ignore]
     fn test_part_1_actual() {
         assert_eq!(format!("{}", solve_part_1(ACTUAL)), "1365");
     }
 
-    // My solution is not for all inputs
     #[test]
-    #[ignore]
     fn test_part_2() {
         assert_eq!(format!("{}", solve_part_2(INPUT)), "3472");
     }
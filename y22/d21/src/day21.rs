@@ -1,10 +1,9 @@
-use advent_utils::nom::{self, Parser};
+use advent_utils::{
+    graph::{Graph, GraphBuilder},
+    nom::{self, Parser},
+};
 use fxhash::FxHashMap;
 use itertools::Itertools;
-use petgraph::{
-    graph::DiGraph,
-    visit::{IntoNodeReferences, Walker},
-};
 
 #[derive(Copy, Clone, Debug)]
 enum Operation {
@@ -154,22 +153,22 @@ struct ValueCalculator<'i> {
 }
 
 impl<'i> ValueCalculator<'i> {
-    fn build(value: &'i str, nodes: &[Node<'i>], graph: &DiGraph<&'i str, ()>) -> Self {
-        let root_node = graph
-            .node_references()
-            .find(|(_, &n)| n == value)
-            .map(|(id, _)| id)
-            .unwrap();
-        let mut deps = petgraph::visit::Bfs::new(graph, root_node)
-            .iter(&graph)
-            .map(|n| graph.node_weight(n).copied().unwrap())
-            .map(|name| nodes.iter().find(|n| n.name == name).cloned().unwrap())
+    /// Node ids are positions in `nodes`
+    fn build(value: &'i str, nodes: &[Node<'i>], graph: &Graph<'i>) -> Self {
+        let root_node = graph.id(value).unwrap();
+        let reachable = graph.reachable(root_node);
+        // Dependencies before the monkeys waiting for them
+        let deps = graph
+            .topological_sort()
+            .unwrap()
+            .into_iter()
+            .rev()
+            .filter(|n| reachable[*n as usize])
+            .map(|n| nodes[n as usize].clone())
             .collect_vec();
 
-        deps.reverse();
-
         Self {
-            node: nodes.iter().find(|n| n.name == value).cloned().unwrap(),
+            node: nodes[root_node as usize].clone(),
             deps,
         }
     }
@@ -191,22 +190,19 @@ impl<'i> ValueCalculator<'i> {
     }
 }
 
-fn parse_graph_and_nodes(file_content: &str) -> (Vec<Node<'_>>, DiGraph<&'_ str, ()>) {
+fn parse_graph_and_nodes(file_content: &str) -> (Vec<Node<'_>>, Graph<'_>) {
     let nodes = parse_nodes(file_content).map(|x| x.1).unwrap();
-    let mut graph = DiGraph::new();
-    let graph_nodes =
-        fxhash::FxHashMap::from_iter(nodes.iter().map(|n| (n.name, graph.add_node(n.name))));
-
+    let mut builder = GraphBuilder::new();
+    for n in nodes.iter() {
+        builder.node(n.name);
+    }
     for n in nodes.iter() {
-        let n_node = graph_nodes.get(n.name).copied().unwrap();
         for d in n.value.dependencies() {
-            let d_node = graph_nodes.get(d).copied().unwrap();
-
-            graph.add_edge(n_node, d_node, ());
+            builder.edge(n.name, d);
         }
     }
 
-    (nodes, graph)
+    (nodes, builder.directed())
 }
 
 #[tracing::instrument(skip(file_content))]
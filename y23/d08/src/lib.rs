@@ -1,5 +1,6 @@
 use std::{fmt::Write, ops::Deref, str::FromStr};
 
+use advent_utils::math::modular::crt;
 use advent_utils::nom::{
     self,
     branch::alt,
@@ -9,6 +10,7 @@ use advent_utils::nom::{
     sequence::separated_pair,
     IResult, Parser,
 };
+use itertools::Itertools;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Direction {
@@ -131,6 +133,8 @@ pub fn solve_part_1(file_content: &str) -> usize {
 struct PositionInfo {
     loop_start_cycle: usize,
     loop_reset_cycle: usize,
+    /// Steps of the first loop that finish on a `..Z` position
+    finish_steps: Vec<usize>,
 }
 
 fn get_position_info(
@@ -156,28 +160,22 @@ fn get_position_info(
         cycle_finish_positions.push(pos);
     }
 
+    let mut pos = start;
+    let mut finish_steps = Vec::new();
+    for (step, &t) in (1..=loop_reset_cycle * turns.len()).zip(turns.iter().cycle()) {
+        pos.turn(t, network);
+        if step > loop_start_cycle * turns.len() && pos.0 % 26 == 25 {
+            finish_steps.push(step);
+        }
+    }
+
     PositionInfo {
         loop_reset_cycle,
         loop_start_cycle,
+        finish_steps,
     }
 }
 
-fn gcd(a: usize, b: usize) -> usize {
-    let mut a = a;
-    let mut b = b;
-    while a > 0 && b > 0 {
-        if a > b {
-            a %= b;
-        } else {
-            b %= a
-        }
-    }
-    a.max(b)
-}
-fn nok(a: usize, b: usize) -> usize {
-    a / gcd(a, b) * b
-}
-
 pub fn solve_part_2(file_content: &str) -> usize {
     let (turns, nodes) = parse_input(file_content);
     let turns_per_cycle = turns.len();
@@ -187,13 +185,35 @@ pub fn solve_part_2(file_content: &str) -> usize {
         network[node.0 .0] = (node.1, node.2);
     }
 
-    nodes
+    let infos = nodes
         .into_iter()
         .map(|(n, _, _)| n)
         .filter(|n| n.0 % 26 == 0)
         .map(|p| get_position_info(&turns, &network, p))
-        .map(|p| (p.loop_reset_cycle - p.loop_start_cycle) * turns_per_cycle)
-        .fold(1usize, nok)
+        .collect_vec();
+
+    // Every ghost finishes at its steps plus any number of loops, so each
+    // combination of finish steps is a system of congruences, solved from the
+    // first step where all ghosts are in their loops
+    let first = infos
+        .iter()
+        .map(|p| p.loop_start_cycle * turns_per_cycle + 1)
+        .max()
+        .unwrap_or(1) as i128;
+    infos
+        .iter()
+        .fold(vec![(0, 1)], |candidates, p| {
+            let period = ((p.loop_reset_cycle - p.loop_start_cycle) * turns_per_cycle) as i128;
+            candidates
+                .iter()
+                .cartesian_product(&p.finish_steps)
+                .filter_map(|(c, step)| crt([*c, (*step as i128, period)]))
+                .collect()
+        })
+        .into_iter()
+        .map(|(residue, modulus)| residue + (first - residue + modulus - 1) / modulus * modulus)
+        .min()
+        .expect("ghosts never finish together") as usize
 }
 #[cfg(test)]
 mod tests {
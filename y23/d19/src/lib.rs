@@ -1,5 +1,6 @@
 use std::{cmp, collections::BTreeMap, ops::Range, str::FromStr};
 
+use advent_utils::parse::sections;
 use nom::{error::ErrorKind, Parser};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -197,11 +198,13 @@ impl Workflow {
 }
 
 fn parse_input(input: &str) -> (BTreeMap<WorkflowId, Workflow>, Vec<[usize; 4]>) {
-    let (workflows_str, objects_str) = input.split_once("\n\n").unwrap();
+    let mut sections = sections(input);
+    let workflows_section = sections.expect_next().unwrap();
+    let objects_section = sections.expect_next().unwrap();
     let mut workflows = BTreeMap::new();
     let mut objects = Vec::new();
 
-    for line in workflows_str.lines() {
+    for (_, line) in workflows_section.lines() {
         let (name, rules) = line.split_once("{").unwrap();
         let rules_str = &rules[..rules.len() - 1];
         let mut rules = Vec::new();
@@ -233,7 +236,7 @@ fn parse_input(input: &str) -> (BTreeMap<WorkflowId, Workflow>, Vec<[usize; 4]>)
         workflows.insert(id, Workflow { rules });
     }
 
-    for line in objects_str.lines() {
+    for (_, line) in objects_section.lines() {
         let line = &line[1..line.len() - 1];
         let mut fields = [0; 4];
         for f_str in line.split(',') {
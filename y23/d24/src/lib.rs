@@ -1,4 +1,4 @@
-use advent_utils::math::{self, Rat, Vec2, Vec3};
+use advent_utils::math::{Matrix, Rat, Vec2, Vec3};
 use itertools::Itertools;
 use std::ops::RangeInclusive;
 
@@ -166,67 +166,69 @@ pub fn solve_part_2(file_content: &str) -> i64 {
     let p2y = p2.y;
     let p2z = p2.z;
 
-    let [a, b, c, ..] = math::solve_system(
+    let lefts = Matrix::from_rows([
         [
-            [
-                v0y - v1y,
-                -v0x + v1x,
-                Rat::ZERO,
-                -p0y + p1y,
-                p0x - p1x,
-                Rat::ZERO,
-            ],
-            [
-                v0z - v1z,
-                Rat::ZERO,
-                -v0x + v1x,
-                -p0z + p1z,
-                Rat::ZERO,
-                p0x - p1x,
-            ],
-            [
-                Rat::ZERO,
-                v0z - v1z,
-                -v0y + v1y,
-                Rat::ZERO,
-                -p0z + p1z,
-                p0y - p1y,
-            ],
-            [
-                v0y - v2y,
-                -v0x + v2x,
-                Rat::ZERO,
-                -p0y + p2y,
-                p0x - p2x,
-                Rat::ZERO,
-            ],
-            [
-                v0z - v2z,
-                Rat::ZERO,
-                -v0x + v2x,
-                -p0z + p2z,
-                Rat::ZERO,
-                p0x - p2x,
-            ],
-            [
-                Rat::ZERO,
-                v0z - v2z,
-                -v0y + v2y,
-                Rat::ZERO,
-                -p0z + p2z,
-                p0y - p2y,
-            ],
+            v0y - v1y,
+            -v0x + v1x,
+            Rat::ZERO,
+            -p0y + p1y,
+            p0x - p1x,
+            Rat::ZERO,
         ],
         [
-            p0x * v0y - p0y * v0x - p1x * v1y + p1y * v1x,
-            p0x * v0z - p0z * v0x - p1x * v1z + p1z * v1x,
-            p0y * v0z - p0z * v0y - p1y * v1z + p1z * v1y,
-            p0x * v0y - p0y * v0x - p2x * v2y + p2y * v2x,
-            p0x * v0z - p0z * v0x - p2x * v2z + p2z * v2x,
-            p0y * v0z - p0z * v0y - p2y * v2z + p2z * v2y,
+            v0z - v1z,
+            Rat::ZERO,
+            -v0x + v1x,
+            -p0z + p1z,
+            Rat::ZERO,
+            p0x - p1x,
         ],
-    )
-    .expect("expected to have a solution");
+        [
+            Rat::ZERO,
+            v0z - v1z,
+            -v0y + v1y,
+            Rat::ZERO,
+            -p0z + p1z,
+            p0y - p1y,
+        ],
+        [
+            v0y - v2y,
+            -v0x + v2x,
+            Rat::ZERO,
+            -p0y + p2y,
+            p0x - p2x,
+            Rat::ZERO,
+        ],
+        [
+            v0z - v2z,
+            Rat::ZERO,
+            -v0x + v2x,
+            -p0z + p2z,
+            Rat::ZERO,
+            p0x - p2x,
+        ],
+        [
+            Rat::ZERO,
+            v0z - v2z,
+            -v0y + v2y,
+            Rat::ZERO,
+            -p0z + p2z,
+            p0y - p2y,
+        ],
+    ]);
+    let rights = [
+        p0x * v0y - p0y * v0x - p1x * v1y + p1y * v1x,
+        p0x * v0z - p0z * v0x - p1x * v1z + p1z * v1x,
+        p0y * v0z - p0z * v0y - p1y * v1z + p1z * v1y,
+        p0x * v0y - p0y * v0x - p2x * v2y + p2y * v2x,
+        p0x * v0z - p0z * v0x - p2x * v2z + p2z * v2x,
+        p0y * v0z - p0z * v0y - p2y * v2z + p2z * v2y,
+    ];
+    let solutions = lefts.solve(&rights).expect("expected to have a solution");
+    assert!(solutions.is_unique(), "the rock should be determined");
+    let [a, b, c, ..] = solutions.particular[..] else {
+        unreachable!("there are 6 variables");
+    };
 
     (a + b + c)
         .top
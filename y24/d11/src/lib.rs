@@ -1,6 +1,4 @@
-use core::str;
-use itertools::Itertools;
-use std::collections::HashMap;
+use advent_utils::memo::Memo;
 
 #[tracing::instrument(skip(file_content))]
 pub fn solve_part_1(file_content: &str) -> usize {
@@ -14,40 +12,27 @@ pub fn solve_part_2(file_content: &str) -> usize {
 
 #[tracing::instrument(skip(file_content))]
 pub fn solve(file_content: &str, blinks: usize) -> usize {
-    let mut nums = file_content
+    let mut memo = Memo::new();
+    file_content
         .trim()
         .split_ascii_whitespace()
-        .map(|x| x.parse::<usize>().unwrap())
-        .counts();
-    let mut buf = HashMap::new();
-
-    for _ in 0..(blinks / 2) {
-        blink(&nums, &mut buf);
-        blink(&buf, &mut nums);
-    }
-
-    if blinks % 2 == 1 {
-        blink(&nums, &mut buf);
-        buf.values().sum()
-    } else {
-        nums.values().sum()
-    }
+        .map(|x| stones(x.parse::<usize>().unwrap(), blinks, &mut memo))
+        .sum()
 }
 
-fn blink(src: &HashMap<usize, usize>, dst: &mut HashMap<usize, usize>) {
-    dst.clear();
-    for (stone, n) in src {
-        let stone = *stone;
-        if stone == 0 {
-            *dst.entry(1).or_default() += n;
-        } else if let Some(pair) = split_even(stone) {
-            for x in pair {
-                *dst.entry(x).or_default() += n;
-            }
+/// Number of stones the stone turns into after blinking
+fn stones(stone: usize, blinks: usize, memo: &mut Memo<(usize, usize), usize>) -> usize {
+    memo.call((stone, blinks), &|rec, (stone, blinks)| {
+        if blinks == 0 {
+            1
+        } else if stone == 0 {
+            rec.call((1, blinks - 1))
+        } else if let Some([a, b]) = split_even(stone) {
+            rec.call((a, blinks - 1)) + rec.call((b, blinks - 1))
         } else {
-            *dst.entry(stone * 2024).or_default() += n;
+            rec.call((stone * 2024, blinks - 1))
         }
-    }
+    })
 }
 
 fn split_even(n: usize) -> Option<[usize; 2]> {
@@ -54,10 +54,11 @@ impl Machine {
         let U64Vec2 { x: bx, y: by } = self.button_b;
         let U64Vec2 { x: tx, y: ty } = self.prize;
 
-        math::solve_system(
+        math::solve_system::<math::Rat, 2, 2>(
             [[ax.into(), bx.into()], [ay.into(), by.into()]],
             [tx.into(), ty.into()],
         )
+        .ok()
         .and_then(|res| {
             res.into_iter()
                 .filter_map(|x| usize::try_from(x).ok())
@@ -1,94 +1,39 @@
-use std::collections::{HashMap, HashSet};
-
+use advent_utils::memo::Memo;
 use itertools::Itertools;
 
 #[tracing::instrument(skip(file_content))]
 pub fn solve_part_1(file_content: &str) -> usize {
     let (towels_str, patterns_str) = file_content.split_once("\n\n").unwrap();
     let towels = towels_str.split(", ").collect_vec();
-    let mut possible = HashSet::new();
-    let mut impossible = HashSet::new();
-    let mut total = 0;
-    for pattern in patterns_str.lines() {
-        if is_possible(&towels, pattern, &mut possible, &mut impossible) {
-            total += 1;
-        }
-    }
-
-    total
+    let mut memo = Memo::new();
+    patterns_str
+        .lines()
+        .filter(|pattern| ways(&towels, pattern, &mut memo) > 0)
+        .count()
 }
 #[tracing::instrument(skip(file_content))]
 pub fn solve_part_2(file_content: &str) -> usize {
     let (towels_str, patterns_str) = file_content.split_once("\n\n").unwrap();
     let towels = towels_str.split(", ").collect_vec();
-    let mut possible = HashMap::new();
-    let mut impossible = HashSet::new();
-    let mut total = 0;
-    for pattern in patterns_str.lines() {
-        total += ways(&towels, pattern, &mut possible, &mut impossible)
-    }
-
-    total
+    let mut memo = Memo::new();
+    patterns_str
+        .lines()
+        .map(|pattern| ways(&towels, pattern, &mut memo))
+        .sum()
 }
 
-#[tracing::instrument(skip(towels, possible, impossible), ret)]
-fn is_possible<'t>(
-    towels: &[&'t str],
-    pattern: &'t str,
-    possible: &mut HashSet<&'t str>,
-    impossible: &mut HashSet<&'t str>,
-) -> bool {
-    if pattern.is_empty() {
-        return true;
-    }
-    if possible.contains(pattern) {
-        return true;
-    }
-    if impossible.contains(pattern) {
-        return false;
-    }
-    for prefix in towels {
-        if let Some(rest) = pattern.strip_prefix(prefix) {
-            tracing::info!(?prefix);
-            let is_possible = is_possible(towels, rest, possible, impossible);
-            if is_possible {
-                possible.insert(pattern);
-                return true;
-            }
+/// Number of ways to arrange the towels into the pattern
+fn ways<'t>(towels: &[&str], pattern: &'t str, memo: &mut Memo<&'t str, usize>) -> usize {
+    memo.call(pattern, &|rec, pattern| {
+        if pattern.is_empty() {
+            return 1;
         }
-    }
-    impossible.insert(pattern);
-    false
-}
-#[tracing::instrument(skip(towels, possible, impossible), ret)]
-fn ways<'t>(
-    towels: &[&'t str],
-    pattern: &'t str,
-    possible: &mut HashMap<&'t str, usize>,
-    impossible: &mut HashSet<&'t str>,
-) -> usize {
-    if pattern.is_empty() {
-        return 1;
-    }
-    if let Some(x) = possible.get(pattern) {
-        return *x;
-    }
-    if impossible.contains(pattern) {
-        return 0;
-    }
-    let mut total_ways = 0;
-    for prefix in towels {
-        if let Some(rest) = pattern.strip_prefix(prefix) {
-            tracing::info!(?prefix);
-            total_ways += ways(towels, rest, possible, impossible);
-        }
-    }
-    if total_ways > 0 {
-        possible.insert(pattern, total_ways);
-    } else {
-        impossible.insert(pattern);
-    }
-    total_ways
+        towels
+            .iter()
+            .filter_map(|prefix| pattern.strip_prefix(prefix))
+            .map(|rest| rec.call(rest))
+            .sum()
+    })
 }
 
 #[cfg(test)]
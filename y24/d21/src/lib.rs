@@ -1,8 +1,8 @@
-use advent_utils::{glam::IVec2, parse};
+use advent_utils::{glam::IVec2, memo::Memo, parse};
 use itertools::{Either, Itertools};
 
 pub fn solve<const ROBOTS: usize>(file_content: &str) -> usize {
-    let mut cache = Cache::default();
+    let mut cache = Cache::with_store(Default::default());
     file_content
         .lines()
         .map(|line| {
@@ -265,7 +265,7 @@ fn move_from_to(a: IVec2, b: IVec2) -> RobotTask {
     }
 }
 
-type Cache = fxhash::FxHashMap<(usize, usize), usize>;
+type Cache = Memo<(usize, usize), usize, fxhash::FxHashMap<(usize, usize), usize>>;
 
 fn min_steps_for_code<const ROBOTS: usize>(code: &str, cache: &mut Cache) -> usize {
     get_possible_trajectories(code)
@@ -309,15 +309,9 @@ fn min_steps_to_execute_controls(
     if intermediate_robots == 0 {
         return total_steps(tasks);
     }
-    let key = tasks_key(tasks);
-    if let Some(x) = cache.get(&(key, intermediate_robots)) {
-        return *x;
-    }
-    let min_steps = calculate_min_steps(tasks, intermediate_robots, cache);
-
-    cache.insert((key, intermediate_robots), min_steps);
-
-    min_steps
+    cache.get_or_insert_with((tasks_key(tasks), intermediate_robots), |cache| {
+        calculate_min_steps(tasks, intermediate_robots, cache)
+    })
 }
 
 /// Calculates min steps without using cache directly
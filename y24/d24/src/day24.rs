@@ -1,18 +1,20 @@
 use std::{fmt::Write, fs};
 
-use advent_utils::nom::{
-    self, Parser,
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{alphanumeric1, line_ending},
-    combinator::{all_consuming, value},
-    multi::separated_list1,
-    parse_usize,
-    sequence::{preceded, separated_pair},
+use advent_utils::{
+    graph::{Graph, GraphBuilder},
+    nom::{
+        self, Parser,
+        branch::alt,
+        bytes::complete::tag,
+        character::complete::{alphanumeric1, line_ending},
+        combinator::{all_consuming, value},
+        multi::separated_list1,
+        parse_usize,
+        sequence::{preceded, separated_pair},
+    },
 };
 use fxhash::{FxHashMap, FxHashSet};
 use itertools::Itertools;
-use petgraph::dot::Dot;
 
 struct Schema<'t> {
     rules: FxHashMap<&'t str, Rule<'t>>,
@@ -136,74 +138,26 @@ pub fn part2(file_content: &str) -> String {
     }
     validate_z(&schema, &zs, &initials, expected).expect("valid");
 
-    let mut graph = petgraph::prelude::DiGraph::<&str, Operation>::new();
-    let xs_nodes = xs.iter().copied().map(|x| graph.add_node(x)).collect_vec();
-    let ys_nodes = ys.iter().copied().map(|x| graph.add_node(x)).collect_vec();
+    let mut builder = GraphBuilder::new();
+    for x in xs.iter().chain(&ys) {
+        builder.node(x);
+    }
     let keys = schema.rules.keys().copied().collect_vec();
     // schema.get_all_dependents(xs[0..13].iter().copied().chain(ys[0..13].iter().copied()));
 
-    let nodes = keys
-        .iter()
-        .copied()
-        .map(|x| graph.add_node(x))
-        .collect_vec();
-    for i in 0..keys.len() {
-        let key = keys[i];
-        let node = nodes[i];
+    for key in keys.iter().copied() {
+        builder.node(key);
+    }
+    for key in keys.iter().copied() {
         let rule = schema.rules.get(key).unwrap();
-        match &[&keys, &xs, &ys].map(|x| {
-            x.iter()
-                .copied()
-                .find_position(|y| rule.a == *y)
-                .map(|x| x.0)
-        }) {
-            [None, None, None] => {
-                // panic!("failed to find the source of {}", keys[i]);
-            }
-            [Some(i), None, None] => {
-                let a_node = nodes[*i];
-                graph.add_edge(a_node, node, rule.op);
-            }
-            [None, None, Some(i)] => {
-                let a_node = ys_nodes[*i];
-                graph.add_edge(a_node, node, rule.op);
-            }
-            [None, Some(i), None] => {
-                let a_node = xs_nodes[*i];
-                graph.add_edge(a_node, node, rule.op);
-            }
-            x => {
-                panic!("{x:?}");
-            }
-        };
-        match &[&keys, &xs, &ys].map(|x| {
-            x.iter()
-                .copied()
-                .find_position(|y| rule.b == *y)
-                .map(|x| x.0)
-        }) {
-            [None, None, None] => {
-                // panic!("failed to find the source of {}", keys[i]);
-            }
-            [Some(i), None, None] => {
-                let b_node = nodes[*i];
-                graph.add_edge(b_node, node, rule.op);
-            }
-            [None, None, Some(i)] => {
-                let b_node = ys_nodes[*i];
-                graph.add_edge(b_node, node, rule.op);
+        for source in [rule.a, rule.b] {
+            if schema.rules.contains_key(source) || xs.contains(&source) || ys.contains(&source) {
+                builder.weighted_edge(source, key, rule.op);
             }
-            [None, Some(i), None] => {
-                let b_node = xs_nodes[*i];
-                graph.add_edge(b_node, node, rule.op);
-            }
-            x => {
-                panic!("{x:?}");
-            }
-        };
+        }
     }
 
-    fs::write("./adder.dot", Dot::with_config(&graph, &[]).to_string()).unwrap();
+    fs::write("./adder.dot", to_dot(&builder.directed())).unwrap();
 
     swaps
         .into_iter()
@@ -266,6 +220,22 @@ fn validate_z<'t>(
     }
 }
 
+/// Graphviz rendering of the wires with operations as edge labels
+fn to_dot(graph: &Graph<'_, Operation>) -> String {
+    let mut dot = String::from("digraph {\n");
+    for node in graph.nodes() {
+        let name = graph.name(node).unwrap_or_default();
+        writeln!(dot, "    {node} [ label = \"{name}\" ]").unwrap();
+    }
+    for node in graph.nodes() {
+        for (to, op) in graph.edges(node) {
+            writeln!(dot, "    {node} -> {to} [ label = \"{op}\" ]").unwrap();
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 enum Operation {
     And,
@@ -1,16 +1,11 @@
 use itertools::Itertools;
 
-use crate::{model::Machine, parse::parse_machines};
+use crate::parse::parse_machines;
 
 #[tracing::instrument(skip(input))]
 pub fn part1(input: &str) -> usize {
-    let machines = parse_machines(input).collect_vec();
-
-    let mut dp = Machine::prepare_fewest_button_clicks_to_target();
-
-    machines
-        .into_iter()
-        .map(|m| m.get_fewest_button_clicks_to_target(&mut dp))
+    parse_machines(input)
+        .map(|m| m.get_fewest_button_clicks_to_target())
         .sum()
 }
 #[tracing::instrument(skip(input))]
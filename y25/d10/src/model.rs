@@ -1,7 +1,8 @@
-use std::collections::VecDeque;
-
+use advent_utils::math::{
+    gf2::Gf2System,
+    lp::{Problem, Relation, VariableDef},
+};
 use derive_more::{Constructor, From};
-use good_lp::{Expression, Solution, SolverModel, VariableDefinition, highs};
 use itertools::Itertools;
 
 #[derive(Copy, Clone, From, Eq, PartialEq, Constructor)]
@@ -13,12 +14,6 @@ impl Indicators {
     fn len(self) -> usize {
         self.size
     }
-    fn states(self) -> usize {
-        1 << self.size
-    }
-    fn empty(size: usize) -> Self {
-        Self { bitmask: 0, size }
-    }
 }
 
 impl std::fmt::Debug for Indicators {
@@ -87,88 +82,56 @@ pub struct Machine {
 }
 
 impl Machine {
-    pub fn prepare_fewest_button_clicks_to_target() -> FewestButtonClicksToIndicatorsState {
-        FewestButtonClicksToIndicatorsState {
-            visited: Vec::with_capacity(2048),
-            to_visit: VecDeque::with_capacity(2048),
+    /// Pressing a button twice cancels out, so the presses are a solution
+    /// of the XOR system with an equation per indicator
+    pub fn get_fewest_button_clicks_to_target(&self) -> usize {
+        let mut system = Gf2System::new(self.buttons.len());
+        for light in 0..self.target.len() {
+            let coefficients = self
+                .buttons
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| b.0 & (1 << light) != 0)
+                .fold(0u128, |acc, (i, _)| acc | (1 << i));
+            system.add_equation(coefficients, self.target.bitmask & (1 << light) != 0);
         }
-    }
-
-    pub fn get_fewest_button_clicks_to_target(
-        &self,
-        dp: &mut FewestButtonClicksToIndicatorsState,
-    ) -> usize {
-        let visited = &mut dp.visited;
-        let to_visit = &mut dp.to_visit;
-        visited.clear();
-        visited.resize(self.target.states(), false);
-
-        to_visit.clear();
-        to_visit.push_back((Indicators::empty(self.target.size), 0));
-
-        while let Some((state, steps)) = to_visit.pop_front() {
-            let ind = state.bitmask as usize;
-            if visited[ind] {
-                continue;
-            }
-            visited[ind] = true;
-            if state == self.target {
-                return steps;
-            }
-
-            for b in &self.buttons {
-                let new_state = Indicators::new(b.0 ^ state.bitmask, state.len());
-                let new_ind = new_state.bitmask as usize;
-                if !visited[new_ind] {
-                    to_visit.push_back((new_state, steps + 1));
-                }
-            }
-        }
-
-        usize::MAX
+        system.solve().map_or(usize::MAX, |solutions| {
+            solutions.min_weight().count_ones() as usize
+        })
     }
 
     pub fn get_fewest_button_clicks_to_joltage(&self) -> usize {
-        let mut vars = good_lp::ProblemVariables::new();
-
-        let button_clicks_vars: Vec<_> =
-            vars.add_all(self.buttons.iter().enumerate().map(|(i, b)| {
-                VariableDefinition::default()
-                    .name(format!("button[{i}]"))
-                    .integer()
-                    .min(0)
-                    .max(
+        let mut problem = Problem::new();
+
+        let button_clicks_vars = self
+            .buttons
+            .iter()
+            .map(|b| {
+                problem.add_variable(
+                    VariableDef::default().integer().max(
                         b.iter()
                             .map(|i| self.target_joltage[i])
                             .min()
                             .unwrap_or_default(),
-                    )
-            }));
-
-        let mut clicks_sum = Expression::with_capacity(button_clicks_vars.len());
-        for v in &button_clicks_vars {
-            clicks_sum.add_mul(1., v);
-        }
+                    ),
+                )
+            })
+            .collect_vec();
 
-        let mut problem = vars.minimise(clicks_sum.clone()).using(highs);
+        problem.minimize(button_clicks_vars.iter().copied().collect());
 
         // Ensuring that all clicks are creating target_joltage at the end
         for place in 0..self.target_joltage.len() {
-            let buttons_for_place = std::iter::zip(button_clicks_vars.iter(), &self.buttons)
+            let expr = std::iter::zip(button_clicks_vars.iter(), &self.buttons)
                 .filter(|(_, b)| b.iter().contains(&place))
-                .collect_vec();
-            let mut expr = Expression::with_capacity(buttons_for_place.len());
-            for (v, _) in buttons_for_place {
-                expr.add_mul(1., *v);
-            }
-            problem = problem.with(expr.eq(self.target_joltage[place]));
+                .map(|(v, _)| *v)
+                .collect();
+            problem.add_constraint(expr, Relation::Eq, self.target_joltage[place]);
         }
 
         let solution = problem.solve().unwrap();
 
-        let clicks = solution.eval(clicks_sum);
-
-        clicks.round() as usize
+        solution.objective().floor() as usize
     }
 }
 
@@ -190,8 +153,3 @@ impl std::fmt::Debug for Machine {
         Ok(())
     }
 }
-
-pub struct FewestButtonClicksToIndicatorsState {
-    visited: Vec<bool>,
-    to_visit: VecDeque<(Indicators, usize)>,
-}